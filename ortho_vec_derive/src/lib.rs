@@ -1,4 +1,6 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(test), no_std)]
+#![cfg_attr(all(test, feature = "nightly"), feature(allocator_api))]
 #![warn(
     clippy::all,
     clippy::missing_const_for_fn,
@@ -9,13 +11,18 @@
     clippy::needless_pass_by_value
 )]
 
+// The generated code names the traits it implements through this crate, this lets it do so inside
+// the crate itself too
+extern crate self as ortho_vec_derive;
+
 pub mod prelude;
 
+pub use ortho_vec_derive_impl::*;
 pub use ortho_vec_derive_macro::*;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
-    use ortho_vec_derive_macro::OrthoVec;
+    use crate::prelude::*;
 
     #[derive(OrthoVec)]
     struct WeirdStruct<'a, T: Send>
@@ -27,6 +34,215 @@ mod tests {
         c: T,
     }
 
+    #[derive(OrthoVec)]
+    struct TupleStruct(i32, f32, &'static str);
+
+    #[derive(OrthoVec)]
+    enum Event<'a> {
+        Move { x: f32, y: f32 },
+        Hit(u32, &'a str),
+        Quit,
+    }
+
+    #[derive(OrthoVec, Clone)]
+    struct UnitStruct;
+
+    #[derive(OrthoVec)]
+    struct Tagged<T> {
+        _m: std::marker::PhantomData<T>,
+        _n: ::core::marker::PhantomData<T>,
+        id: u32,
+    }
+
+    mod units {
+        #[derive(Debug, PartialEq, Eq)]
+        pub struct PhantomData(pub u8);
+    }
+
+    #[derive(OrthoVec)]
+    struct Measure {
+        unit: units::PhantomData,
+        value: i32,
+    }
+
+    #[derive(OrthoVec)]
+    struct Ring<const N: usize, T = f32> {
+        data: [T; N],
+        head: usize,
+    }
+
+    #[derive(OrthoVec)]
+    struct Borrowed<'a, 'b: 'a, T: 'b + std::fmt::Debug> {
+        short: &'a T,
+        long: &'b str,
+    }
+
+    #[derive(OrthoVec)]
+    enum Samples<const N: usize, T: Copy = i16> {
+        Mono([T; N]),
+        Stereo { left: [T; N], right: [T; N] },
+        Silent,
+    }
+
+    // `Next` keeps `U` for the `Item = U` bound of `S`, `Value` drops `U` along with `From<U>`
+    #[derive(OrthoVec)]
+    enum Meter<T, U, S>
+    where
+        S: Iterator<Item = U>,
+        T: From<U> + Copy,
+    {
+        Next(S::Item),
+        Value(T),
+        Raw(U),
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(name = "Particles", view = "ParticleRef", view_mut = "ParticleMut")]
+    #[ortho(iter = "ParticlesIter", iter_mut = "ParticlesIterMut")]
+    #[ortho(into_iter = "ParticlesIntoIter")]
+    struct Particle {
+        pos: f32,
+        vel: f32,
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(
+        name = "Commands",
+        view = "CommandRef",
+        view_mut = "CommandMut",
+        tag = "CommandKind"
+    )]
+    enum Command {
+        Wait(u32),
+        Stop,
+    }
+
+    #[derive(OrthoVec)]
+    struct Entity<T> {
+        pos: f32,
+        #[ortho(group = "cold")]
+        name: String,
+        vel: f32,
+        #[ortho(group = "cold")]
+        extra: T,
+    }
+
+    #[derive(OrthoVec)]
+    struct Sample(
+        u8,
+        #[ortho(group = "rest")] String,
+        #[ortho(group = "rest")] u16,
+    );
+
+    #[derive(OrthoVec)]
+    struct Transform {
+        pos: [f32; 3],
+        rot: f32,
+    }
+
+    #[derive(OrthoVec)]
+    struct Body<T> {
+        id: u32,
+        #[ortho(flatten)]
+        transform: Transform,
+        #[ortho(flatten)]
+        state: Event<'static>,
+        #[ortho(flatten)]
+        mass: Wrapped<T>,
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(derive(Debug, Clone, PartialEq), view_mut_derive(Debug))]
+    #[ortho(view_derive(Debug, Clone, Copy, PartialEq))]
+    struct Wrapped<T>(T);
+
+    #[derive(OrthoVec)]
+    #[ortho(derive(Debug, Clone, PartialEq), attr(must_use))]
+    #[ortho(view_derive(Debug, Clone, Copy, PartialEq), view_mut_derive(Debug))]
+    struct Stat {
+        value: i32,
+        #[ortho(group = "meta")]
+        label: &'static str,
+        #[ortho(flatten)]
+        scale: Wrapped<f32>,
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(derive(Debug, Clone, PartialEq), view_derive(Debug))]
+    enum Reading {
+        Value(i32),
+        Missing,
+    }
+
+    // `any()` is never true, so the disabled fields don't even have to name a type
+    #[derive(OrthoVec)]
+    struct Gated {
+        id: u32,
+        #[cfg(any())]
+        debug_name: NoSuchType,
+        #[cfg(test)]
+        weight: f32,
+        #[cfg_attr(any(), ortho(group = "no_such_group"))]
+        #[cfg_attr(test, doc = "Always documented")]
+        flags: u8,
+    }
+
+    #[derive(OrthoVec)]
+    enum GatedEvent {
+        Spawn {
+            id: u32,
+            #[cfg(any())]
+            debug_name: NoSuchType,
+        },
+        Despawn(#[cfg(any())] NoSuchType, u32),
+    }
+
+    #[derive(OrthoVec)]
+    enum Shape {
+        Circle {
+            radius: f32,
+            #[ortho(group = "meta")]
+            label: String,
+        },
+        Empty,
+    }
+
+    mod nested {
+        mod deeper {
+            use ortho_vec_derive_macro::OrthoVec;
+
+            #[derive(OrthoVec)]
+            struct Hidden {
+                value: u8,
+            }
+
+            pub fn sum_hidden() -> u32 {
+                let v_hd = OrthoVecHidden::from(vec![Hidden { value: 1 }, Hidden { value: 2 }]);
+                v_hd.iter().map(|hd| u32::from(*hd.value)).sum()
+            }
+        }
+
+        pub use deeper::sum_hidden;
+    }
+
+    mod api {
+        use ortho_vec_derive_macro::OrthoVec;
+
+        #[derive(OrthoVec)]
+        pub struct Particle {
+            pub pos: f32,
+            pub vel: f32,
+        }
+
+        #[derive(OrthoVec)]
+        #[ortho(vis = "pub(super)")]
+        pub struct Charge(pub i8);
+
+        #[derive(OrthoVec)]
+        #[ortho(vis = "pub(in super)")]
+        pub(super) struct Spin(pub(super) i8);
+    }
+
     struct TestContext<'a> {
         v_ws: OrthoVecWeirdStruct<'a, &'a str>,
     }
@@ -120,4 +336,2008 @@ mod tests {
 
         assert_eq!(ctx.v_ws.remove(0).c, "Going to the start");
     }
+
+    #[test]
+    fn test_tuple_struct() {
+        let mut v_ts = vec![TupleStruct(1, 1.5, "one"), TupleStruct(2, 2.5, "two")].into_ortho();
+
+        v_ts.push(TupleStruct(3, 3.5, "three"));
+        v_ts.insert(0, TupleStruct(0, 0.5, "zero"));
+        assert_eq!(v_ts.len(), 4);
+
+        for ts in v_ts.iter_mut() {
+            *ts.0 *= 10;
+        }
+
+        for (index, ts) in v_ts.iter().enumerate() {
+            assert_eq!(*ts.0, index as i32 * 10);
+            assert!((*ts.1 - (index as f32 + 0.5)).abs() < f32::EPSILON);
+        }
+
+        assert_eq!(v_ts.remove(1).2, "one");
+        assert_eq!(v_ts.pop().unwrap().2, "three");
+
+        let rest: Vec<TupleStruct> = v_ts.into_iter().collect();
+        assert_eq!(rest.len(), 2);
+        assert_eq!(rest[0].0, 0);
+        assert_eq!(rest[1].2, "two");
+    }
+
+    #[test]
+    fn test_enum() {
+        let mut v_ev = vec![
+            Event::Move { x: 1.0, y: 2.0 },
+            Event::Hit(10, "first"),
+            Event::Move { x: 3.0, y: 4.0 },
+        ]
+        .into_ortho();
+
+        v_ev.push(Event::Quit);
+        v_ev.insert(1, Event::Hit(5, "inserted"));
+        v_ev.insert(0, Event::Move { x: -1.0, y: -2.0 });
+        assert_eq!(v_ev.len(), 6);
+        assert_eq!(v_ev.move_variant().len(), 3);
+        assert_eq!(v_ev.hit_variant().len(), 2);
+
+        for x in v_ev.move_variant_iter_mut().map(|m| m.x) {
+            *x *= 2.0;
+        }
+
+        let xs: Vec<f32> = v_ev.move_variant().iter().map(|m| *m.x).collect();
+        assert_eq!(xs, [-2.0, 2.0, 6.0]);
+
+        let mut moves = 0;
+        for ev in v_ev.iter_mut() {
+            match ev {
+                OrthoMutEvent::Move(m) => {
+                    *m.y += 1.0;
+                    moves += 1;
+                }
+                OrthoMutEvent::Hit(hit) => *hit.0 += 1,
+                OrthoMutEvent::Quit => {}
+            }
+        }
+        assert_eq!(moves, 3);
+
+        let ys: Vec<f32> = v_ev
+            .iter()
+            .filter_map(|ev| match ev {
+                OrthoEvent::Move(m) => Some(*m.y),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ys, [-1.0, 3.0, 5.0]);
+
+        assert!(matches!(
+            v_ev.iter().nth(2),
+            Some(OrthoEvent::Hit(OrthoEventHit(6, &"inserted")))
+        ));
+        assert!(matches!(v_ev.pop(), Some(Event::Quit)));
+        assert!(matches!(v_ev.remove(1), Event::Move { x, .. } if x == 2.0));
+        assert!(matches!(v_ev.swap_remove(0), Event::Move { x, .. } if x == -2.0));
+        assert_eq!(v_ev.len(), 3);
+
+        // The last element took the place of the removed one
+        let rest: Vec<Event> = v_ev.into_iter().collect();
+        assert!(matches!(rest[0], Event::Move { x, y } if x == 6.0 && y == 5.0));
+        assert!(matches!(rest[1], Event::Hit(6, "inserted")));
+        assert!(matches!(rest[2], Event::Hit(11, "first")));
+    }
+
+    #[test]
+    fn test_unit_struct() {
+        let mut v_us = OrthoVecUnitStruct::new();
+        assert!(v_us.pop().is_none());
+
+        v_us.push(UnitStruct);
+        v_us.insert(0, UnitStruct);
+        assert_eq!(v_us.len(), 2);
+        assert_eq!(v_us.iter().count(), 2);
+        assert_eq!(v_us.iter_mut().count(), 2);
+
+        v_us.swap_remove(1);
+        assert!(v_us.pop().is_some());
+        assert!(v_us.pop().is_none());
+
+        assert_eq!(vec![UnitStruct; 3].into_ortho().into_iter().count(), 3);
+    }
+
+    #[test]
+    fn test_marker_field() {
+        let mut v_tg: OrthoVecTagged<String> = (0..4)
+            .map(|id| Tagged {
+                _m: std::marker::PhantomData,
+                _n: std::marker::PhantomData,
+                id,
+            })
+            .collect::<Vec<_>>()
+            .into_ortho();
+
+        for tg in v_tg.iter_mut() {
+            *tg.id += 1;
+        }
+
+        let ids: Vec<u32> = v_tg.iter().map(|tg| *tg.id).collect();
+        assert_eq!(ids, [1, 2, 3, 4]);
+        assert_eq!(v_tg.remove(0).id, 1);
+        assert_eq!(v_tg.into_iter().map(|tg| tg.id).sum::<u32>(), 9);
+    }
+
+    #[test]
+    fn test_field_named_like_a_marker() {
+        let mut v_ms = OrthoVecMeasure::new();
+        for value in 0..3 {
+            v_ms.push(Measure {
+                unit: units::PhantomData(value as u8 * 2),
+                value,
+            });
+        }
+
+        let units: Vec<_> = v_ms.iter().map(|ms| ms.unit.0).collect();
+        assert_eq!(units, [0, 2, 4]);
+        assert_eq!(v_ms.pop().map(|ms| ms.unit), Some(units::PhantomData(4)));
+    }
+
+    #[test]
+    fn test_struct_in_function() {
+        #[derive(OrthoVec)]
+        struct Local {
+            x: i64,
+            name: String,
+        }
+
+        let mut v_lc = vec![
+            Local {
+                x: 1,
+                name: "one".to_string(),
+            },
+            Local {
+                x: 2,
+                name: "two".to_string(),
+            },
+        ]
+        .into_ortho();
+
+        for lc in v_lc.iter_mut() {
+            lc.name.push('!');
+        }
+
+        let names: Vec<String> = v_lc.into_iter().map(|lc| lc.name).collect();
+        assert_eq!(names, ["one!", "two!"]);
+    }
+
+    #[test]
+    fn test_struct_in_nested_module() {
+        assert_eq!(nested::sum_hidden(), 3);
+    }
+
+    #[test]
+    fn test_struct_visibility() {
+        use api::OrthoVecParticle;
+
+        let mut v_pt: OrthoVecParticle = vec![
+            api::Particle { pos: 0.0, vel: 1.0 },
+            api::Particle {
+                pos: 1.0,
+                vel: -2.0,
+            },
+        ]
+        .into_ortho();
+
+        for pt in v_pt.iter_mut() {
+            *pt.pos += *pt.vel;
+        }
+
+        let positions: Vec<f32> = v_pt.iter().map(|pt: api::OrthoParticle| *pt.pos).collect();
+        assert_eq!(positions, vec![1.0, -1.0]);
+
+        let mut v_ch = api::OrthoVecCharge::new();
+        v_ch.push(api::Charge(-1));
+        assert_eq!(v_ch.iter().map(|ch| *ch.0).sum::<i8>(), -1);
+
+        let v_sp: api::OrthoVecSpin = vec![api::Spin(1), api::Spin(-1)].into_ortho();
+        assert_eq!(v_sp.iter().map(|sp| *sp.0).collect::<Vec<_>>(), [1, -1]);
+    }
+
+    #[test]
+    fn test_const_generics_and_defaults() {
+        let mut v_rg: OrthoVecRing<3> = vec![
+            Ring {
+                data: [1.0, 2.0, 3.0],
+                head: 0,
+            },
+            Ring {
+                data: [4.0, 5.0, 6.0],
+                head: 2,
+            },
+        ]
+        .into_ortho();
+
+        for rg in v_rg.iter_mut() {
+            rg.data[*rg.head] = 0.0;
+        }
+
+        let sums: Vec<f32> = v_rg.iter().map(|rg| rg.data.iter().sum()).collect();
+        assert_eq!(sums, vec![5.0, 9.0]);
+
+        let v_sm: OrthoVecSamples<2> = vec![
+            Samples::Mono([1, 2]),
+            Samples::Silent,
+            Samples::Stereo {
+                left: [3, 4],
+                right: [5, 6],
+            },
+        ]
+        .into_ortho();
+
+        let totals: Vec<i16> = v_sm
+            .iter()
+            .map(|sm| match sm {
+                OrthoSamples::Mono(mono) => mono.0.iter().sum(),
+                OrthoSamples::Stereo(stereo) => stereo.left.iter().chain(stereo.right).sum(),
+                OrthoSamples::Silent => 0,
+            })
+            .collect();
+        assert_eq!(totals, vec![3, 0, 18]);
+    }
+
+    #[test]
+    fn test_where_predicates_of_removed_generics() {
+        let v_mt: OrthoVecMeter<u32, u8, core::ops::Range<u8>> =
+            vec![Meter::Next(1), Meter::Value(u32::from(2_u8)), Meter::Raw(3)].into_ortho();
+
+        let values: Vec<u32> = v_mt
+            .iter()
+            .map(|mt| match mt {
+                OrthoMeter::Next(next) => u32::from(*next.0),
+                OrthoMeter::Value(value) => *value.0,
+                OrthoMeter::Raw(raw) => u32::from(*raw.0),
+            })
+            .collect();
+        assert_eq!(values, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_lifetime_bounds() {
+        let value = 7_u64;
+        let long = String::from("long");
+
+        let mut v_bw = OrthoVecBorrowed::new();
+        v_bw.push(Borrowed {
+            short: &value,
+            long: &long,
+        });
+
+        let views: Vec<(u64, &str)> = v_bw.iter().map(|bw| (**bw.short, *bw.long)).collect();
+        assert_eq!(views, vec![(7, "long")]);
+    }
+
+    #[test]
+    fn test_custom_names() {
+        let mut v_pt: Particles = vec![
+            Particle { pos: 0.0, vel: 1.0 },
+            Particle { pos: 2.0, vel: 3.0 },
+        ]
+        .into_ortho();
+
+        let iter_mut: ParticlesIterMut = v_pt.iter_mut();
+        for pt in iter_mut {
+            let pt: ParticleMut = pt;
+            *pt.pos += *pt.vel;
+        }
+
+        let iter: ParticlesIter = v_pt.iter();
+        let positions: Vec<f32> = iter.map(|pt: ParticleRef| *pt.pos).collect();
+        assert_eq!(positions, vec![1.0, 5.0]);
+
+        let into_iter: ParticlesIntoIter = v_pt.into_iter();
+        assert_eq!(into_iter.map(|pt| pt.vel).sum::<f32>(), 4.0);
+
+        let mut v_cm: Commands = vec![Command::Wait(3), Command::Stop].into_ortho();
+        assert_eq!(v_cm.tags(), &[CommandKind::Wait, CommandKind::Stop]);
+
+        for cm in v_cm.iter_mut() {
+            if let CommandMut::Wait(wait) = cm {
+                *wait.0 *= 2;
+            }
+        }
+
+        let waits: Vec<u32> = v_cm
+            .iter()
+            .filter_map(|cm| match cm {
+                CommandRef::Wait(wait) => Some(*wait.0),
+                CommandRef::Stop => None,
+            })
+            .collect();
+        assert_eq!(waits, vec![6]);
+    }
+
+    #[test]
+    fn test_field_groups() {
+        let entity = |id: u8| Entity {
+            pos: f32::from(id),
+            name: format!("entity {id}"),
+            vel: 1.0,
+            extra: [id; 4],
+        };
+
+        let mut v_en: OrthoVecEntity<[u8; 4]> = (0..3).map(entity).collect::<Vec<_>>().into_ortho();
+        v_en.push(entity(3));
+        assert_eq!(v_en.cold.len(), 4);
+
+        for en in v_en.iter_mut() {
+            *en.pos += *en.vel;
+            en.name.push('!');
+            en.extra[0] += 10;
+        }
+
+        let views: Vec<(f32, &str, u8)> = v_en
+            .iter()
+            .map(|en| (*en.pos, en.name.as_str(), en.extra[0]))
+            .collect();
+        assert_eq!(
+            views,
+            vec![
+                (1.0, "entity 0!", 10),
+                (2.0, "entity 1!", 11),
+                (3.0, "entity 2!", 12),
+                (4.0, "entity 3!", 13),
+            ]
+        );
+
+        v_en.insert(1, entity(7));
+        let removed = v_en.remove(2);
+        assert_eq!((removed.pos, removed.name.as_str()), (2.0, "entity 1!"));
+        let swapped = v_en.swap_remove(0);
+        assert_eq!(swapped.extra, [10, 0, 0, 0]);
+
+        let popped = v_en.pop().unwrap();
+        assert_eq!(popped.name, "entity 2!");
+
+        let names: Vec<String> = v_en.into_iter().map(|en| en.name).collect();
+        assert_eq!(names, vec!["entity 3!", "entity 7"]);
+
+        let mut v_sm = OrthoVecSample::from(vec![Sample(1, "one".to_string(), 10)]);
+        v_sm.push(Sample(2, "two".to_string(), 20));
+        for sm in v_sm.iter_mut() {
+            *sm.2 += u16::from(*sm.0);
+        }
+        let samples: Vec<(u8, &str, u16)> = v_sm
+            .iter()
+            .map(|sm| (*sm.0, sm.1.as_str(), *sm.2))
+            .collect();
+        assert_eq!(samples, vec![(1, "one", 11), (2, "two", 22)]);
+
+        let v_sh = vec![
+            Shape::Circle {
+                radius: 1.5,
+                label: "small".to_string(),
+            },
+            Shape::Empty,
+        ]
+        .into_ortho();
+        let labels: Vec<&str> = v_sh
+            .circle_variant()
+            .iter()
+            .map(|circle| circle.label.as_str())
+            .collect();
+        assert_eq!(labels, vec!["small"]);
+    }
+
+    #[test]
+    fn test_flatten() {
+        let body = |id: u32| Body {
+            id,
+            transform: Transform {
+                pos: [0.0; 3],
+                rot: 0.5,
+            },
+            state: if id % 2 == 0 {
+                Event::Quit
+            } else {
+                Event::Hit(id, "hit")
+            },
+            mass: Wrapped(f64::from(id)),
+        };
+
+        let mut v_bd: OrthoVecBody<f64> = (0..3).map(body).collect::<Vec<_>>().into_ortho();
+        v_bd.push(body(3));
+        assert_eq!(v_bd.transform.len(), 4);
+
+        for bd in v_bd.iter_mut() {
+            bd.transform.pos[0] += *bd.transform.rot;
+            *bd.mass.0 *= 2.0;
+            if let OrthoMutEvent::Hit(hit) = bd.state {
+                *hit.0 += 10;
+            }
+        }
+
+        let views: Vec<(u32, f32, f64, Option<u32>)> = v_bd
+            .iter()
+            .map(|bd| {
+                let hit = match bd.state {
+                    OrthoEvent::Hit(hit) => Some(*hit.0),
+                    _ => None,
+                };
+                (*bd.id, bd.transform.pos[0], *bd.mass.0, hit)
+            })
+            .collect();
+        assert_eq!(
+            views,
+            vec![
+                (0, 0.5, 0.0, None),
+                (1, 0.5, 2.0, Some(11)),
+                (2, 0.5, 4.0, None),
+                (3, 0.5, 6.0, Some(13)),
+            ]
+        );
+
+        v_bd.insert(0, body(9));
+        let removed = v_bd.remove(1);
+        assert_eq!((removed.id, removed.mass.0), (0, 0.0));
+        let popped = v_bd.pop().unwrap();
+        assert_eq!(popped.transform.pos, [0.5, 0.0, 0.0]);
+        assert!(matches!(popped.state, Event::Hit(13, "hit")));
+
+        let ids: Vec<u32> = v_bd.into_iter().map(|bd| bd.id).collect();
+        assert_eq!(ids, vec![9, 1, 2]);
+    }
+
+    #[test]
+    fn test_cfg_fields() {
+        let mut v_gt = vec![Gated {
+            id: 1,
+            weight: 0.5,
+            flags: 3,
+        }]
+        .into_ortho();
+        v_gt.push(Gated {
+            id: 2,
+            weight: 1.5,
+            flags: 4,
+        });
+
+        for gt in v_gt.iter_mut() {
+            *gt.weight *= 2.0;
+        }
+
+        let views: Vec<(u32, f32, u8)> = v_gt
+            .iter()
+            .map(|gt| (*gt.id, *gt.weight, *gt.flags))
+            .collect();
+        assert_eq!(views, vec![(1, 1.0, 3), (2, 3.0, 4)]);
+
+        let v_ge = vec![GatedEvent::Spawn { id: 7 }, GatedEvent::Despawn(7)].into_ortho();
+        let ids: Vec<u32> = v_ge
+            .into_iter()
+            .map(|ge| match ge {
+                GatedEvent::Spawn { id } | GatedEvent::Despawn(id) => id,
+            })
+            .collect();
+        assert_eq!(ids, vec![7, 7]);
+    }
+
+    #[test]
+    fn test_forwarded_derives() {
+        let mut v_st = vec![Stat {
+            value: 3,
+            label: "three",
+            scale: Wrapped(0.5),
+        }]
+        .into_ortho();
+
+        let v_st_clone = v_st.clone();
+        assert_eq!(v_st, v_st_clone);
+
+        let st = v_st.iter().next().unwrap();
+        let st_copy = st;
+        assert_eq!(st, st_copy);
+        assert_eq!(
+            format!("{st:?}"),
+            "OrthoStat { value: 3, label: \"three\", scale: OrthoWrapped(0.5) }"
+        );
+
+        for st in v_st.iter_mut() {
+            *st.value += 1;
+            assert_eq!(
+                format!("{st:?}"),
+                "OrthoMutStat { value: 4, label: \"three\", scale: OrthoMutWrapped(0.5) }"
+            );
+        }
+        assert_ne!(v_st, v_st_clone);
+
+        let v_rd = vec![Reading::Value(1), Reading::Missing].into_ortho();
+        assert_eq!(v_rd.clone(), v_rd);
+        let readings: Vec<String> = v_rd.iter().map(|rd| format!("{rd:?}")).collect();
+        assert_eq!(readings, vec!["Value(OrthoReadingValue(1))", "Missing"]);
+    }
+
+    /// Moves the first element to the back, through `OrthoVec` alone.
+    fn rotate_left<V: OrthoVec>(v: &mut V) {
+        if !v.is_empty() {
+            let first = v.remove(0);
+            v.push(first);
+        }
+    }
+
+    fn count_where<V: OrthoVec>(v: &V, predicate: impl Fn(V::Ref<'_>) -> bool) -> usize {
+        v.iter().map(predicate).filter(|matched| *matched).count()
+    }
+
+    #[test]
+    fn test_ortho_vec_trait() {
+        let mut v_ts = vec![TupleStruct(1, 1.5, "one"), TupleStruct(2, 2.5, "two")].into_ortho();
+        rotate_left(&mut v_ts);
+        assert_eq!(count_where(&v_ts, |ts| *ts.0 > 1), 1);
+        assert_eq!(v_ts.iter().map(|ts| *ts.0).collect::<Vec<_>>(), vec![2, 1]);
+
+        let mut v_ev = vec![Event::Hit(1, "a"), Event::Quit, Event::Hit(2, "b")].into_ortho();
+        rotate_left(&mut v_ev);
+        assert_eq!(count_where(&v_ev, |ev| matches!(ev, OrthoEvent::Hit(_))), 2);
+        assert!(matches!(v_ev.iter().last(), Some(OrthoEvent::Hit(hit)) if *hit.0 == 1));
+
+        // Generic code can build ortho-`Vec`s too
+        fn rebuilt<V: OrthoVec>(v: V) -> V {
+            let mut rebuilt = V::with_capacity(v.len());
+            for element in v {
+                rebuilt.insert(0, element);
+            }
+            rebuilt
+        }
+
+        let v_ent = rebuilt(OrthoVecEntity::<u8>::from(vec![
+            Entity {
+                pos: 0.0,
+                name: "first".to_string(),
+                vel: 1.0,
+                extra: 1,
+            },
+            Entity {
+                pos: 1.0,
+                name: "second".to_string(),
+                vel: 2.0,
+                extra: 2,
+            },
+        ]));
+        let names: Vec<String> = v_ent.into_iter().map(|ent| ent.name).collect();
+        assert_eq!(names, ["second", "first"]);
+
+        // Types without `into_ortho()` still implement it
+        let mut v_ch = <api::OrthoVecCharge as OrthoVec>::new();
+        OrthoVec::push(&mut v_ch, api::Charge(3));
+        assert_eq!(OrthoVec::pop(&mut v_ch).map(|ch| ch.0), Some(3));
+        assert!(OrthoVec::is_empty(&v_ch));
+    }
+
+    /// Holds the columns of any type deriving `OrthoVec`, knowing only the type.
+    struct Storage<T: crate::Columnar> {
+        rows: T::Vec,
+    }
+
+    impl<T: crate::Columnar> Storage<T> {
+        fn new() -> Self {
+            Self {
+                rows: OrthoVec::new(),
+            }
+        }
+
+        fn spawn(&mut self, row: T) -> usize {
+            self.rows.push(row);
+            self.rows.len() - 1
+        }
+
+        fn rows(&self) -> <T::Vec as OrthoVec>::Iter<'_> {
+            self.rows.iter()
+        }
+    }
+
+    #[test]
+    fn test_columnar() {
+        let mut st_pt = Storage::<Particle>::new();
+        st_pt.spawn(Particle { pos: 1.0, vel: 2.0 });
+        assert_eq!(st_pt.spawn(Particle { pos: 3.0, vel: 4.0 }), 1);
+        let velocities: Vec<f32> = st_pt.rows().map(|pt: ParticleRef| *pt.vel).collect();
+        assert_eq!(velocities, vec![2.0, 4.0]);
+
+        let mut st_ev = Storage::<Event<'static>>::new();
+        st_ev.spawn(Event::Quit);
+        st_ev.spawn(Event::Move { x: 1.0, y: 2.0 });
+        assert!(matches!(st_ev.rows().last(), Some(OrthoEvent::Move(_))));
+
+        let v_pt: crate::Soa<Particle> = st_pt.rows;
+        assert_eq!(v_pt.len(), 2);
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(storage = "crate::storage::SmallVecStorage<4>")]
+    struct Inline<T> {
+        id: u32,
+        #[ortho(group = "meta")]
+        label: String,
+        #[ortho(group = "meta")]
+        extra: T,
+        #[ortho(flatten)]
+        transform: Transform,
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(storage = "crate::storage::ArrayVecStorage<4>")]
+    struct Fixed(u8, i16);
+
+    #[derive(OrthoVec)]
+    #[ortho(storage = "crate::storage::BoxedStorage")]
+    enum Frozen {
+        Point { x: f32, y: f32 },
+        Empty,
+    }
+
+    #[test]
+    fn test_storage() {
+        let inline = |id| Inline {
+            id,
+            label: id.to_string(),
+            extra: id * 2,
+            transform: Transform {
+                pos: [id as f32; 3],
+                rot: 0.0,
+            },
+        };
+        let mut v_in: OrthoVecInline<u32> = (1..=3).map(inline).collect::<Vec<_>>().into_ortho();
+        v_in.insert(0, inline(0));
+        v_in.reverse();
+        for inl in v_in.iter_mut() {
+            *inl.extra += 1;
+            *inl.transform.rot += 1.0;
+        }
+        assert_eq!(v_in.swap_remove(0).label, "3");
+        assert_eq!(v_in.remove(1).extra, 5);
+        v_in.shrink_to_fit();
+        let ids: Vec<u32> = v_in.iter().map(|inl| *inl.id).collect();
+        assert_eq!(ids, vec![0, 1]);
+        let rots: Vec<f32> = v_in.into_iter().map(|inl| inl.transform.rot).collect();
+        assert_eq!(rots, vec![1.0, 1.0]);
+
+        let mut v_fx = OrthoVecFixed::with_capacity(100);
+        for i in 0..4 {
+            v_fx.push(Fixed(i, -i16::from(i)));
+        }
+        assert_eq!(v_fx.pop().map(|fx| fx.1), Some(-3));
+        assert_eq!(v_fx.iter().map(|fx| *fx.1).sum::<i16>(), -3);
+        v_fx.clear();
+        assert_eq!(v_fx.len(), 0);
+
+        let mut v_fr = vec![
+            Frozen::Point { x: 1.0, y: 2.0 },
+            Frozen::Empty,
+            Frozen::Point { x: 3.0, y: 4.0 },
+        ]
+        .into_ortho();
+        for fr in v_fr.iter_mut() {
+            if let OrthoMutFrozen::Point(p) = fr {
+                *p.x *= 10.0;
+            }
+        }
+        v_fr.remove(1);
+        let xs: Vec<f32> = v_fr
+            .into_iter()
+            .filter_map(|fr| match fr {
+                Frozen::Point { x, .. } => Some(x),
+                Frozen::Empty => None,
+            })
+            .collect();
+        assert_eq!(xs, vec![10.0, 30.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "CapacityError: insufficient capacity")]
+    fn test_storage_over_capacity() {
+        let mut v_fx = OrthoVecFixed::new();
+        for i in 0..5 {
+            v_fx.push(Fixed(i, 0));
+        }
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(storage = "crate::storage::BoxedStorage")]
+    struct Sealed {
+        id: u32,
+        name: String,
+    }
+
+    #[test]
+    fn test_boxed_storage() {
+        let sealed = |id: u32| Sealed {
+            id,
+            name: id.to_string(),
+        };
+
+        // Converted all at once, rather than reallocating the columns for every element
+        let v_sl: OrthoVecSealed = (0..20_000).map(sealed).collect::<Vec<_>>().into_ortho();
+        assert_eq!(v_sl.len(), 20_000);
+        assert_eq!(v_sl.iter().last().map(|sl| sl.name.as_str()), Some("19999"));
+
+        // A column that panics keeps its elements, like the others do
+        let mut v_sl: OrthoVecSealed = vec![sealed(0), sealed(1)].into_ortho();
+        let inserted = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            v_sl.insert(3, sealed(3));
+        }));
+        assert!(inserted.is_err());
+        let removed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| v_sl.remove(2)));
+        assert!(removed.is_err());
+        let ids: Vec<u32> = v_sl.iter().map(|sl| *sl.id).collect();
+        assert_eq!(ids, [0, 1]);
+        assert_eq!(v_sl.pop().map(|sl| sl.name), Some("1".to_string()));
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(layout = "single")]
+    struct Packed<T> {
+        id: u8,
+        #[ortho(group = "meta")]
+        owner: std::rc::Rc<()>,
+        #[ortho(group = "meta")]
+        weight: T,
+        offset: u64,
+        nothing: (),
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(layout = "single")]
+    enum Script {
+        Say(String),
+        Wait { ticks: u16 },
+        Stop,
+    }
+
+    #[test]
+    fn test_single_layout() {
+        let owner = std::rc::Rc::new(());
+        let packed = |id| Packed {
+            id,
+            owner: owner.clone(),
+            weight: f32::from(id) / 2.0,
+            offset: u64::from(id) * 1000,
+            nothing: (),
+        };
+
+        let mut v_pk = OrthoVecPacked::with_capacity(2);
+        assert_eq!(v_pk.capacity(), 4);
+        for id in 0..10 {
+            v_pk.push(packed(id));
+        }
+        assert!(v_pk.capacity() >= 10);
+        assert_eq!(std::rc::Rc::strong_count(&owner), 11);
+
+        v_pk.insert(0, packed(10));
+        assert_eq!(v_pk.remove(1).id, 0);
+        assert_eq!(v_pk.swap_remove(0).offset, 10_000);
+        assert_eq!(
+            v_pk.pop().map(|pk| (pk.weight, pk.nothing)),
+            Some((4.0, ()))
+        );
+        v_pk.reverse();
+        for pk in v_pk.iter_mut() {
+            *pk.offset += 1;
+        }
+        v_pk.shrink_to_fit();
+        assert_eq!(v_pk.capacity(), 8);
+        let ids: Vec<u8> = v_pk.iter().map(|pk| *pk.id).collect();
+        assert_eq!(ids, vec![7, 6, 5, 4, 3, 2, 1, 9]);
+        let offsets: Vec<u64> = v_pk.iter().map(|pk| *pk.offset).collect();
+        assert_eq!(offsets[0], 7001);
+        assert_eq!(std::rc::Rc::strong_count(&owner), 9);
+
+        let mut into_iter = v_pk.into_iter();
+        assert_eq!(into_iter.next().map(|pk| pk.id), Some(7));
+        drop(into_iter);
+        assert_eq!(std::rc::Rc::strong_count(&owner), 1);
+
+        let mut v_pk: OrthoVecPacked<f32> = (0..3).map(packed).collect::<Vec<_>>().into_ortho();
+        v_pk.clear();
+        assert_eq!(v_pk.len(), 0);
+        v_pk.push(packed(1));
+        drop(v_pk);
+        assert_eq!(std::rc::Rc::strong_count(&owner), 1);
+
+        let mut v_cm = vec![
+            Script::Say("hi".to_string()),
+            Script::Wait { ticks: 3 },
+            Script::Stop,
+            Script::Say("bye".to_string()),
+        ]
+        .into_ortho();
+        for cm in v_cm.iter_mut() {
+            if let OrthoMutScript::Wait(wait) = cm {
+                *wait.ticks *= 2;
+            }
+        }
+        v_cm.remove(0);
+        let said: Vec<String> = v_cm
+            .into_iter()
+            .filter_map(|cm| match cm {
+                Script::Say(text) => Some(text),
+                Script::Wait { ticks } => Some(ticks.to_string()),
+                Script::Stop => None,
+            })
+            .collect();
+        assert_eq!(said, vec!["6", "bye"]);
+    }
+
+    /// Counts the live allocations it made
+    #[derive(Clone, Copy)]
+    struct Counting<'a>(&'a std::cell::Cell<isize>);
+
+    // SAFETY: every call is forwarded to `Global`
+    unsafe impl crate::allocator_api2::alloc::Allocator for Counting<'_> {
+        fn allocate(
+            &self,
+            layout: std::alloc::Layout,
+        ) -> Result<std::ptr::NonNull<[u8]>, crate::allocator_api2::alloc::AllocError> {
+            self.0.set(self.0.get() + 1);
+            crate::allocator_api2::alloc::Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: std::ptr::NonNull<u8>, layout: std::alloc::Layout) {
+            self.0.set(self.0.get() - 1);
+            // SAFETY: `ptr` was allocated by `Global` with `layout`
+            unsafe { crate::allocator_api2::alloc::Global.deallocate(ptr, layout) }
+        }
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(allocator = "A")]
+    struct Tracked<T> {
+        id: u32,
+        #[ortho(group = "meta")]
+        name: String,
+        #[ortho(group = "meta")]
+        tag: T,
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(allocator = "A")]
+    enum Input {
+        Key(char),
+        Click { x: i32, y: i32 },
+        Idle,
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(layout = "single", allocator = "A")]
+    struct Gauge(u16, f64);
+
+    #[test]
+    fn test_allocator() {
+        let live = std::cell::Cell::new(0);
+
+        let mut v_tr = OrthoVecTracked::new_in(Counting(&live));
+        for id in 0..5 {
+            v_tr.push(Tracked {
+                id,
+                name: format!("#{id}"),
+                tag: id % 2 == 0,
+            });
+        }
+        assert_eq!(live.get(), 2);
+        v_tr.insert(
+            0,
+            Tracked {
+                id: 9,
+                name: "#9".to_string(),
+                tag: false,
+            },
+        );
+        assert_eq!(v_tr.remove(1).name, "#0");
+        v_tr.reverse();
+        for tr in v_tr.iter_mut() {
+            *tr.id *= 10;
+        }
+        let ids: Vec<u32> = v_tr.iter().map(|tr| *tr.id).collect();
+        assert_eq!(ids, vec![40, 30, 20, 10, 90]);
+        let names: Vec<String> = v_tr.into_iter().map(|tr| tr.name).collect();
+        assert_eq!(names, vec!["#4", "#3", "#2", "#1", "#9"]);
+        assert_eq!(live.get(), 0);
+
+        let mut v_in = OrthoVecInput::with_capacity_in(4, Counting(&live));
+        v_in.push(Input::Key('q'));
+        v_in.push(Input::Idle);
+        v_in.push(Input::Click { x: 1, y: 2 });
+        v_in.reverse();
+        let mut xs = vec![];
+        for inp in v_in.iter() {
+            if let OrthoInput::Click(click) = inp {
+                xs.push(*click.x);
+            }
+        }
+        assert_eq!(xs, vec![1]);
+        assert!(matches!(
+            v_in.into_iter().next(),
+            Some(Input::Click { x: 1, y: 2 })
+        ));
+        assert_eq!(live.get(), 0);
+
+        let mut v_rd = OrthoVecGauge::with_capacity_in(2, Counting(&live));
+        assert_eq!(live.get(), 1);
+        v_rd.push(Gauge(1, 0.5));
+        v_rd.push(Gauge(2, 1.5));
+        v_rd.push(Gauge(3, 2.5));
+        assert_eq!(v_rd.pop().map(|rd| rd.1), Some(2.5));
+        let sums: Vec<f64> = v_rd.iter().map(|rd| f64::from(*rd.0) + *rd.1).collect();
+        assert_eq!(sums, vec![1.5, 3.5]);
+        drop(v_rd);
+        assert_eq!(live.get(), 0);
+
+        // Without an allocator, the columns are in the global one
+        let mut v_rd = OrthoVecGauge::new();
+        v_rd.push(Gauge(4, 0.0));
+        assert_eq!(v_rd.len(), 1);
+        let v_tr: OrthoVecTracked<()> = vec![Tracked {
+            id: 1,
+            name: String::new(),
+            tag: (),
+        }]
+        .into_ortho();
+        assert_eq!(v_tr.len(), 1);
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(align = 64)]
+    struct Signal {
+        level: f32,
+        #[ortho(group = "meta")]
+        channel: u8,
+        #[ortho(group = "meta")]
+        muted: bool,
+        gain: f32,
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(align = 32, layout = "single")]
+    struct Wave(u8, f64);
+
+    #[derive(OrthoVec)]
+    #[ortho(align = 128, layout = "single", allocator = "A")]
+    struct Grain {
+        offset: u32,
+        amplitude: f32,
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(align = 64)]
+    enum Voice {
+        Tone(f32),
+        Noise { seed: u32 },
+        Rest,
+    }
+
+    #[test]
+    fn test_aligned_columns() {
+        let mut v_sg = OrthoVecSignal::new();
+        for i in 0..19u8 {
+            v_sg.push(Signal {
+                level: f32::from(i),
+                channel: i % 4,
+                muted: i % 2 == 0,
+                gain: 2.0,
+            });
+        }
+
+        let mut lanes = v_sg.level_lanes::<8>();
+        assert_eq!(lanes.len(), 2);
+        assert!(lanes
+            .clone()
+            .all(|chunk| chunk.as_ptr().align_offset(32) == 0));
+        assert_eq!(
+            lanes.next(),
+            Some(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0])
+        );
+        assert_eq!(lanes.remainder(), &[16.0, 17.0, 18.0]);
+
+        let mut gain_lanes = v_sg.gain_lanes_mut::<4>();
+        for chunk in gain_lanes.by_ref() {
+            chunk.iter_mut().for_each(|gain| *gain *= 2.0);
+        }
+        assert_eq!(gain_lanes.into_remainder(), &[2.0, 2.0, 2.0]);
+        let gains: Vec<f32> = v_sg.iter().map(|sg| *sg.gain).collect();
+        assert_eq!(&gains[14..], &[4.0, 4.0, 2.0, 2.0, 2.0]);
+
+        let last = v_sg.remove(18);
+        v_sg.insert(0, last);
+        v_sg.shrink_to_fit();
+        assert_eq!(
+            v_sg.level_lanes::<16>().next().map(|chunk| chunk[0]),
+            Some(18.0)
+        );
+        assert_eq!(
+            v_sg.level_lanes::<16>()
+                .next()
+                .unwrap()
+                .as_ptr()
+                .align_offset(64),
+            0
+        );
+        let muted = v_sg.iter().filter(|sg| *sg.muted).count();
+        assert_eq!(muted, 10);
+
+        let mut v_wv = OrthoVecWave::new();
+        for i in 0..10 {
+            v_wv.push(Wave(i, f64::from(i) / 4.0));
+        }
+        assert_eq!(v_wv._0_lanes::<32>().remainder().len(), 10);
+        assert_eq!(
+            v_wv._1_lanes::<4>()
+                .next()
+                .unwrap()
+                .as_ptr()
+                .align_offset(32),
+            0
+        );
+        for chunk in v_wv._1_lanes_mut::<2>() {
+            chunk[1] = -chunk[1];
+        }
+        let sum: f64 = v_wv._1_lanes::<2>().map(|chunk| chunk[0] + chunk[1]).sum();
+        assert_eq!(sum, -1.25);
+
+        let live = std::cell::Cell::new(0);
+        let mut v_gr = OrthoVecGrain::new_in(Counting(&live));
+        for offset in 0..5 {
+            v_gr.push(Grain {
+                offset,
+                amplitude: 0.5,
+            });
+        }
+        assert_eq!(
+            v_gr.offset_lanes::<32>()
+                .remainder()
+                .as_ptr()
+                .align_offset(128),
+            0
+        );
+        assert_eq!(
+            v_gr.amplitude_lanes::<4>()
+                .next()
+                .unwrap()
+                .as_ptr()
+                .align_offset(128),
+            0
+        );
+        assert_eq!(v_gr.amplitude_lanes::<4>().remainder(), &[0.5]);
+        assert_eq!(live.get(), 1);
+        drop(v_gr);
+        assert_eq!(live.get(), 0);
+
+        let mut v_vc = OrthoVecVoice::with_capacity(3);
+        v_vc.push(Voice::Tone(440.0));
+        v_vc.push(Voice::Rest);
+        v_vc.push(Voice::Noise { seed: 7 });
+        v_vc.reverse();
+        let seeds: Vec<u32> = v_vc
+            .into_iter()
+            .filter_map(|vc| match vc {
+                Voice::Noise { seed } => Some(seed),
+                Voice::Tone(_) | Voice::Rest => None,
+            })
+            .collect();
+        assert_eq!(seeds, vec![7]);
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(chunk = 4)]
+    struct Boid<T> {
+        x: f32,
+        y: f32,
+        #[ortho(group = "meta")]
+        flock: std::rc::Rc<()>,
+        #[ortho(group = "meta")]
+        heading: T,
+        marker: std::marker::PhantomData<T>,
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(chunk = 3, storage = "crate::storage::SmallVecStorage<2>")]
+    struct Blip(u16, String);
+
+    #[derive(OrthoVec)]
+    #[ortho(chunk = 2, allocator = "A")]
+    struct Tick {
+        at: u64,
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(chunk = 8)]
+    enum Cue {
+        Play(String),
+        Seek { to: u32 },
+        Pause,
+    }
+
+    #[test]
+    fn test_chunked_layout() {
+        let flock = std::rc::Rc::new(());
+        let boid = |i: u8| Boid {
+            x: f32::from(i),
+            y: -f32::from(i),
+            flock: flock.clone(),
+            heading: u16::from(i) * 10,
+            marker: std::marker::PhantomData,
+        };
+        let xs = |v_bd: &OrthoVecBoid<u16>| v_bd.iter().map(|bd| *bd.x as u8).collect::<Vec<_>>();
+
+        let mut v_bd = OrthoVecBoid::with_capacity(6);
+        let mut expected: Vec<u8> = (0..10).collect();
+        for i in 0..10 {
+            v_bd.push(boid(i));
+        }
+        assert_eq!(std::rc::Rc::strong_count(&flock), 11);
+
+        for (index, i) in [(0, 10), (4, 11), (12, 12), (7, 13)] {
+            v_bd.insert(index, boid(i));
+            expected.insert(index, i);
+        }
+        assert_eq!(xs(&v_bd), expected);
+        for index in [3, 0, 11, 5] {
+            assert_eq!(v_bd.remove(index).x as u8, expected.remove(index));
+        }
+        assert_eq!(xs(&v_bd), expected);
+        assert_eq!(
+            v_bd.swap_remove(1).heading,
+            u16::from(expected.swap_remove(1)) * 10
+        );
+        assert_eq!(
+            v_bd.pop().map(|bd| bd.y as i8),
+            expected.pop().map(|i| -(i as i8))
+        );
+        v_bd.reverse();
+        expected.reverse();
+        assert_eq!(xs(&v_bd), expected);
+        assert_eq!(v_bd.len(), 8);
+        assert_eq!(std::rc::Rc::strong_count(&flock), 9);
+
+        for bd in v_bd.iter_mut() {
+            *bd.heading += 1;
+            *bd.y = *bd.x * 2.0;
+        }
+        let headings: Vec<u16> = v_bd.iter().map(|bd| *bd.heading).collect();
+        assert_eq!(headings[0], u16::from(expected[0]) * 10 + 1);
+
+        v_bd.pop();
+        let mut blocks = v_bd.blocks();
+        assert_eq!(blocks.len(), 2);
+        let first = blocks.next().unwrap();
+        assert_eq!(first.x.len(), 4);
+        assert_eq!(first.y[1], first.x[1] * 2.0);
+        assert_eq!(blocks.next_back().unwrap().x.len(), 3);
+        drop(blocks);
+        for block in v_bd.blocks_mut() {
+            for (x, y) in block.x.iter_mut().zip(block.y.iter()) {
+                *x += *y;
+            }
+        }
+        assert_eq!(*v_bd.iter().last().unwrap().x, f32::from(expected[6]) * 3.0);
+
+        let mut into_iter = v_bd.into_iter();
+        assert_eq!(
+            into_iter.next().map(|bd| bd.heading),
+            Some(u16::from(expected[0]) * 10 + 1)
+        );
+        drop(into_iter);
+        assert_eq!(std::rc::Rc::strong_count(&flock), 1);
+
+        let mut v_bd: OrthoVecBoid<u16> = (0..9).map(boid).collect::<Vec<_>>().into_ortho();
+        v_bd.clear();
+        assert_eq!(v_bd.blocks().len(), 0);
+        v_bd.push(boid(1));
+        v_bd.shrink_to_fit();
+        drop(v_bd);
+        assert_eq!(std::rc::Rc::strong_count(&flock), 1);
+
+        let mut v_bp = OrthoVecBlip::new();
+        for i in 0..5 {
+            v_bp.push(Blip(i, i.to_string()));
+        }
+        v_bp.insert(2, Blip(9, "nine".to_string()));
+        let blocks: Vec<Vec<&str>> = v_bp
+            .blocks()
+            .map(|block| block._1.iter().map(String::as_str).collect())
+            .collect();
+        assert_eq!(blocks, vec![vec!["0", "1", "nine"], vec!["2", "3", "4"]]);
+        assert_eq!(v_bp.remove(0).1, "0");
+        assert_eq!(
+            v_bp.blocks()
+                .map(|block| block._0.len())
+                .collect::<Vec<_>>(),
+            vec![3, 2]
+        );
+
+        let live = std::cell::Cell::new(0);
+        let mut v_tk = OrthoVecTick::with_capacity_in(3, Counting(&live));
+        for at in 0..5 {
+            v_tk.push(Tick { at });
+        }
+        assert_eq!(live.get(), 1);
+        assert_eq!(v_tk.iter().map(|tk| *tk.at).sum::<u64>(), 10);
+        drop(v_tk);
+        assert_eq!(live.get(), 0);
+
+        let mut v_cu = vec![
+            Cue::Play("intro".to_string()),
+            Cue::Seek { to: 30 },
+            Cue::Pause,
+            Cue::Play("outro".to_string()),
+        ]
+        .into_ortho();
+        v_cu.swap_remove(0);
+        let played: Vec<String> = v_cu
+            .into_iter()
+            .filter_map(|cu| match cu {
+                Cue::Play(track) => Some(track),
+                Cue::Seek { to } => Some(to.to_string()),
+                Cue::Pause => None,
+            })
+            .collect();
+        assert_eq!(played, vec!["outro", "30"]);
+    }
+
+    /// Named like the blocks of `Lap` would be if they weren't named after its ortho-`Vec`.
+    #[derive(Debug, PartialEq)]
+    struct OrthoVecBlockLap(u32);
+
+    #[derive(OrthoVec)]
+    #[ortho(chunk = 2, name = "Laps")]
+    struct Lap {
+        ms: u32,
+    }
+
+    #[test]
+    fn test_chunked_layout_named() {
+        let mut v_lp = Laps::new();
+        for ms in [300, 310, 290] {
+            v_lp.push(Lap { ms });
+        }
+
+        let best = v_lp
+            .blocks()
+            .flat_map(|block| block.ms.iter().copied())
+            .min()
+            .map(OrthoVecBlockLap);
+        assert_eq!(best, Some(OrthoVecBlockLap(290)));
+        assert_eq!(v_lp.blocks().len(), 2);
+    }
+
+    #[derive(OrthoVec)]
+    struct Mob {
+        id: u32,
+        #[ortho(bitpacked)]
+        alive: bool,
+        #[ortho(bitpacked)]
+        visible: bool,
+        #[ortho(group = "meta")]
+        hostile: bool,
+        #[ortho(group = "meta")]
+        level: u8,
+    }
+
+    #[derive(OrthoVec, Debug, PartialEq)]
+    #[ortho(
+        storage = "crate::storage::SmallVecStorage<1>",
+        derive(Clone, Debug, PartialEq)
+    )]
+    struct Flag(#[ortho(bitpacked)] bool, u8);
+
+    #[derive(OrthoVec)]
+    enum Switch {
+        On {
+            #[ortho(bitpacked)]
+            dimmed: bool,
+        },
+        Off,
+    }
+
+    #[test]
+    fn test_bitpacked_fields() {
+        let mob = |id: u32| Mob {
+            id,
+            alive: id % 3 != 0,
+            visible: id % 2 == 0,
+            hostile: id % 5 == 0,
+            level: id as u8,
+        };
+        let alive = |v_mb: &OrthoVecMob| v_mb.iter().map(|mb| *mb.alive).collect::<Vec<_>>();
+
+        let mut v_mb: OrthoVecMob = (0..130).map(mob).collect::<Vec<_>>().into_ortho();
+        let mut expected: Vec<bool> = (0..130).map(|id| mob(id).alive).collect();
+        assert_eq!(v_mb.alive_bits().len(), 130);
+        assert_eq!(v_mb.alive_bits().words().len(), 3);
+        assert_eq!(v_mb.alive_bits().count_ones(), 86);
+        assert_eq!(v_mb.visible_bits().count_zeros(), 65);
+
+        for (index, id) in [(0, 200), (63, 201), (64, 202), (133, 204), (100, 206)] {
+            v_mb.insert(index, mob(id));
+            expected.insert(index, mob(id).alive);
+        }
+        assert_eq!(alive(&v_mb), expected);
+        for index in [134, 0, 62, 64, 127, 3] {
+            assert_eq!(v_mb.remove(index).alive, expected.remove(index));
+        }
+        assert_eq!(alive(&v_mb), expected);
+        assert_eq!(v_mb.swap_remove(5).alive, expected.swap_remove(5));
+        assert_eq!(v_mb.pop().map(|mb| mb.alive), expected.pop());
+        v_mb.reverse();
+        expected.reverse();
+        assert_eq!(alive(&v_mb), expected);
+        assert_eq!(v_mb.len(), 127);
+        assert_eq!(v_mb.alive_bits().words().len(), 2);
+        assert_eq!(
+            v_mb.alive_bits().count_ones(),
+            expected.iter().filter(|alive| **alive).count()
+        );
+
+        let ones: Vec<usize> = v_mb.alive_bits().ones().collect();
+        let expected_ones: Vec<usize> = (0..expected.len()).filter(|i| expected[*i]).collect();
+        assert_eq!(ones, expected_ones);
+
+        for mut mb in v_mb.iter_mut() {
+            if *mb.level % 4 == 0 {
+                mb.alive.set(false);
+            }
+            *mb.level += 1;
+        }
+        let mut visible = v_mb.visible_bits_mut();
+        visible.fill(true);
+        assert_eq!(visible.as_bits().count_ones(), 127);
+        assert!(v_mb.iter().all(|mb| *mb.visible));
+
+        let mut mask: crate::bits::BitVec<Vec<u64>> = v_mb.alive_bits().into();
+        mask.bits_mut().and(v_mb.visible_bits());
+        assert_eq!(mask.bits().count_ones(), v_mb.alive_bits().count_ones());
+        v_mb.visible_bits_mut().set(0, false);
+        mask.bits_mut().or(v_mb.visible_bits());
+        assert_eq!(mask.bits().count_ones(), 127);
+        assert_eq!(mask.bits().get(127), None);
+
+        let first = v_mb.iter().next().unwrap();
+        assert!(!first.visible.get());
+        assert_eq!(format!("{:?}", first.alive), format!("{}", *first.alive));
+        let hostile_ids: Vec<u32> = v_mb
+            .into_iter()
+            .filter(|mb| mb.hostile && mb.alive)
+            .map(|mb| mb.id)
+            .collect();
+        assert!(hostile_ids.iter().all(|id| id % 5 == 0 && id % 3 != 0));
+
+        let mut v_fl = OrthoVecFlag::new();
+        for i in 0..3 {
+            v_fl.push(Flag(i != 1, i));
+        }
+        let copy = v_fl.clone();
+        assert_eq!(copy, v_fl);
+        assert_eq!(
+            format!("{v_fl:?}"),
+            "OrthoVecFlag { _0: [true, false, true], _1: [0, 1, 2], __len: 3, __marker: PhantomData<ortho_vec_derive::tests::Flag> }"
+        );
+        v_fl.iter_mut().for_each(|mut fl| {
+            let previous = fl.0.replace(false);
+            *fl.1 += u8::from(previous);
+        });
+        assert_ne!(copy, v_fl);
+        assert_eq!(v_fl._0_bits().ones().count(), 0);
+        assert_eq!(v_fl.pop(), Some(Flag(false, 3)));
+
+        let mut v_sw = vec![
+            Switch::On { dimmed: true },
+            Switch::Off,
+            Switch::On { dimmed: false },
+        ]
+        .into_ortho();
+        for sw in v_sw.iter_mut() {
+            if let OrthoMutSwitch::On(mut on) = sw {
+                let dimmed = *on.dimmed;
+                on.dimmed.set(!dimmed);
+            }
+        }
+        let dimmed: Vec<bool> = v_sw
+            .into_iter()
+            .filter_map(|sw| match sw {
+                Switch::On { dimmed } => Some(dimmed),
+                Switch::Off => None,
+            })
+            .collect();
+        assert_eq!(dimmed, vec![false, true]);
+    }
+
+    #[derive(OrthoVec)]
+    struct Trade {
+        symbol: u16,
+        #[ortho(nullable)]
+        price: Option<f64>,
+        #[ortho(nullable)]
+        volume: Option<u32>,
+        #[ortho(bitpacked)]
+        settled: bool,
+    }
+
+    #[derive(OrthoVec, Clone, Debug, PartialEq)]
+    #[ortho(
+        storage = "crate::storage::SmallVecStorage<2>",
+        derive(Clone, Debug, PartialEq)
+    )]
+    struct Probe<T>(#[ortho(nullable)] Option<T>, u8);
+
+    #[derive(OrthoVec)]
+    enum Quote {
+        Bid {
+            #[ortho(nullable)]
+            size: Option<u64>,
+        },
+        Cancel,
+    }
+
+    #[test]
+    fn test_nullable_fields() {
+        let trade = |i: u32| Trade {
+            symbol: i as u16,
+            price: (i % 3 != 0).then(|| f64::from(i) / 2.0),
+            volume: (i < 70 && i % 2 == 0).then_some(i * 100),
+            settled: i % 4 == 0,
+        };
+        let prices =
+            |v_tr: &OrthoVecTrade| v_tr.iter().map(|tr| tr.price.copied()).collect::<Vec<_>>();
+
+        let mut v_tr = OrthoVecTrade::new();
+        let mut expected: Vec<Option<f64>> = Vec::new();
+        for i in 0..100 {
+            v_tr.push(trade(i));
+            expected.push(trade(i).price);
+        }
+        assert_eq!(v_tr.price_present().len(), 66);
+        assert_eq!(v_tr.volume_validity().count_ones(), 35);
+        assert_eq!(v_tr.volume_present().last(), Some((68, &6800)));
+
+        for (index, i) in [(0, 200), (64, 201), (65, 202), (103, 203), (30, 204)] {
+            v_tr.insert(index, trade(i));
+            expected.insert(index, trade(i).price);
+        }
+        assert_eq!(prices(&v_tr), expected);
+        for index in [0, 103, 63, 64, 2, 50] {
+            assert_eq!(v_tr.remove(index).price, expected.remove(index));
+        }
+        assert_eq!(prices(&v_tr), expected);
+        for index in [1, 2, 10, 0] {
+            assert_eq!(v_tr.swap_remove(index).price, expected.swap_remove(index));
+            assert_eq!(prices(&v_tr), expected);
+        }
+        assert_eq!(v_tr.pop().map(|tr| tr.price), expected.pop());
+        v_tr.reverse();
+        expected.reverse();
+        assert_eq!(prices(&v_tr), expected);
+        assert_eq!(v_tr.len(), 94);
+
+        let present: Vec<(usize, f64)> = v_tr.price_present().map(|(i, p)| (i, *p)).collect();
+        let expected_present: Vec<(usize, f64)> = expected
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| Some((i, (*p)?)))
+            .collect();
+        assert_eq!(present, expected_present);
+
+        for (_, price) in v_tr.price_present_mut() {
+            *price *= 2.0;
+        }
+        for tr in v_tr.iter_mut() {
+            assert_eq!(tr.volume.is_some(), *tr.symbol % 2 == 0 && *tr.symbol < 70);
+            if let Some(volume) = tr.volume {
+                *volume += u32::from(*tr.symbol);
+            }
+        }
+        let total: f64 = v_tr.iter().filter_map(|tr| tr.price.copied()).sum();
+        assert_eq!(total, expected.iter().flatten().sum::<f64>() * 2.0);
+
+        let settled_volume: u32 = v_tr
+            .into_iter()
+            .filter(|tr| tr.settled)
+            .filter_map(|tr| tr.volume)
+            .sum();
+        assert_eq!(settled_volume, (0..70).step_by(4).map(|i| i * 101).sum());
+
+        let mut v_pr = OrthoVecProbe::new();
+        v_pr.push(Probe(Some("a".to_string()), 0));
+        v_pr.push(Probe(None, 1));
+        v_pr.push(Probe(Some("c".to_string()), 2));
+        let copy = v_pr.clone();
+        assert_eq!(copy, v_pr);
+        assert_eq!(
+            format!("{:?}", v_pr._0_present().collect::<Vec<_>>()),
+            r#"[(0, "a"), (2, "c")]"#
+        );
+        assert!(
+            format!("{v_pr:?}").starts_with(r#"OrthoVecProbe { _0: [Some("a"), None, Some("c")]"#)
+        );
+        for pr in v_pr.iter_mut() {
+            if let Some(name) = pr.0 {
+                name.push('!');
+            }
+        }
+        assert_ne!(copy, v_pr);
+        assert_eq!(v_pr.remove(0), Probe(Some("a!".to_string()), 0));
+        assert_eq!(v_pr.swap_remove(0), Probe(None, 1));
+        assert_eq!(v_pr._0_validity().len(), 1);
+
+        let v_qt = vec![
+            Quote::Bid { size: Some(5) },
+            Quote::Cancel,
+            Quote::Bid { size: None },
+        ]
+        .into_ortho();
+        let sizes: Vec<Option<&u64>> = v_qt
+            .iter()
+            .filter_map(|qt| match qt {
+                OrthoQuote::Bid(bid) => Some(bid.size),
+                OrthoQuote::Cancel => None,
+            })
+            .collect();
+        assert_eq!(sizes, vec![Some(&5), None]);
+    }
+
+    #[derive(OrthoVec)]
+    struct Visit {
+        #[ortho(dictionary = "u8")]
+        page: String,
+        #[ortho(dictionary)]
+        country: &'static str,
+        duration: u32,
+    }
+
+    #[derive(OrthoVec, Clone, Debug, PartialEq, Eq, Hash)]
+    #[ortho(
+        storage = "crate::storage::SmallVecStorage<4>",
+        derive(Clone, Debug, PartialEq, Eq, Hash)
+    )]
+    struct Label<T: Ord + Clone>(#[ortho(dictionary = "u16")] T, u8);
+
+    #[derive(OrthoVec)]
+    enum Order {
+        Placed {
+            #[ortho(dictionary = "u8")]
+            currency: char,
+            amount: u64,
+        },
+        Dropped,
+    }
+
+    #[test]
+    fn test_dictionary_fields() {
+        const COUNTRIES: [&str; 3] = ["fr", "jp", "br"];
+        let visit = |i: u32| Visit {
+            page: format!("/page/{}", i % 7),
+            country: COUNTRIES[i as usize % 3],
+            duration: i,
+        };
+        let pages =
+            |v_vs: &OrthoVecVisit| v_vs.iter().map(|vs| vs.page.clone()).collect::<Vec<_>>();
+
+        let mut v_vs = OrthoVecVisit::new();
+        let mut expected = Vec::new();
+        for i in 0..50 {
+            v_vs.push(visit(i));
+            expected.push(visit(i).page);
+        }
+        assert_eq!(v_vs.page_dictionary().len(), 7);
+        assert_eq!(v_vs.country_dictionary().values(), &COUNTRIES);
+        assert_eq!(v_vs.page_codes()[..8], [0, 1, 2, 3, 4, 5, 6, 0]);
+
+        for (index, i) in [(0, 20), (10, 21), (52, 22), (7, 100)] {
+            v_vs.insert(index, visit(i));
+            expected.insert(index, visit(i).page);
+        }
+        assert_eq!(pages(&v_vs), expected);
+        for index in [0, 52, 3, 30] {
+            assert_eq!(v_vs.remove(index).page, expected.remove(index));
+        }
+        for index in [1, 5, 0] {
+            assert_eq!(v_vs.swap_remove(index).page, expected.swap_remove(index));
+        }
+        assert_eq!(v_vs.pop().map(|vs| vs.page), expected.pop());
+        v_vs.reverse();
+        expected.reverse();
+        assert_eq!(pages(&v_vs), expected);
+        assert_eq!(v_vs.len(), 46);
+
+        let jp = v_vs.country_dictionary().code(&"jp").unwrap();
+        let in_jp = v_vs
+            .country_codes()
+            .iter()
+            .filter(|&&code| code == jp)
+            .count();
+        assert_eq!(in_jp, v_vs.iter().filter(|vs| *vs.country == "jp").count());
+        assert_eq!(v_vs.country_dictionary().code(&"de"), None);
+
+        let mut by_page = [0; 7];
+        for (&code, vs) in v_vs.page_codes().iter().zip(v_vs.iter()) {
+            by_page[usize::from(code)] += vs.duration;
+        }
+        assert_eq!(
+            by_page.iter().sum::<u32>(),
+            v_vs.iter().map(|vs| vs.duration).sum()
+        );
+
+        let expected_durations: u32 = v_vs
+            .iter()
+            .filter(|vs| *vs.page == "/page/2" && *vs.country != "br")
+            .map(|vs| *vs.duration)
+            .sum();
+        for vs in v_vs.iter_mut() {
+            if *vs.country == "br" {
+                *vs.duration = 0;
+            }
+        }
+        let durations: u32 = v_vs
+            .into_iter()
+            .filter(|vs| vs.page == "/page/2")
+            .map(|vs| vs.duration)
+            .sum();
+        assert_eq!(durations, expected_durations);
+
+        let mut v_lb = OrthoVecLabel::new();
+        v_lb.push(Label("b", 0));
+        v_lb.push(Label("a", 1));
+        v_lb.push(Label("b", 2));
+        let copy = v_lb.clone();
+        assert_eq!(copy, v_lb);
+        assert_eq!(v_lb._0_codes(), &[0, 1, 0]);
+        assert!(format!("{v_lb:?}").starts_with(r#"OrthoVecLabel { _0: ["b", "a", "b"]"#));
+        assert_eq!(v_lb.remove(0), Label("b", 0));
+        v_lb.clear();
+        assert!(v_lb._0_dictionary().is_empty());
+        assert_ne!(copy, v_lb);
+
+        let v_or = vec![
+            Order::Placed {
+                currency: '€',
+                amount: 5,
+            },
+            Order::Dropped,
+            Order::Placed {
+                currency: '¥',
+                amount: 300,
+            },
+        ]
+        .into_ortho();
+        let currencies: Vec<char> = v_or
+            .iter()
+            .filter_map(|or| match or {
+                OrthoOrder::Placed(placed) => Some(*placed.currency),
+                OrthoOrder::Dropped => None,
+            })
+            .collect();
+        assert_eq!(currencies, vec!['€', '¥']);
+    }
+
+    #[test]
+    #[should_panic(expected = "the dictionary is full")]
+    fn test_dictionary_fields_full() {
+        let mut v_vs = OrthoVecVisit::new();
+        for i in 0..=256 {
+            v_vs.push(Visit {
+                page: i.to_string(),
+                country: "fr",
+                duration: 0,
+            });
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Status {
+        Idle,
+        Busy,
+        Fault,
+    }
+
+    #[derive(OrthoVec)]
+    struct Telemetry {
+        #[ortho(encoding = "delta")]
+        timestamp: u64,
+        #[ortho(encoding = "rle")]
+        status: Status,
+        #[ortho(encoding = "delta")]
+        offset: i16,
+        load: f32,
+    }
+
+    #[derive(OrthoVec, Clone, Debug, PartialEq, Eq, Hash)]
+    #[ortho(derive(Clone, Debug, PartialEq, Eq, Hash))]
+    struct Stretch<T: PartialEq + Clone>(
+        #[ortho(encoding = "rle")] T,
+        #[ortho(encoding = "delta")] i32,
+    );
+
+    #[derive(OrthoVec)]
+    enum Log {
+        Line {
+            #[ortho(encoding = "delta")]
+            at: u32,
+        },
+        Gap,
+    }
+
+    #[test]
+    fn test_encoded_fields() {
+        let telemetry = |i: u64| Telemetry {
+            timestamp: 1_700_000_000_000 + i * 250,
+            status: match i % 100 {
+                0..=59 => Status::Idle,
+                60..=89 => Status::Busy,
+                _ => Status::Fault,
+            },
+            offset: i16::try_from(i % 9).unwrap() - 4,
+            load: 0.5,
+        };
+        let decoded = |v_tl: &OrthoVecTelemetry| {
+            v_tl.iter()
+                .map(|tl| (tl.timestamp, *tl.status, tl.offset))
+                .collect::<Vec<_>>()
+        };
+        let fields = |tl: &Telemetry| (tl.timestamp, tl.status, tl.offset);
+
+        let mut v_tl = OrthoVecTelemetry::new();
+        let mut expected = Vec::new();
+        for i in 0..300 {
+            v_tl.push(telemetry(i));
+            expected.push(fields(&telemetry(i)));
+        }
+        assert_eq!(decoded(&v_tl), expected);
+        assert_eq!(v_tl.status_encoded().run_count(), 9);
+        assert_eq!(v_tl.timestamp_encoded().encoded_len(), 300 - 5 + 295);
+        assert_eq!(
+            v_tl.timestamp_encoded().get(130),
+            Some(1_700_000_000_000 + 130 * 250)
+        );
+        assert_eq!(v_tl.status_encoded().get(295), Some(&Status::Fault));
+
+        for (index, i) in [(0, 1000), (64, 1001), (128, 1002), (303, 1003), (61, 61)] {
+            v_tl.insert(index, telemetry(i));
+            expected.insert(index, fields(&telemetry(i)));
+        }
+        assert_eq!(decoded(&v_tl), expected);
+        for index in [0, 63, 64, 301, 150, 60] {
+            assert_eq!(fields(&v_tl.remove(index)), expected.remove(index));
+        }
+        assert_eq!(decoded(&v_tl), expected);
+        for index in [1, 127, 128, 0] {
+            assert_eq!(
+                fields(&v_tl.swap_remove(index)),
+                expected.swap_remove(index)
+            );
+            assert_eq!(decoded(&v_tl), expected);
+        }
+        for _ in 0..40 {
+            assert_eq!(v_tl.pop().map(|tl| fields(&tl)), expected.pop());
+        }
+        v_tl.reverse();
+        expected.reverse();
+        assert_eq!(decoded(&v_tl), expected);
+        assert_eq!(v_tl.len(), 255);
+        assert_eq!(
+            v_tl.status_encoded()
+                .runs()
+                .map(|(_, len)| len)
+                .sum::<usize>(),
+            255
+        );
+
+        for tl in v_tl.iter_mut() {
+            *tl.load += f32::from(tl.offset);
+        }
+        let faults: Vec<(u64, Status)> = v_tl
+            .into_iter()
+            .filter(|tl| tl.status == Status::Fault)
+            .map(|tl| (tl.timestamp, tl.status))
+            .collect();
+        let expected_faults: Vec<(u64, Status)> = expected
+            .iter()
+            .filter(|(_, status, _)| *status == Status::Fault)
+            .map(|&(timestamp, status, _)| (timestamp, status))
+            .collect();
+        assert_eq!(faults, expected_faults);
+
+        let mut v_st = OrthoVecStretch::new();
+        v_st.push(Stretch("a", -5));
+        v_st.push(Stretch("a", 100));
+        v_st.push(Stretch("b", i32::MIN));
+        v_st.push(Stretch("a", i32::MAX));
+        let copy = v_st.clone();
+        assert_eq!(copy, v_st);
+        assert_eq!(v_st._0_encoded().run_count(), 3);
+        assert!(format!("{v_st:?}").starts_with(
+            r#"OrthoVecStretch { _0: ["a", "a", "b", "a"], _1: [-5, 100, -2147483648, 2147483647]"#
+        ));
+        assert_eq!(v_st.remove(2), Stretch("b", i32::MIN));
+        assert_eq!(v_st._0_encoded().run_count(), 1);
+        assert_eq!(v_st.swap_remove(0), Stretch("a", -5));
+        assert_eq!(
+            v_st.iter().map(|st| st.1).collect::<Vec<_>>(),
+            [i32::MAX, 100]
+        );
+        assert_ne!(copy, v_st);
+
+        let v_lg = vec![Log::Line { at: 10 }, Log::Gap, Log::Line { at: 7 }].into_ortho();
+        let lines: Vec<u32> = v_lg
+            .iter()
+            .filter_map(|lg| match lg {
+                OrthoLog::Line(line) => Some(line.at),
+                OrthoLog::Gap => None,
+            })
+            .collect();
+        assert_eq!(lines, vec![10, 7]);
+    }
+
+    /// Levels between 0 and 1, quantized to 16 bits.
+    #[derive(Default)]
+    struct Quantized {
+        steps: Vec<u16>,
+    }
+
+    fn quantize(level: f32) -> u16 {
+        (level.clamp(0.0, 1.0) * f32::from(u16::MAX)).round() as u16
+    }
+
+    fn dequantize(step: u16) -> f32 {
+        f32::from(step) / f32::from(u16::MAX)
+    }
+
+    struct QuantizedMut<'a>(&'a mut u16);
+
+    impl QuantizedMut<'_> {
+        fn get(&self) -> f32 {
+            dequantize(*self.0)
+        }
+
+        fn set(&mut self, level: f32) {
+            *self.0 = quantize(level);
+        }
+    }
+
+    impl IntoIterator for Quantized {
+        type Item = f32;
+        type IntoIter = std::iter::Map<std::vec::IntoIter<u16>, fn(u16) -> f32>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.steps.into_iter().map(dequantize)
+        }
+    }
+
+    impl crate::storage::ColumnStorage<f32> for Quantized {
+        type Ref<'a> = f32;
+        type RefMut<'a> = QuantizedMut<'a>;
+        type Iter<'a> =
+            std::iter::Map<std::iter::Copied<std::slice::Iter<'a, u16>>, fn(u16) -> f32>;
+        type IterMut<'a> =
+            std::iter::Map<std::slice::IterMut<'a, u16>, fn(&'a mut u16) -> QuantizedMut<'a>>;
+
+        fn new() -> Self {
+            Self::default()
+        }
+
+        fn len(&self) -> usize {
+            self.steps.len()
+        }
+
+        fn reserve(&mut self, additional: usize) {
+            self.steps.reserve(additional);
+        }
+
+        fn get(&self, index: usize) -> Option<f32> {
+            self.steps.get(index).copied().map(dequantize)
+        }
+
+        fn get_mut(&mut self, index: usize) -> Option<QuantizedMut<'_>> {
+            self.steps.get_mut(index).map(QuantizedMut)
+        }
+
+        fn iter(&self) -> Self::Iter<'_> {
+            self.steps.iter().copied().map(dequantize)
+        }
+
+        fn iter_mut(&mut self) -> Self::IterMut<'_> {
+            self.steps.iter_mut().map(QuantizedMut)
+        }
+
+        fn push(&mut self, value: f32) {
+            self.steps.push(quantize(value));
+        }
+
+        fn pop(&mut self) -> Option<f32> {
+            self.steps.pop().map(dequantize)
+        }
+
+        fn insert(&mut self, index: usize, element: f32) {
+            self.steps.insert(index, quantize(element));
+        }
+
+        fn remove(&mut self, index: usize) -> f32 {
+            dequantize(self.steps.remove(index))
+        }
+    }
+
+    #[derive(OrthoVec)]
+    struct Dimmer<T> {
+        #[ortho(column = Quantized)]
+        level: f32,
+        #[ortho(column = "Vec<T>")]
+        tag: T,
+        id: u32,
+    }
+
+    #[derive(OrthoVec)]
+    enum Light {
+        Level(#[ortho(column = Quantized)] f32),
+        Off,
+    }
+
+    #[test]
+    fn test_custom_columns() {
+        use crate::storage::ColumnStorage;
+
+        let dimmer = |i: u32| Dimmer {
+            level: i as f32 / 100.0,
+            tag: format!("s{i}"),
+            id: i,
+        };
+        let fields = |v_dm: &OrthoVecDimmer<String>| {
+            v_dm.iter()
+                .map(|dm| (dm.level, dm.tag.clone(), *dm.id))
+                .collect::<Vec<_>>()
+        };
+
+        let mut v_dm = OrthoVecDimmer::with_capacity(4);
+        let mut expected = Vec::new();
+        for i in 0..=100 {
+            v_dm.push(dimmer(i));
+            expected.push((dequantize(quantize(i as f32 / 100.0)), format!("s{i}"), i));
+        }
+        assert_eq!(fields(&v_dm), expected);
+        assert_eq!(v_dm.level_column().len(), 101);
+        assert!((v_dm.level_column().get(50).unwrap() - 0.5).abs() < 1e-4);
+        assert_eq!(v_dm.tag_column()[7], "s7");
+
+        v_dm.insert(3, dimmer(40));
+        expected.insert(3, expected[40].clone());
+        let removed = v_dm.remove(10);
+        let expected_removed = expected.remove(10);
+        assert_eq!((removed.level, removed.tag, removed.id), expected_removed);
+        let swapped = v_dm.swap_remove(0);
+        let expected_swapped = expected.swap_remove(0);
+        assert_eq!((swapped.level, swapped.tag, swapped.id), expected_swapped);
+        v_dm.reverse();
+        expected.reverse();
+        assert_eq!(fields(&v_dm), expected);
+
+        for mut dm in v_dm.iter_mut() {
+            let level = dm.level.get();
+            dm.level.set(1.0 - level);
+            dm.tag.push('!');
+        }
+        for (level, tag, _) in &mut expected {
+            *level = dequantize(quantize(1.0 - *level));
+            tag.push('!');
+        }
+        assert_eq!(fields(&v_dm), expected);
+
+        let last = v_dm.pop().unwrap();
+        assert_eq!((last.level, last.tag, last.id), expected.pop().unwrap());
+        let moved: Vec<_> = v_dm
+            .into_iter()
+            .map(|dm| (dm.level, dm.tag, dm.id))
+            .collect();
+        assert_eq!(moved, expected);
+
+        let v_lt = vec![Light::Level(0.25), Light::Off, Light::Level(2.0)].into_ortho();
+        let levels: Vec<f32> = v_lt
+            .iter()
+            .filter_map(|lt| match lt {
+                OrthoLight::Level(level) => Some(level.0),
+                OrthoLight::Off => None,
+            })
+            .collect();
+        assert_eq!(levels, vec![dequantize(quantize(0.25)), 1.0]);
+    }
 }
@@ -21,4 +21,4 @@
 /// }
 /// # }
 /// ```
-pub use crate::OrthoVec;
+pub use crate::{IntoOrtho, OrthoVec};
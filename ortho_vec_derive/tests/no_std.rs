@@ -0,0 +1,154 @@
+//! The generated code only needs `core`, and `alloc` for the columns that allocate.
+#![no_std]
+
+use ortho_vec_derive::{prelude::*, storage::ArrayVecStorage};
+
+#[derive(OrthoVec)]
+#[ortho(storage = "ArrayVecStorage<4>")]
+struct Sample {
+    channel: u8,
+    #[ortho(group = "reading")]
+    value: i16,
+    #[ortho(group = "reading")]
+    scale: u8,
+    #[ortho(bitpacked)]
+    clipped: bool,
+}
+
+#[derive(OrthoVec)]
+#[ortho(storage = "ArrayVecStorage<4>")]
+enum Frame {
+    Data(#[ortho(nullable)] Option<u32>),
+    Remote {
+        #[ortho(dictionary = "u8")]
+        id: u16,
+    },
+    Error,
+}
+
+#[derive(OrthoVec)]
+#[ortho(chunk = 4, storage = "ArrayVecStorage<2>")]
+struct Level {
+    left: i16,
+    right: i16,
+}
+
+#[test]
+fn test_without_alloc() {
+    let mut v_sm = OrthoVecSample::new();
+    for channel in 0..4 {
+        v_sm.push(Sample {
+            channel,
+            value: i16::from(channel) * -10,
+            scale: 2,
+            clipped: channel > 1,
+        });
+    }
+    assert_eq!(v_sm.remove(0).channel, 0);
+    v_sm.reverse();
+    for sm in v_sm.iter_mut() {
+        *sm.value *= i16::from(*sm.scale);
+    }
+    let total: i16 = v_sm.iter().map(|sm| *sm.value).sum();
+    assert_eq!(total, -120);
+    assert_eq!(v_sm.clipped_bits().ones().next(), Some(0));
+    assert_eq!(v_sm.clipped_bits().count_ones(), 2);
+    assert_eq!(v_sm.into_iter().next().map(|sm| sm.channel), Some(3));
+
+    let mut v_fr = OrthoVecFrame::new();
+    v_fr.push(Frame::Data(Some(7)));
+    v_fr.push(Frame::Data(None));
+    v_fr.push(Frame::Error);
+    v_fr.insert(1, Frame::Remote { id: 3 });
+    let ids = v_fr
+        .iter()
+        .filter_map(|fr| match fr {
+            OrthoFrame::Remote(remote) => Some(*remote.id),
+            _ => None,
+        })
+        .sum::<u16>();
+    assert_eq!(ids, 3);
+    let data = v_fr
+        .iter()
+        .filter_map(|fr| match fr {
+            OrthoFrame::Data(data) => Some(data.0.copied()),
+            _ => None,
+        })
+        .sum::<Option<u32>>();
+    assert_eq!(data, None);
+    assert!(matches!(v_fr.pop(), Some(Frame::Error)));
+
+    let mut v_lv = OrthoVecLevel::new();
+    for i in 0..6 {
+        v_lv.push(Level { left: i, right: -i });
+    }
+    v_lv.remove(1);
+    let mut peaks = v_lv.blocks().map(|block| {
+        block
+            .left
+            .iter()
+            .chain(block.right)
+            .map(|level| level.abs())
+            .max()
+    });
+    assert_eq!(peaks.next(), Some(Some(4)));
+    assert_eq!(peaks.next(), Some(Some(5)));
+}
+
+#[cfg(feature = "alloc")]
+mod with_alloc {
+    extern crate alloc;
+
+    use alloc::{vec, vec::Vec};
+    use ortho_vec_derive::prelude::*;
+
+    #[derive(OrthoVec)]
+    struct Packet {
+        #[ortho(encoding = "delta")]
+        id: u32,
+        #[ortho(encoding = "rle")]
+        len: u16,
+        #[ortho(column = Vec<u8>)]
+        port: u8,
+    }
+
+    #[derive(OrthoVec)]
+    #[ortho(layout = "single")]
+    struct Span(u32, u32);
+
+    #[test]
+    fn test_with_alloc() {
+        let mut v_pk = vec![
+            Packet {
+                id: 1,
+                len: 64,
+                port: 80,
+            },
+            Packet {
+                id: 2,
+                len: 128,
+                port: 22,
+            },
+        ]
+        .into_ortho();
+        v_pk.push(Packet {
+            id: 3,
+            len: 32,
+            port: 53,
+        });
+        let total: u16 = v_pk.iter().map(|pk| *pk.len).sum();
+        assert_eq!(total, 224);
+        assert_eq!(v_pk.id_encoded().get(1), Some(2));
+        for pk in v_pk.iter_mut() {
+            *pk.port += 1;
+        }
+        assert_eq!(v_pk.port_column()[..], [81, 23, 54]);
+        assert_eq!(v_pk.into_iter().last().map(|pk| pk.id), Some(3));
+
+        let mut v_sp = OrthoVecSpan::with_capacity(8);
+        v_sp.push(Span(0, 4));
+        v_sp.push(Span(4, 9));
+        let lens: u32 = v_sp.iter().map(|sp| *sp.1 - *sp.0).sum();
+        assert_eq!(lens, 9);
+    }
+}
@@ -0,0 +1,9 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+enum Direction {
+    Up,
+    Down,
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+#[ortho(align = 48)]
+struct Point {
+    x: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(align = "64")]
+struct Velocity {
+    x: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(align = 64, storage = "ortho_vec_derive::storage::BoxedStorage")]
+struct Sample {
+    value: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(align = 64, allocator = "A")]
+struct Body {
+    mass: f32,
+}
+
+fn main() {}
@@ -0,0 +1,44 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+#[ortho(allocator = "crate::Arena")]
+struct Point {
+    x: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(allocator = "A")]
+struct Pair<A> {
+    first: A,
+    second: A,
+}
+
+#[derive(OrthoVec)]
+#[ortho(allocator = "A", storage = "ortho_vec_derive::storage::BoxedStorage")]
+struct Sample {
+    value: f32,
+}
+
+#[derive(OrthoVec)]
+struct Velocity {
+    x: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(allocator = "A")]
+struct Body {
+    mass: f32,
+    #[ortho(flatten)]
+    velocity: Velocity,
+}
+
+#[derive(OrthoVec)]
+#[ortho(allocator = "A")]
+struct Tick {
+    #[ortho(bitpacked)]
+    late: bool,
+    #[ortho(column = Vec<u64>)]
+    at: u64,
+}
+
+fn main() {}
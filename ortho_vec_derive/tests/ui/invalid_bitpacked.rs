@@ -0,0 +1,46 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+struct Point {
+    #[ortho(bitpacked)]
+    x: u8,
+}
+
+#[derive(OrthoVec)]
+struct Reading {
+    #[ortho(bitpacked)]
+    valid: Option<bool>,
+    #[ortho(bitpacked)]
+    flags: [bool; 8],
+}
+
+#[derive(OrthoVec)]
+struct Unit {
+    #[ortho(bitpacked, bitpacked)]
+    alive: bool,
+}
+
+#[derive(OrthoVec)]
+struct Body {
+    #[ortho(group = "flags", bitpacked)]
+    alive: bool,
+    #[ortho(group = "flags")]
+    level: u8,
+}
+
+#[derive(OrthoVec)]
+struct Swarm {
+    #[ortho(flatten, bitpacked)]
+    body: Body,
+}
+
+#[derive(OrthoVec)]
+enum Signal {
+    Level(#[ortho(bitpacked)] u8),
+    Edge {
+        #[ortho(bitpacked)]
+        rising: bool,
+    },
+}
+
+fn main() {}
@@ -0,0 +1,43 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+#[ortho(chunk = 0)]
+struct Point {
+    x: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(chunk = "8")]
+struct Velocity {
+    x: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(chunk = 8, layout = "single")]
+struct Sample {
+    value: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(chunk = 8, align = 64, derive(Clone))]
+struct Body {
+    mass: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(chunk = 8)]
+struct Swarm {
+    #[ortho(flatten)]
+    body: Body,
+}
+
+#[derive(OrthoVec)]
+#[ortho(chunk = 8)]
+struct Boid {
+    #[ortho(bitpacked)]
+    leader: bool,
+    #[ortho(column = Vec<u32>)]
+    species: u32,
+}
+
+fn main() {}
@@ -0,0 +1,45 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+struct Point {
+    #[ortho(column = "Vec<")]
+    x: u32,
+}
+
+#[derive(OrthoVec)]
+struct Level {
+    #[ortho(column = 8)]
+    value: u8,
+}
+
+#[derive(OrthoVec)]
+struct Sample {
+    #[ortho(column = Vec<u64>, column = Vec<u64>)]
+    at: u64,
+}
+
+#[derive(OrthoVec)]
+struct Clock {
+    #[ortho(encoding = "delta", column = Vec<u64>)]
+    at: u64,
+    #[ortho(dictionary, column = Vec<u8>)]
+    zone: u8,
+    #[ortho(nullable, column = Vec<Option<u8>>)]
+    offset: Option<u8>,
+}
+
+#[derive(OrthoVec)]
+struct Body {
+    #[ortho(group = "inertia", column = Vec<u8>)]
+    material: u8,
+    #[ortho(group = "inertia")]
+    density: f32,
+}
+
+#[derive(OrthoVec)]
+struct Swarm {
+    #[ortho(flatten, column = Vec<Body>)]
+    body: Body,
+}
+
+fn main() {}
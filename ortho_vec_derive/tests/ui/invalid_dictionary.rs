@@ -0,0 +1,43 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+struct Point {
+    #[ortho(dictionary = "u64")]
+    x: u32,
+}
+
+#[derive(OrthoVec)]
+struct Label {
+    #[ortho(dictionary = u8)]
+    text: &'static str,
+}
+
+#[derive(OrthoVec)]
+struct Tag {
+    #[ortho(dictionary, dictionary = "u8")]
+    name: &'static str,
+}
+
+#[derive(OrthoVec)]
+struct Flag {
+    #[ortho(bitpacked, dictionary)]
+    raised: bool,
+    #[ortho(nullable, dictionary = "u8")]
+    owner: Option<u32>,
+}
+
+#[derive(OrthoVec)]
+struct Body {
+    #[ortho(group = "inertia", dictionary)]
+    material: &'static str,
+    #[ortho(group = "inertia")]
+    density: f32,
+}
+
+#[derive(OrthoVec)]
+struct Swarm {
+    #[ortho(flatten, dictionary)]
+    body: Body,
+}
+
+fn main() {}
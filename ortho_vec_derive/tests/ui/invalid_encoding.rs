@@ -0,0 +1,45 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+struct Point {
+    #[ortho(encoding = "lz4")]
+    x: u32,
+}
+
+#[derive(OrthoVec)]
+struct Level {
+    #[ortho(encoding)]
+    value: u8,
+}
+
+#[derive(OrthoVec)]
+struct Sample {
+    #[ortho(encoding = "rle", encoding = "delta")]
+    at: u64,
+}
+
+#[derive(OrthoVec)]
+struct Clock {
+    #[ortho(dictionary, encoding = "delta")]
+    at: u64,
+    #[ortho(nullable, encoding = "rle")]
+    zone: Option<u8>,
+    #[ortho(bitpacked, encoding = "rle")]
+    dst: bool,
+}
+
+#[derive(OrthoVec)]
+struct Body {
+    #[ortho(group = "inertia", encoding = "rle")]
+    material: u8,
+    #[ortho(group = "inertia")]
+    density: f32,
+}
+
+#[derive(OrthoVec)]
+struct Swarm {
+    #[ortho(flatten, encoding = "rle")]
+    body: Body,
+}
+
+fn main() {}
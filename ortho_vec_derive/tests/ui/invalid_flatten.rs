@@ -0,0 +1,29 @@
+use std::marker::PhantomData;
+
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+struct Transform {
+    pos: f32,
+}
+
+#[derive(OrthoVec)]
+struct Tagged<T> {
+    value: u32,
+    #[ortho(flatten)]
+    marker: PhantomData<T>,
+}
+
+#[derive(OrthoVec)]
+struct Body {
+    #[ortho(flatten, group = "cold")]
+    transform: Transform,
+}
+
+#[derive(OrthoVec)]
+struct Pivot {
+    #[ortho(flatten, flatten)]
+    origin: Transform,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+#[ortho(derive = "Debug")]
+struct Point {
+    x: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(view_derive(Debug = true))]
+struct Segment {
+    start: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(attr("must_use"))]
+struct Circle {
+    radius: f32,
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+use std::marker::PhantomData;
+
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+struct Tagged<T> {
+    value: u32,
+    #[ortho(group = "meta")]
+    marker: PhantomData<T>,
+}
+
+#[derive(OrthoVec)]
+struct Body {
+    mass: f32,
+    #[ortho(group = "mass")]
+    name: String,
+    #[ortho(group = "__len")]
+    id: u64,
+}
+
+#[derive(OrthoVec)]
+struct Label {
+    #[ortho(group = "cold", group = "hot")]
+    text: String,
+    #[ortho(group = "not a name")]
+    size: u8,
+}
+
+fn main() {}
@@ -0,0 +1,38 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+#[ortho(layout = "packed")]
+struct Point {
+    x: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(layout = "single", storage = "ortho_vec_derive::storage::BoxedStorage")]
+#[ortho(derive(Clone))]
+struct Sample {
+    value: f32,
+}
+
+#[derive(OrthoVec)]
+struct Velocity {
+    x: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(layout = "single")]
+struct Body {
+    mass: f32,
+    #[ortho(flatten)]
+    velocity: Velocity,
+}
+
+#[derive(OrthoVec)]
+#[ortho(layout = "single")]
+struct Flags {
+    #[ortho(bitpacked)]
+    valid: bool,
+    #[ortho(column = Vec<u64>)]
+    at: u64,
+}
+
+fn main() {}
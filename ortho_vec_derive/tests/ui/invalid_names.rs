@@ -0,0 +1,21 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+#[ortho(name = "Points", name = "PointVec")]
+struct Point {
+    x: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(view = "Segment Ref")]
+struct Segment {
+    start: f32,
+}
+
+#[derive(OrthoVec)]
+#[ortho(tag = "LineKind")]
+struct Line {
+    end: f32,
+}
+
+fn main() {}
@@ -0,0 +1,44 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+struct Point {
+    #[ortho(nullable)]
+    x: f32,
+}
+
+#[derive(OrthoVec)]
+struct Reading {
+    #[ortho(nullable)]
+    value: Result<f32, ()>,
+}
+
+#[derive(OrthoVec)]
+struct Gauge {
+    #[ortho(nullable, nullable)]
+    value: Option<f32>,
+}
+
+#[derive(OrthoVec)]
+struct Body {
+    #[ortho(group = "inertia", nullable)]
+    mass: Option<f32>,
+    #[ortho(group = "inertia")]
+    density: f32,
+}
+
+#[derive(OrthoVec)]
+struct Swarm {
+    #[ortho(flatten, nullable)]
+    body: Option<Body>,
+}
+
+#[derive(OrthoVec)]
+enum Event {
+    Reading(#[ortho(nullable)] f32),
+    Gap {
+        #[ortho(nullable)]
+        since: Option<u64>,
+    },
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+#[ortho(storage = "SmallVecStorage<8")]
+struct Point {
+    x: f32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+#[ortho(vis = "public")]
+struct Point {
+    x: f32,
+    y: f32,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+struct Name<'ortho> {
+    first: &'ortho str,
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+#[ortho(layout = "single")]
+struct Particle {
+    mass: f32,
+    charge: i8,
+}
+
+#[derive(OrthoVec)]
+#[ortho(chunk = 4)]
+struct Spark {
+    heat: f32,
+}
+
+// The module of the struct can reach the private fields, but not change the length of the
+// columns without `unsafe`
+fn stretch(particles: &mut OrthoVecParticle, sparks: &mut OrthoVecSpark) {
+    particles.__columns.set_len(1000);
+    sparks.__blocks.set_len(1000);
+    sparks.__blocks.storage_mut().clear();
+}
+
+fn main() {}
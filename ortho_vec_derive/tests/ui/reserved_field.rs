@@ -0,0 +1,15 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+struct Buffer {
+    data: u8,
+    __len: usize,
+}
+
+#[derive(OrthoVec)]
+enum Shape {
+    Segment { __marker: u8 },
+    Empty,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+union IntOrFloat {
+    i: u32,
+    f: f32,
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+#[ortho(columns = 2)]
+struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[derive(OrthoVec)]
+struct Segment {
+    #[ortho(skip)]
+    start: f32,
+    end: f32,
+}
+
+#[derive(OrthoVec)]
+enum Shape {
+    #[ortho(vis = "pub")]
+    Circle { radius: f32 },
+    Square(#[ortho(skip)] f32),
+}
+
+fn main() {}
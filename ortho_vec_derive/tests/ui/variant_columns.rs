@@ -0,0 +1,13 @@
+use ortho_vec_derive::prelude::*;
+
+#[derive(OrthoVec)]
+#[allow(non_camel_case_types)]
+enum Response {
+    HttpError(u16),
+    Http_error(u16),
+    BoDy { len: usize },
+    Bo_dy { len: usize },
+    Empty,
+}
+
+fn main() {}
@@ -0,0 +1,885 @@
+//! Bit-packed columns of `bool`s, which the fields given `#[ortho(bitpacked)]` are stored in.
+//!
+//! The bits are packed in words of 64, the bit at `index` being bit `index % 64` of the word at
+//! `index / 64`, and the bits of the last word past the length are always 0, so the bulk
+//! operations go over whole words at once.
+//! As single bits can't be borrowed, the views of a bit-packed field hold a [`BitRef`] or a
+//! [`BitMut`] instead of a reference, and the whole column is reached through [`Bits`] and
+//! [`BitsMut`].
+
+use core::{cell::Cell, fmt, iter::FusedIterator, ops::Deref};
+
+use crate::storage::Column;
+
+/// The number of bits in a word.
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Returns the index of the word holding the bit at `index`, and the mask of the bit in it.
+const fn locate(index: usize) -> (usize, u64) {
+    (index / WORD_BITS, 1 << (index % WORD_BITS))
+}
+
+fn get_bit(words: &[u64], index: usize) -> bool {
+    let (word, mask) = locate(index);
+    words[word] & mask != 0
+}
+
+fn set_bit(words: &mut [u64], index: usize, value: bool) {
+    let (word, mask) = locate(index);
+
+    if value {
+        words[word] |= mask;
+    } else {
+        words[word] &= !mask;
+    }
+}
+
+/// A column of `bool`s packed in words of 64 bits, held in a storage column of `u64`s.
+///
+/// Has the methods of `Vec` the ortho-`Vec` changes its columns with, the elements themselves are
+/// reached through [`bits`](BitVec::bits) and [`bits_mut`](BitVec::bits_mut).
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitVec<C> {
+    words: C,
+    len: usize,
+}
+
+impl<C: Column<u64>> BitVec<C> {
+    /// Constructs a new, empty column.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            words: C::new(),
+            len: 0,
+        }
+    }
+
+    /// Constructs a new, empty column with room for at least `capacity` bits, if its storage can
+    /// make room for them.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            words: C::with_capacity((capacity + WORD_BITS - 1) / WORD_BITS),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of bits.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no bits.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the bits of the column.
+    #[must_use]
+    pub fn bits(&self) -> Bits<'_> {
+        Bits {
+            words: &self.words,
+            len: self.len,
+        }
+    }
+
+    /// Returns the bits of the column, to be changed in place.
+    #[must_use]
+    pub fn bits_mut(&mut self) -> BitsMut<'_> {
+        BitsMut {
+            words: &mut self.words,
+            len: self.len,
+        }
+    }
+
+    /// Returns an iterator over references to the bits.
+    pub fn iter(&self) -> Iter<'_> {
+        self.bits().iter()
+    }
+
+    /// Returns an iterator over mutable references to the bits.
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        self.bits_mut().into_iter()
+    }
+
+    /// Appends a bit to the back.
+    pub fn push(&mut self, value: bool) {
+        if self.len % WORD_BITS == 0 {
+            self.words.push(0);
+        }
+
+        set_bit(&mut self.words, self.len, value);
+        self.len += 1;
+    }
+
+    /// Removes the last bit and returns it, or `None` if there are no bits.
+    pub fn pop(&mut self) -> Option<bool> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        let value = get_bit(&self.words, self.len);
+        set_bit(&mut self.words, self.len, false);
+
+        if self.len % WORD_BITS == 0 {
+            self.words.pop();
+        }
+
+        Some(value)
+    }
+
+    /// Inserts a bit at `index`, moving the bits after it up by one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length.
+    pub fn insert(&mut self, index: usize, value: bool) {
+        let len = self.len;
+        assert!(
+            index <= len,
+            "insertion index (is {index}) should be <= len (is {len})"
+        );
+
+        if len % WORD_BITS == 0 {
+            self.words.push(0);
+        }
+
+        // Every word from the one holding `index` moves up by a bit, carrying its top bit into
+        // the next word
+        let (first, mask) = locate(index);
+        let words = &mut self.words[first..];
+        let below = mask - 1;
+        let mut carry = words[0] >> (WORD_BITS - 1);
+        words[0] = (words[0] & below) | ((words[0] & !below) << 1);
+
+        for word in &mut words[1..] {
+            let next_carry = *word >> (WORD_BITS - 1);
+            *word = (*word << 1) | carry;
+            carry = next_carry;
+        }
+
+        set_bit(&mut self.words, index, value);
+        self.len += 1;
+    }
+
+    /// Removes the bit at `index` and returns it, moving the bits after it down by one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> bool {
+        let len = self.len;
+        assert!(
+            index < len,
+            "remove index (is {index}) should be < len (is {len})"
+        );
+
+        let value = get_bit(&self.words, index);
+
+        // Every word from the one holding `index` moves down by a bit, taking the bottom bit of
+        // the next word as its top bit
+        let (first, mask) = locate(index);
+        let words = &mut self.words[first..];
+        let below = mask - 1;
+
+        for word_index in 0..words.len() {
+            let carry = words.get(word_index + 1).map_or(0, |next| next & 1);
+            let word = words[word_index];
+            let moved = if word_index == 0 {
+                (word & below) | ((word >> 1) & !below)
+            } else {
+                word >> 1
+            };
+            words[word_index] = moved | (carry << (WORD_BITS - 1));
+        }
+
+        self.len -= 1;
+        if self.len % WORD_BITS == 0 {
+            self.words.pop();
+        }
+
+        value
+    }
+
+    /// Removes the bit at `index` and returns it, moving the last bit into its place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> bool {
+        let len = self.len;
+        assert!(
+            index < len,
+            "swap_remove index (is {index}) should be < len (is {len})"
+        );
+
+        let value = get_bit(&self.words, index);
+        let last = get_bit(&self.words, len - 1);
+        set_bit(&mut self.words, index, last);
+        self.pop();
+
+        value
+    }
+
+    /// Removes all bits.
+    pub fn clear(&mut self) {
+        self.words.clear();
+        self.len = 0;
+    }
+
+    /// Reverses the order of the bits.
+    pub fn reverse(&mut self) {
+        for index in 0..self.len / 2 {
+            let other = self.len - 1 - index;
+            let (value, other_value) = (get_bit(&self.words, index), get_bit(&self.words, other));
+            set_bit(&mut self.words, index, other_value);
+            set_bit(&mut self.words, other, value);
+        }
+    }
+
+    /// Shrinks the capacity as much as possible, if the storage has any to shrink.
+    pub fn shrink_to_fit(&mut self) {
+        self.words.shrink_to_fit();
+    }
+}
+
+impl<C: Column<u64>> From<Bits<'_>> for BitVec<C> {
+    fn from(bits: Bits<'_>) -> Self {
+        let mut words = C::with_capacity(bits.words.len());
+        for &word in bits.words {
+            words.push(word);
+        }
+
+        Self {
+            words,
+            len: bits.len,
+        }
+    }
+}
+
+impl<C: Column<u64>> fmt::Debug for BitVec<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.bits().fmt(f)
+    }
+}
+
+impl<'a, C: Column<u64>> IntoIterator for &'a BitVec<C> {
+    type Item = BitRef<'a>;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a, C: Column<u64>> IntoIterator for &'a mut BitVec<C> {
+    type Item = BitMut<'a>;
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> IterMut<'a> {
+        self.iter_mut()
+    }
+}
+
+impl<C: Column<u64>> IntoIterator for BitVec<C> {
+    type Item = bool;
+    type IntoIter = IntoIter<C>;
+
+    fn into_iter(self) -> IntoIter<C> {
+        IntoIter {
+            bits: self,
+            index: 0,
+        }
+    }
+}
+
+/// An iterator moving the bits out of a [`BitVec`].
+pub struct IntoIter<C> {
+    bits: BitVec<C>,
+    index: usize,
+}
+
+impl<C: Column<u64>> Iterator for IntoIter<C> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        let value = self.bits.bits().get(self.index)?;
+        self.index += 1;
+
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.bits.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<C: Column<u64>> ExactSizeIterator for IntoIter<C> {}
+
+impl<C: Column<u64>> FusedIterator for IntoIter<C> {}
+
+/// The bits of a bit-packed column.
+#[derive(Clone, Copy)]
+pub struct Bits<'a> {
+    words: &'a [u64],
+    len: usize,
+}
+
+impl<'a> Bits<'a> {
+    /// Returns the number of bits.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no bits.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the bit at `index`, or `None` if it's out of bounds.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<bool> {
+        (index < self.len).then(|| get_bit(self.words, index))
+    }
+
+    /// Returns the words the bits are packed in, the bits past the length being 0.
+    #[must_use]
+    pub const fn words(&self) -> &'a [u64] {
+        self.words
+    }
+
+    /// Returns the number of bits set to `true`.
+    #[must_use]
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns the number of bits set to `false`.
+    #[must_use]
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    /// Returns an iterator over the indices of the bits set to `true`, in order.
+    #[must_use]
+    pub fn ones(&self) -> Ones<'a> {
+        let (current, rest) = self
+            .words
+            .split_first()
+            .map_or((0, &[][..]), |(&first, rest)| (first, rest));
+
+        Ones {
+            words: rest.iter(),
+            current,
+            base: 0,
+        }
+    }
+
+    /// Returns an iterator over references to the bits.
+    #[must_use]
+    pub const fn iter(&self) -> Iter<'a> {
+        Iter {
+            words: self.words,
+            index: 0,
+            end: self.len,
+        }
+    }
+}
+
+impl<'a> IntoIterator for Bits<'a> {
+    type Item = BitRef<'a>;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &Bits<'a> {
+    type Item = BitRef<'a>;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl PartialEq for Bits<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.words == other.words
+    }
+}
+
+impl Eq for Bits<'_> {}
+
+impl fmt::Debug for Bits<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// The bits of a bit-packed column, which can be changed in place.
+pub struct BitsMut<'a> {
+    words: &'a mut [u64],
+    len: usize,
+}
+
+impl BitsMut<'_> {
+    /// Returns the bits, borrowed from these.
+    #[must_use]
+    pub const fn as_bits(&self) -> Bits<'_> {
+        Bits {
+            words: self.words,
+            len: self.len,
+        }
+    }
+
+    /// Sets the bit at `index` to `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: bool) {
+        let len = self.len;
+        assert!(
+            index < len,
+            "index out of bounds: the len is {len} but the index is {index}"
+        );
+
+        set_bit(self.words, index, value);
+    }
+
+    /// Sets every bit to `value`.
+    pub fn fill(&mut self, value: bool) {
+        self.words.fill(if value { u64::MAX } else { 0 });
+
+        // The bits past the length have to stay 0
+        if let (Some(last), tail @ 1..) = (self.words.last_mut(), self.len % WORD_BITS) {
+            *last &= (1 << tail) - 1;
+        }
+    }
+
+    /// Keeps the bits set only where they are set in `other` too, a word at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` has a different length.
+    pub fn and(&mut self, other: Bits<'_>) {
+        self.combine(other, |word, other| word & other);
+    }
+
+    /// Sets the bits that are set in `other`, a word at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` has a different length.
+    pub fn or(&mut self, other: Bits<'_>) {
+        self.combine(other, |word, other| word | other);
+    }
+
+    fn combine(&mut self, other: Bits<'_>, op: impl Fn(u64, u64) -> u64) {
+        assert_eq!(
+            self.len, other.len,
+            "the bits can only be combined with bits of the same length"
+        );
+
+        for (word, &other) in self.words.iter_mut().zip(other.words) {
+            *word = op(*word, other);
+        }
+    }
+
+    /// Returns an iterator over mutable references to the bits.
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        BitsMut {
+            words: self.words,
+            len: self.len,
+        }
+        .into_iter()
+    }
+}
+
+impl<'a> IntoIterator for BitsMut<'a> {
+    type Item = BitMut<'a>;
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> IterMut<'a> {
+        IterMut {
+            words: Cell::from_mut(self.words).as_slice_of_cells(),
+            index: 0,
+            end: self.len,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a mut BitsMut<'_> {
+    type Item = BitMut<'a>;
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> IterMut<'a> {
+        self.iter_mut()
+    }
+}
+
+impl fmt::Debug for BitsMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_bits().fmt(f)
+    }
+}
+
+/// A reference to a bit of a bit-packed column, which derefs to its value.
+#[derive(Clone, Copy)]
+pub struct BitRef<'a> {
+    word: &'a u64,
+    mask: u64,
+}
+
+impl BitRef<'_> {
+    /// Returns the value of the bit.
+    #[must_use]
+    pub const fn get(&self) -> bool {
+        *self.word & self.mask != 0
+    }
+}
+
+impl Deref for BitRef<'_> {
+    type Target = bool;
+
+    fn deref(&self) -> &bool {
+        if self.get() {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+impl fmt::Debug for BitRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+/// A mutable reference to a bit of a bit-packed column, which derefs to its value and is changed
+/// with [`set`](BitMut::set).
+///
+/// The bits of a word are changed through a shared `Cell`, so it can't be sent to another thread.
+pub struct BitMut<'a> {
+    word: &'a Cell<u64>,
+    mask: u64,
+}
+
+impl BitMut<'_> {
+    /// Returns the value of the bit.
+    #[must_use]
+    pub fn get(&self) -> bool {
+        self.word.get() & self.mask != 0
+    }
+
+    /// Sets the bit to `value`.
+    pub fn set(&mut self, value: bool) {
+        let word = self.word.get();
+        self.word.set(if value {
+            word | self.mask
+        } else {
+            word & !self.mask
+        });
+    }
+
+    /// Sets the bit to `value`, and returns its previous value.
+    pub fn replace(&mut self, value: bool) -> bool {
+        let previous = self.get();
+        self.set(value);
+
+        previous
+    }
+}
+
+impl Deref for BitMut<'_> {
+    type Target = bool;
+
+    fn deref(&self) -> &bool {
+        if self.get() {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+impl fmt::Debug for BitMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+/// An iterator over references to the bits of a bit-packed column.
+#[derive(Clone)]
+pub struct Iter<'a> {
+    words: &'a [u64],
+    index: usize,
+    end: usize,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = BitRef<'a>;
+
+    fn next(&mut self) -> Option<BitRef<'a>> {
+        if self.index >= self.end {
+            return None;
+        }
+
+        let (word, mask) = locate(self.index);
+        self.index += 1;
+
+        Some(BitRef {
+            word: &self.words[word],
+            mask,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index >= self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        let (word, mask) = locate(self.end);
+
+        Some(BitRef {
+            word: &self.words[word],
+            mask,
+        })
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+/// An iterator over mutable references to the bits of a bit-packed column.
+pub struct IterMut<'a> {
+    words: &'a [Cell<u64>],
+    index: usize,
+    end: usize,
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = BitMut<'a>;
+
+    fn next(&mut self) -> Option<BitMut<'a>> {
+        if self.index >= self.end {
+            return None;
+        }
+
+        let (word, mask) = locate(self.index);
+        self.index += 1;
+
+        Some(BitMut {
+            word: &self.words[word],
+            mask,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for IterMut<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index >= self.end {
+            return None;
+        }
+
+        self.end -= 1;
+        let (word, mask) = locate(self.end);
+
+        Some(BitMut {
+            word: &self.words[word],
+            mask,
+        })
+    }
+}
+
+impl ExactSizeIterator for IterMut<'_> {}
+
+impl FusedIterator for IterMut<'_> {}
+
+/// An iterator over the indices of the bits set to `true`, skipping the words without any.
+#[derive(Clone)]
+pub struct Ones<'a> {
+    words: core::slice::Iter<'a, u64>,
+    /// The bits of the current word left to go over.
+    current: u64,
+    /// The index of the first bit of the current word.
+    base: usize,
+}
+
+impl Iterator for Ones<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.current = *self.words.next()?;
+            self.base += WORD_BITS;
+        }
+
+        let bit = self.current.trailing_zeros() as usize;
+        // Clears the lowest set bit
+        self.current &= self.current - 1;
+
+        Some(self.base + bit)
+    }
+}
+
+impl FusedIterator for Ones<'_> {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{BitVec, WORD_BITS};
+
+    type Bools = BitVec<Vec<u64>>;
+
+    const fn pattern(index: usize) -> bool {
+        index % 3 != 1
+    }
+
+    fn filled(len: usize) -> (Bools, Vec<bool>) {
+        let expected = (0..len).map(pattern).collect::<Vec<_>>();
+        let mut bits = Bools::new();
+        for &value in &expected {
+            bits.push(value);
+        }
+
+        (bits, expected)
+    }
+
+    /// Checks the bits against `expected`, and that they take as many words as they need with
+    /// the bits past the length left 0.
+    fn assert_bits(bits: &Bools, expected: &[bool]) {
+        let bits = bits.bits();
+        assert_eq!(bits.iter().map(|bit| *bit).collect::<Vec<_>>(), expected);
+        assert_eq!(
+            bits.words().len(),
+            (expected.len() + WORD_BITS - 1) / WORD_BITS
+        );
+        if let (Some(last), tail @ 1..) = (bits.words().last(), expected.len() % WORD_BITS) {
+            assert_eq!(last >> tail, 0);
+        }
+
+        assert_eq!(
+            bits.count_ones(),
+            expected.iter().filter(|&&value| value).count()
+        );
+        assert!(bits.ones().eq(expected
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &value)| value.then_some(index))));
+    }
+
+    #[test]
+    fn test_push_and_pop_at_word_edges() {
+        for len in [63, 64, 65] {
+            let (mut bits, mut expected) = filled(len);
+            assert_bits(&bits, &expected);
+
+            while let Some(value) = bits.pop() {
+                assert_eq!(Some(value), expected.pop());
+                assert_bits(&bits, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_insert_and_remove_at_word_edges() {
+        for len in [63, 64, 65] {
+            for index in [0, 1, 62, 63, 64, len] {
+                let index = index.min(len);
+                let (mut bits, mut expected) = filled(len);
+                bits.insert(index, true);
+                expected.insert(index, true);
+                assert_bits(&bits, &expected);
+
+                // The top bit of every word is carried back down
+                assert_eq!(bits.remove(index), expected.remove(index));
+                assert_bits(&bits, &expected);
+                let last = len - 1;
+                assert_eq!(
+                    bits.remove(last.min(index)),
+                    expected.remove(last.min(index))
+                );
+                assert_bits(&bits, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_swap_remove() {
+        for len in [1, 63, 64, 65] {
+            let (mut bits, mut expected) = filled(len);
+
+            // The last bit is moved into its own place
+            assert_eq!(bits.swap_remove(len - 1), expected.swap_remove(len - 1));
+            assert_bits(&bits, &expected);
+
+            if let Some(last) = expected.len().checked_sub(1) {
+                bits.bits_mut().set(last, true);
+                expected[last] = true;
+                assert_eq!(bits.swap_remove(0), expected.swap_remove(0));
+                assert_bits(&bits, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reverse() {
+        for len in [0, 1, 2, 63, 64, 65, 130] {
+            let (mut bits, mut expected) = filled(len);
+            bits.reverse();
+            expected.reverse();
+            assert_bits(&bits, &expected);
+        }
+    }
+
+    #[test]
+    fn test_bulk_operations() {
+        for len in [0, 63, 64, 65] {
+            let (mut bits, mut expected) = filled(len);
+
+            bits.bits_mut().fill(true);
+            expected.fill(true);
+            assert_bits(&bits, &expected);
+
+            let (other, other_expected) = filled(len);
+            bits.bits_mut().and(other.bits());
+            assert_bits(&bits, &other_expected);
+
+            bits.bits_mut().fill(false);
+            expected.fill(false);
+            assert_bits(&bits, &expected);
+
+            bits.bits_mut().or(other.bits());
+            assert_bits(&bits, &other_expected);
+            assert_eq!(Bools::from(other.bits()), other);
+        }
+    }
+}
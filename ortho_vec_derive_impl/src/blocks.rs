@@ -0,0 +1,654 @@
+//! The blocks of an ortho-`Vec` with `#[ortho(chunk = N)]`, each holding `N` elements of every
+//! column.
+//!
+//! Every column of a block is an array of `N` elements which may be uninitialized, reached through
+//! the field of the block holding it, and the elements of a column are numbered across the
+//! blocks: the element at `index` is at `index % N` in the block at `index / N`.
+//! The blocks and the length are kept in a [`Blocks`], which only unsafe code can change, and the
+//! ortho-`Vec` moves the elements in and out of them with these functions.
+//!
+//! Not meant to be used directly, it may change between versions.
+
+use core::{
+    mem::MaybeUninit,
+    ops::{Deref, DerefMut, Range},
+    ptr, slice,
+};
+
+/// Returns the uninitialized elements of a column of a new block.
+#[must_use]
+pub const fn uninit<T, const N: usize>() -> [MaybeUninit<T>; N] {
+    // SAFETY: an array of `MaybeUninit`s is valid uninitialized
+    unsafe { MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init() }
+}
+
+/// Returns the number of blocks of `N` elements that `len` elements take.
+#[must_use]
+pub const fn block_count<const N: usize>(len: usize) -> usize {
+    if len % N == 0 {
+        len / N
+    } else {
+        len / N + 1
+    }
+}
+
+/// Returns `elements` as a slice of initialized elements.
+///
+/// # Safety
+///
+/// All of `elements` have to be initialized.
+#[must_use]
+pub const unsafe fn slice<T>(elements: &[MaybeUninit<T>]) -> &[T] {
+    // SAFETY: `MaybeUninit<T>` has the layout of `T`, and the caller makes sure the elements are
+    // initialized
+    unsafe { &*(elements as *const [MaybeUninit<T>] as *const [T]) }
+}
+
+/// Returns `elements` as a mutable slice of initialized elements.
+///
+/// # Safety
+///
+/// All of `elements` have to be initialized.
+#[must_use]
+pub const unsafe fn slice_mut<T>(elements: &mut [MaybeUninit<T>]) -> &mut [T] {
+    // SAFETY: `MaybeUninit<T>` has the layout of `T`, and the caller makes sure the elements are
+    // initialized
+    unsafe { &mut *(elements as *mut [MaybeUninit<T>] as *mut [T]) }
+}
+
+/// Inserts `element` at `index` in a column of `len` elements, moving the elements after it up
+/// by one, the last element of every full block into the start of the next one.
+///
+/// # Safety
+///
+/// The first `len` elements of the column have to be initialized, `index` has to be at most
+/// `len`, and `blocks` has to have room for `len + 1` elements.
+pub unsafe fn insert<B, T, const N: usize>(
+    blocks: &mut [B],
+    column: impl Fn(&mut B) -> &mut [MaybeUninit<T>; N],
+    len: usize,
+    index: usize,
+    element: T,
+) {
+    let mut carried = element;
+    let mut offset = index % N;
+
+    for (block_index, block) in blocks
+        .iter_mut()
+        .enumerate()
+        .take(len / N + 1)
+        .skip(index / N)
+    {
+        let elements = column(block);
+        let block_len = (len - block_index * N).min(N);
+
+        // SAFETY: The block is full, so its last element is initialized, and it's moved out
+        // before it's written over
+        let last = (block_len == N).then(|| unsafe { elements[N - 1].assume_init_read() });
+
+        let base = elements.as_mut_ptr();
+        // SAFETY: The elements from `offset` up to the block's length (or to its last element)
+        // are initialized, and moved up by one within the block
+        unsafe {
+            ptr::copy(
+                base.add(offset),
+                base.add(offset + 1),
+                block_len.min(N - 1) - offset,
+            );
+        }
+        elements[offset].write(carried);
+
+        let Some(last) = last else {
+            return;
+        };
+        carried = last;
+        offset = 0;
+    }
+}
+
+/// Removes the element at `index` from a column of `len` elements and returns it, moving the
+/// elements after it down by one, the first element of every block after it into the end of the
+/// block before.
+///
+/// # Safety
+///
+/// The first `len` elements of the column have to be initialized, and `index` has to be less than
+/// `len`.
+pub unsafe fn remove<B, T, const N: usize>(
+    blocks: &mut [B],
+    column: impl Fn(&mut B) -> &mut [MaybeUninit<T>; N],
+    len: usize,
+    index: usize,
+) -> T {
+    // SAFETY: The element at `index` is initialized, and written over by the ones after it
+    let removed = unsafe { column(&mut blocks[index / N])[index % N].assume_init_read() };
+    let last_block = (len - 1) / N;
+    let mut offset = index % N;
+
+    for block_index in index / N..=last_block {
+        let block_len = (len - block_index * N).min(N);
+        let base = column(&mut blocks[block_index]).as_mut_ptr();
+
+        // SAFETY: The elements after `offset` up to the block's length are initialized, and moved
+        // down by one within the block
+        unsafe {
+            ptr::copy(
+                base.add(offset + 1),
+                base.add(offset),
+                block_len - offset - 1,
+            );
+        }
+
+        if block_index < last_block {
+            // SAFETY: The next block holds at least one initialized element, which is moved out
+            // before the elements after it move into its place
+            let first = unsafe { column(&mut blocks[block_index + 1])[0].assume_init_read() };
+            column(&mut blocks[block_index])[N - 1].write(first);
+        }
+        offset = 0;
+    }
+
+    removed
+}
+
+/// Removes the element at `index` from a column of `len` elements and returns it, moving the last
+/// element into its place.
+///
+/// # Safety
+///
+/// The first `len` elements of the column have to be initialized, and `index` has to be less than
+/// `len`.
+pub unsafe fn swap_remove<B, T, const N: usize>(
+    blocks: &mut [B],
+    column: impl Fn(&mut B) -> &mut [MaybeUninit<T>; N],
+    len: usize,
+    index: usize,
+) -> T {
+    swap(blocks, &column, index, len - 1);
+
+    // SAFETY: The last element is initialized, and is the removed one after the swap
+    unsafe { column(&mut blocks[(len - 1) / N])[(len - 1) % N].assume_init_read() }
+}
+
+/// Swaps the elements at `a` and `b` of a column, which don't have to be initialized.
+pub fn swap<B, T, const N: usize>(
+    blocks: &mut [B],
+    column: impl Fn(&mut B) -> &mut [MaybeUninit<T>; N],
+    a: usize,
+    b: usize,
+) {
+    let (low, high) = (a.min(b), a.max(b));
+
+    if low / N == high / N {
+        column(&mut blocks[low / N]).swap(low % N, high % N);
+    } else {
+        let (head, tail) = blocks.split_at_mut(high / N);
+        core::mem::swap(
+            &mut column(&mut head[low / N])[low % N],
+            &mut column(&mut tail[0])[high % N],
+        );
+    }
+}
+
+/// Reverses the order of the first `len` elements of a column.
+pub fn reverse<B, T, const N: usize>(
+    blocks: &mut [B],
+    column: impl Fn(&mut B) -> &mut [MaybeUninit<T>; N],
+    len: usize,
+) {
+    for index in 0..len / 2 {
+        swap(blocks, &column, index, len - 1 - index);
+    }
+}
+
+/// Drops the elements of a column from `start` up to `end`.
+///
+/// # Safety
+///
+/// The elements from `start` up to `end` have to be initialized, and aren't used again.
+pub unsafe fn drop_range<B, T, const N: usize>(
+    blocks: &mut [B],
+    column: impl Fn(&mut B) -> &mut [MaybeUninit<T>; N],
+    start: usize,
+    end: usize,
+) {
+    for (block_index, block) in blocks
+        .iter_mut()
+        .enumerate()
+        .take(block_count::<N>(end))
+        .skip(start / N)
+    {
+        let elements = column(block);
+        let from = start.saturating_sub(block_index * N);
+        let to = (end - block_index * N).min(N);
+
+        // SAFETY: The caller makes sure the elements in the range are initialized
+        unsafe { ptr::drop_in_place(slice_mut(&mut elements[from..to])) };
+    }
+}
+
+/// The blocks of `N` elements of an ortho-`Vec`, kept in the storage `S`, along with the number
+/// of elements in them.
+///
+/// There are blocks for at least the first `len` elements, which are initialized in every column,
+/// and only the unsafe methods can break that.
+pub struct Blocks<S, const N: usize> {
+    blocks: S,
+    len: usize,
+}
+
+impl<S, const N: usize> Blocks<S, N> {
+    /// Keeps the elements in `blocks`, starting with none of them.
+    #[must_use]
+    pub const fn new(blocks: S) -> Self {
+        Self { blocks, len: 0 }
+    }
+
+    /// Returns the number of elements.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no elements.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Sets the number of elements.
+    ///
+    /// # Safety
+    ///
+    /// There have to be blocks for at least `len` elements, and the first `len` elements of
+    /// every column have to be initialized.
+    pub unsafe fn set_len(&mut self, len: usize) {
+        self.len = len;
+    }
+
+    /// Returns the storage of the blocks, mutably.
+    ///
+    /// # Safety
+    ///
+    /// The storage has to be left with blocks for the first `len` elements, which have to be left
+    /// initialized.
+    pub unsafe fn storage_mut(&mut self) -> &mut S {
+        &mut self.blocks
+    }
+}
+
+impl<B, S: Deref<Target = [B]>, const N: usize> Blocks<S, N> {
+    /// Returns all of the blocks, including the ones past the elements.
+    #[must_use]
+    pub fn blocks(&self) -> &[B] {
+        &self.blocks
+    }
+
+    /// Returns an iterator over the blocks holding the elements, along with the number of
+    /// elements in each.
+    #[must_use]
+    pub fn filled(&self) -> Filled<slice::Iter<'_, B>, N> {
+        Filled {
+            blocks: self.blocks[..block_count::<N>(self.len)].iter(),
+            len: self.len,
+        }
+    }
+}
+
+impl<B, S: DerefMut<Target = [B]>, const N: usize> Blocks<S, N> {
+    /// Returns all of the blocks mutably, including the ones past the elements.
+    ///
+    /// # Safety
+    ///
+    /// The first `len` elements of every column have to be left initialized.
+    pub unsafe fn blocks_mut(&mut self) -> &mut [B] {
+        &mut self.blocks
+    }
+
+    /// Returns an iterator over the blocks holding the elements mutably, along with the number
+    /// of elements in each.
+    ///
+    /// # Safety
+    ///
+    /// The elements in the blocks have to be left initialized.
+    pub unsafe fn filled_mut(&mut self) -> Filled<slice::IterMut<'_, B>, N> {
+        Filled {
+            blocks: self.blocks[..block_count::<N>(self.len)].iter_mut(),
+            len: self.len,
+        }
+    }
+}
+
+/// An iterator over the blocks of `N` elements holding the elements of an ortho-`Vec`, along with
+/// the number of elements initialized in each, which is `N` for every block but the last one.
+pub struct Filled<I, const N: usize> {
+    blocks: I,
+    /// The number of elements in the blocks left.
+    len: usize,
+}
+
+impl<I: ExactSizeIterator, const N: usize> Iterator for Filled<I, N> {
+    type Item = (I::Item, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let block = self.blocks.next()?;
+        let block_len = self.len.min(N);
+        self.len -= block_len;
+
+        Some((block, block_len))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.blocks.size_hint()
+    }
+}
+
+impl<I: DoubleEndedIterator + ExactSizeIterator, const N: usize> DoubleEndedIterator
+    for Filled<I, N>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let block = self.blocks.next_back()?;
+        let block_len = self.len - self.blocks.len() * N;
+        self.len -= block_len;
+
+        Some((block, block_len))
+    }
+}
+
+impl<I: ExactSizeIterator, const N: usize> ExactSizeIterator for Filled<I, N> {}
+
+/// The blocks of an ortho-`Vec` whose elements are moved out from the front by its
+/// `IntoIterator`.
+///
+/// The elements from the index up to the length are initialized, and the index only moves
+/// forward, so every element is moved out at most once.
+pub struct BlocksIntoIter<S, const N: usize> {
+    blocks: Blocks<S, N>,
+    index: usize,
+}
+
+impl<S, const N: usize> BlocksIntoIter<S, N> {
+    /// Moves the elements of `blocks` out from the front.
+    #[must_use]
+    pub const fn new(blocks: Blocks<S, N>) -> Self {
+        Self { blocks, index: 0 }
+    }
+
+    /// Moves past the next element and returns its index, or `None` if there are no more.
+    pub fn next_index(&mut self) -> Option<usize> {
+        if self.index >= self.blocks.len {
+            return None;
+        }
+
+        self.index += 1;
+        Some(self.index - 1)
+    }
+
+    /// Moves past the rest of the elements and returns their indices.
+    pub fn skip_rest(&mut self) -> Range<usize> {
+        let rest = self.index..self.blocks.len;
+        self.index = self.blocks.len;
+
+        rest
+    }
+}
+
+impl<B, S: Deref<Target = [B]>, const N: usize> BlocksIntoIter<S, N> {
+    /// Returns all of the blocks, in which only the elements that weren't moved past yet are
+    /// initialized.
+    #[must_use]
+    pub fn blocks(&self) -> &[B] {
+        self.blocks.blocks()
+    }
+}
+
+impl<B, S: DerefMut<Target = [B]>, const N: usize> BlocksIntoIter<S, N> {
+    /// Returns all of the blocks mutably, in which only the elements that weren't moved past yet
+    /// are initialized.
+    ///
+    /// # Safety
+    ///
+    /// The elements that weren't moved past have to be left initialized.
+    pub unsafe fn blocks_mut(&mut self) -> &mut [B] {
+        // SAFETY: The blocks are only used by the iterator, which the caller makes sure keeps the
+        // elements it didn't move past initialized
+        unsafe { self.blocks.blocks_mut() }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+    use core::mem::MaybeUninit;
+
+    use super::{Blocks, BlocksIntoIter};
+
+    /// A block holding a single column of `String`s, which have to be moved and dropped properly.
+    struct Block<const N: usize> {
+        names: [MaybeUninit<String>; N],
+    }
+
+    fn names<const N: usize>(block: &mut Block<N>) -> &mut [MaybeUninit<String>; N] {
+        &mut block.names
+    }
+
+    /// A column in blocks of `N`, dropping its elements like the ortho-`Vec` does.
+    struct Names<const N: usize> {
+        blocks: Blocks<Vec<Block<N>>, N>,
+    }
+
+    impl<const N: usize> Names<N> {
+        const fn new() -> Self {
+            Self {
+                blocks: Blocks::new(Vec::new()),
+            }
+        }
+
+        const fn len(&self) -> usize {
+            self.blocks.len()
+        }
+
+        fn insert(&mut self, index: usize, name: String) {
+            let len = self.len();
+
+            // SAFETY: The first `len` elements are initialized, a block is added when the blocks
+            // are full, so there's room for one more, and it's initialized before the length is
+            // set
+            unsafe {
+                if len == self.blocks.blocks().len() * N {
+                    self.blocks.storage_mut().push(Block {
+                        names: super::uninit(),
+                    });
+                }
+                super::insert(self.blocks.blocks_mut(), names, len, index, name);
+                self.blocks.set_len(len + 1);
+            }
+        }
+
+        fn remove(&mut self, index: usize) -> String {
+            let len = self.len();
+
+            // SAFETY: The first `len` elements are initialized, and `index` is checked by the
+            // callers
+            unsafe {
+                self.blocks.set_len(len - 1);
+                super::remove(self.blocks.blocks_mut(), names, len, index)
+            }
+        }
+
+        fn swap_remove(&mut self, index: usize) -> String {
+            let len = self.len();
+
+            // SAFETY: See `remove`
+            unsafe {
+                self.blocks.set_len(len - 1);
+                super::swap_remove(self.blocks.blocks_mut(), names, len, index)
+            }
+        }
+
+        fn reverse(&mut self) {
+            let len = self.len();
+
+            // SAFETY: Reversing keeps the first `len` elements initialized
+            super::reverse(unsafe { self.blocks.blocks_mut() }, names, len);
+        }
+
+        fn elements(&self) -> Vec<String> {
+            self.blocks
+                .filled()
+                // SAFETY: The first `block_len` elements of every filled block are initialized
+                .flat_map(|(block, block_len)| unsafe { super::slice(&block.names[..block_len]) })
+                .cloned()
+                .collect()
+        }
+    }
+
+    impl<const N: usize> Drop for Names<N> {
+        fn drop(&mut self) {
+            let len = self.len();
+
+            // SAFETY: The first `len` elements are initialized, and dropped only here
+            unsafe {
+                self.blocks.set_len(0);
+                super::drop_range(self.blocks.blocks_mut(), names, 0, len);
+            }
+        }
+    }
+
+    fn filled<const N: usize>(len: usize) -> (Names<N>, Vec<String>) {
+        let mut blocks = Names::new();
+        let mut expected = Vec::new();
+        for i in 0..len {
+            blocks.insert(i, i.to_string());
+            expected.push(i.to_string());
+        }
+
+        (blocks, expected)
+    }
+
+    #[test]
+    fn test_insert_at_block_edges() {
+        for len in [63, 64, 65] {
+            let (mut blocks, mut expected) = filled::<64>(len);
+            assert_eq!(blocks.elements(), expected);
+
+            for index in [0, 63, 64, len, blocks.len()] {
+                let index = index.min(blocks.len());
+                blocks.insert(index, "new".to_string());
+                expected.insert(index, "new".to_string());
+                assert_eq!(blocks.elements(), expected);
+            }
+        }
+
+        // Every insert into a full block carries its last element over to the next one
+        let (mut blocks, mut expected) = filled::<4>(12);
+        for index in [3, 4, 8, 0, 15, 16] {
+            blocks.insert(index, "new".to_string());
+            expected.insert(index, "new".to_string());
+        }
+        assert_eq!(blocks.elements(), expected);
+        assert_eq!(blocks.blocks.blocks().len(), 5);
+    }
+
+    #[test]
+    fn test_remove_at_block_edges() {
+        for len in [63, 64, 65] {
+            let (mut blocks, mut expected) = filled::<64>(len);
+
+            for index in [len - 1, 63, 0] {
+                let index = index.min(blocks.len() - 1);
+                assert_eq!(blocks.remove(index), expected.remove(index));
+                assert_eq!(blocks.elements(), expected);
+            }
+        }
+
+        let (mut blocks, mut expected) = filled::<4>(13);
+        for index in [3, 4, 0, 8, 8] {
+            assert_eq!(blocks.remove(index), expected.remove(index));
+            assert_eq!(blocks.elements(), expected);
+        }
+    }
+
+    #[test]
+    fn test_swap_remove() {
+        let (mut blocks, mut expected) = filled::<4>(9);
+
+        // The last element is swapped with itself, and alone in its block
+        assert_eq!(blocks.swap_remove(8), expected.swap_remove(8));
+        assert_eq!(blocks.elements(), expected);
+        // Across blocks, and within the last one
+        assert_eq!(blocks.swap_remove(1), expected.swap_remove(1));
+        assert_eq!(blocks.swap_remove(5), expected.swap_remove(5));
+        assert_eq!(blocks.elements(), expected);
+
+        while blocks.len() > 0 {
+            assert_eq!(blocks.swap_remove(0), expected.swap_remove(0));
+        }
+        assert!(blocks.elements().is_empty());
+    }
+
+    #[test]
+    fn test_reverse() {
+        for len in [0, 1, 2, 4, 5, 63, 64, 65] {
+            let (mut blocks, mut expected) = filled::<4>(len);
+            blocks.reverse();
+            expected.reverse();
+            assert_eq!(blocks.elements(), expected);
+        }
+    }
+
+    #[test]
+    fn test_drop_range() {
+        let (mut blocks, expected) = filled::<4>(10);
+
+        // The tail of the column, from the middle of a block, is dropped as when truncating
+        // SAFETY: The elements from 5 up to 10 are initialized, and left out of the length
+        unsafe {
+            blocks.blocks.set_len(5);
+            super::drop_range(blocks.blocks.blocks_mut(), names, 5, 10);
+        }
+        assert_eq!(blocks.elements(), expected[..5]);
+    }
+
+    #[test]
+    fn test_filled_from_both_ends() {
+        let (blocks, _) = filled::<4>(10);
+        let lens = |filled: super::Filled<_, 4>| filled.map(|(_, len)| len).collect::<Vec<_>>();
+
+        assert_eq!(blocks.blocks.filled().len(), 3);
+        assert_eq!(lens(blocks.blocks.filled()), [4, 4, 2]);
+
+        let mut from_both_ends = blocks.blocks.filled();
+        assert_eq!(from_both_ends.next_back().map(|(_, len)| len), Some(2));
+        assert_eq!(from_both_ends.next().map(|(_, len)| len), Some(4));
+        assert_eq!(from_both_ends.next_back().map(|(_, len)| len), Some(4));
+        assert!(from_both_ends.next().is_none());
+
+        let (empty, _) = filled::<4>(0);
+        assert_eq!(lens(empty.blocks.filled()), []);
+    }
+
+    #[test]
+    fn test_into_iter_moves_every_element_once() {
+        let (mut blocks, expected) = filled::<4>(6);
+
+        // The strings are now owned by the iterator
+        let taken = core::mem::replace(&mut blocks.blocks, Blocks::new(Vec::new()));
+        let mut into_iter = BlocksIntoIter::<_, 4>::new(taken);
+        for expected in &expected[..5] {
+            let index = into_iter.next_index().unwrap();
+            // SAFETY: `index` was just moved past
+            let name = unsafe { into_iter.blocks()[index / 4].names[index % 4].assume_init_read() };
+            assert_eq!(&name, expected);
+        }
+
+        let rest = into_iter.skip_rest();
+        assert_eq!(rest, 5..6);
+        assert_eq!(into_iter.next_index(), None);
+        // SAFETY: Only the strings in `rest` weren't moved out
+        unsafe { super::drop_range(into_iter.blocks_mut(), names, rest.start, rest.end) };
+    }
+}
@@ -0,0 +1,590 @@
+//! Delta-encoded columns of integers, which the fields given `#[ortho(encoding = "delta")]` are
+//! stored in.
+//!
+//! Every element is stored as its difference from the one before it, zigzag-encoded into a
+//! varint, so sorted or slowly changing integers like timestamps mostly take one or two bytes
+//! each.
+//! Every [`CHECKPOINT_INTERVAL`] elements a checkpoint holds the element itself and where the
+//! differences after it start, so the element at an index is decoded from the checkpoint before
+//! it, and the iterators decode the elements one after the other.
+//! As there's no element stored to borrow, the views hold the elements themselves, and changing
+//! one in the middle encodes the elements after it again.
+
+use alloc::vec::Vec;
+use core::{fmt, hash::Hash, iter::FusedIterator};
+
+/// The number of elements between two checkpoints, at most that many differences are decoded to
+/// reach an element.
+pub const CHECKPOINT_INTERVAL: usize = 64;
+
+/// The integers a delta-encoded column can hold, which are stored as the difference from the one
+/// before them.
+pub trait Delta: Copy {
+    /// Returns the difference from `previous`, wrapping around the bounds of the type.
+    fn delta(self, previous: Self) -> i64;
+
+    /// Returns the value `delta` away from `self`, wrapping around the bounds of the type.
+    #[must_use]
+    fn apply(self, delta: i64) -> Self;
+}
+
+macro_rules! impl_delta {
+    ($($ty:ty => $signed:ty),*) => {
+        $(
+            // The differences wrap around on purpose, and are small when the values are close,
+            // whichever way they wrap
+            #[allow(
+                clippy::cast_lossless,
+                clippy::cast_possible_truncation,
+                clippy::cast_possible_wrap,
+                clippy::cast_sign_loss
+            )]
+            impl Delta for $ty {
+                fn delta(self, previous: Self) -> i64 {
+                    self.wrapping_sub(previous) as $signed as i64
+                }
+
+                fn apply(self, delta: i64) -> Self {
+                    self.wrapping_add(delta as $ty)
+                }
+            }
+        )*
+    };
+}
+
+impl_delta!(
+    u8 => i8, u16 => i16, u32 => i32, u64 => i64, usize => isize,
+    i8 => i8, i16 => i16, i32 => i32, i64 => i64, isize => isize
+);
+
+/// An element stored as is, which the elements after it are decoded from.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Checkpoint<T> {
+    value: T,
+    /// The offset of the difference of the element after it.
+    offset: usize,
+}
+
+/// Appends `delta` to `bytes`, zigzag-encoded into a varint of 7 bits a byte.
+fn write_delta(bytes: &mut Vec<u8>, delta: i64) {
+    #[allow(clippy::cast_sign_loss)] // The zigzag encoding keeps all of the bits
+    let mut zigzag = ((delta << 1) ^ (delta >> 63)) as u64;
+    while zigzag >= 0x80 {
+        #[allow(clippy::cast_possible_truncation)] // Only the low 7 bits are kept
+        bytes.push(zigzag as u8 | 0x80);
+        zigzag >>= 7;
+    }
+    #[allow(clippy::cast_possible_truncation)] // The rest fits in 7 bits
+    bytes.push(zigzag as u8);
+}
+
+/// Returns the difference encoded at `*offset` in `bytes`, moving `*offset` past it.
+fn read_delta(bytes: &[u8], offset: &mut usize) -> i64 {
+    let mut zigzag = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = bytes[*offset];
+        *offset += 1;
+        zigzag |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+
+    #[allow(clippy::cast_possible_wrap)] // The zigzag encoding keeps all of the bits
+    let delta = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+    delta
+}
+
+/// The position of a decoder in a column, shared by the iterators.
+#[derive(Clone, Copy)]
+struct Cursor<T> {
+    index: usize,
+    offset: usize,
+    value: Option<T>,
+}
+
+impl<T: Delta> Cursor<T> {
+    const fn new() -> Self {
+        Self {
+            index: 0,
+            offset: 0,
+            value: None,
+        }
+    }
+
+    /// Decodes the element at the index of the cursor, which has to be in bounds, and moves past
+    /// it.
+    fn next(&mut self, checkpoints: &[Checkpoint<T>], bytes: &[u8]) -> T {
+        let value = match self.value {
+            Some(previous) if self.index % CHECKPOINT_INTERVAL != 0 => {
+                previous.apply(read_delta(bytes, &mut self.offset))
+            }
+            _ => {
+                let checkpoint = checkpoints[self.index / CHECKPOINT_INTERVAL];
+                self.offset = checkpoint.offset;
+                checkpoint.value
+            }
+        };
+        self.index += 1;
+        self.value = Some(value);
+
+        value
+    }
+}
+
+/// A column of integers held as the differences between them, with a checkpoint every
+/// [`CHECKPOINT_INTERVAL`] elements.
+pub struct DeltaVec<T> {
+    checkpoints: Vec<Checkpoint<T>>,
+    bytes: Vec<u8>,
+    len: usize,
+    /// The last element, which the next one is the difference from.
+    last: Option<T>,
+}
+
+impl<T: Delta> DeltaVec<T> {
+    /// Constructs a new, empty column.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            checkpoints: Vec::new(),
+            bytes: Vec::new(),
+            len: 0,
+            last: None,
+        }
+    }
+
+    /// Constructs a new, empty column with room for at least `capacity` elements of a byte each.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            checkpoints: Vec::with_capacity(
+                (capacity + CHECKPOINT_INTERVAL - 1) / CHECKPOINT_INTERVAL,
+            ),
+            bytes: Vec::with_capacity(capacity),
+            len: 0,
+            last: None,
+        }
+    }
+
+    /// Returns the number of elements.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no elements.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of bytes the differences take, not counting the checkpoints.
+    #[must_use]
+    pub fn encoded_len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns the element at `index`, decoded from the checkpoint before it, or `None` if it's
+    /// out of bounds.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+
+        let checkpoint = self.checkpoints[index / CHECKPOINT_INTERVAL];
+        let mut offset = checkpoint.offset;
+        let value = (0..index % CHECKPOINT_INTERVAL).fold(checkpoint.value, |value, _| {
+            value.apply(read_delta(&self.bytes, &mut offset))
+        });
+
+        Some(value)
+    }
+
+    /// Returns an iterator over the elements, decoding them one after the other.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            checkpoints: &self.checkpoints,
+            bytes: &self.bytes,
+            len: self.len,
+            cursor: Cursor::new(),
+        }
+    }
+
+    /// Appends an element to the back, as a checkpoint or as the difference from the last one.
+    pub fn push(&mut self, value: T) {
+        match self.last {
+            Some(last) if self.len % CHECKPOINT_INTERVAL != 0 => {
+                write_delta(&mut self.bytes, value.delta(last));
+            }
+            _ => self.checkpoints.push(Checkpoint {
+                value,
+                offset: self.bytes.len(),
+            }),
+        }
+        self.len += 1;
+        self.last = Some(value);
+    }
+
+    /// Removes the last element and returns it, or `None` if there are no elements.
+    pub fn pop(&mut self) -> Option<T> {
+        let last = self.last?;
+        self.truncate(self.len - 1);
+
+        Some(last)
+    }
+
+    /// Shortens the column to its first `len` elements, dropping the bytes of the rest.
+    fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        let checkpoint = len / CHECKPOINT_INTERVAL;
+        if len % CHECKPOINT_INTERVAL == 0 {
+            self.bytes.truncate(self.checkpoints[checkpoint].offset);
+            self.checkpoints.truncate(checkpoint);
+            self.last = len.checked_sub(1).and_then(|index| self.get(index));
+        } else {
+            let mut cursor = Cursor::new();
+            cursor.index = checkpoint * CHECKPOINT_INTERVAL;
+            while cursor.index < len {
+                cursor.next(&self.checkpoints, &self.bytes);
+            }
+            self.bytes.truncate(cursor.offset);
+            self.checkpoints.truncate(checkpoint + 1);
+            self.last = cursor.value;
+        }
+        self.len = len;
+    }
+
+    /// Encodes the elements from `index` on again, with `skip` of them left out and `element` put
+    /// before the rest.
+    fn splice(&mut self, index: usize, skip: usize, element: Option<T>) {
+        let tail: Vec<T> = self.iter().skip(index + skip).collect();
+        self.truncate(index);
+        for value in element.into_iter().chain(tail) {
+            self.push(value);
+        }
+    }
+
+    /// Inserts an element at `index`, encoding the elements after it again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length.
+    pub fn insert(&mut self, index: usize, element: T) {
+        let len = self.len;
+        assert!(
+            index <= len,
+            "insertion index (is {index}) should be <= len (is {len})"
+        );
+
+        self.splice(index, 0, Some(element));
+    }
+
+    /// Removes the element at `index` and returns it, encoding the elements after it again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        let Some(value) = self.get(index) else {
+            panic!("removal index (is {index}) should be < len (is {len})");
+        };
+
+        self.splice(index, 1, None);
+
+        value
+    }
+
+    /// Removes the element at `index` and returns it, moving the last element into its place and
+    /// encoding the elements after it again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        let Some(value) = self.get(index) else {
+            panic!("swap_remove index (is {index}) should be < len (is {len})");
+        };
+
+        let last = self.pop();
+        if index < len - 1 {
+            self.splice(index, 1, last);
+        }
+
+        value
+    }
+
+    /// Removes all elements.
+    pub fn clear(&mut self) {
+        self.checkpoints.clear();
+        self.bytes.clear();
+        self.len = 0;
+        self.last = None;
+    }
+
+    /// Reverses the order of the elements, encoding all of them again.
+    pub fn reverse(&mut self) {
+        let values: Vec<T> = self.iter().collect();
+        self.clear();
+        for value in values.into_iter().rev() {
+            self.push(value);
+        }
+    }
+
+    /// Shrinks the capacity of the bytes and the checkpoints as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.checkpoints.shrink_to_fit();
+        self.bytes.shrink_to_fit();
+    }
+}
+
+impl<T: Delta> Default for DeltaVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for DeltaVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            checkpoints: self.checkpoints.clone(),
+            bytes: self.bytes.clone(),
+            len: self.len,
+            last: self.last.clone(),
+        }
+    }
+}
+
+/// Compares the encoded elements, which are the same for the same elements.
+impl<T: PartialEq> PartialEq for DeltaVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.checkpoints == other.checkpoints && self.bytes == other.bytes
+    }
+}
+
+impl<T: Eq> Eq for DeltaVec<T> {}
+
+impl<T: Hash> Hash for DeltaVec<T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        self.checkpoints.hash(state);
+        self.bytes.hash(state);
+    }
+}
+
+impl<T: Delta + fmt::Debug> fmt::Debug for DeltaVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T: Delta> IntoIterator for &'a DeltaVec<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: Delta> IntoIterator for DeltaVec<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            checkpoints: self.checkpoints,
+            bytes: self.bytes,
+            len: self.len,
+            cursor: Cursor::new(),
+        }
+    }
+}
+
+/// An iterator over the elements of a [`DeltaVec`], decoding them one after the other.
+pub struct Iter<'a, T> {
+    checkpoints: &'a [Checkpoint<T>],
+    bytes: &'a [u8],
+    len: usize,
+    cursor: Cursor<T>,
+}
+
+impl<T: Copy> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            checkpoints: self.checkpoints,
+            bytes: self.bytes,
+            len: self.len,
+            cursor: self.cursor,
+        }
+    }
+}
+
+impl<T: Delta> Iterator for Iter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        (self.cursor.index < self.len).then(|| self.cursor.next(self.checkpoints, self.bytes))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.cursor.index;
+
+        (len, Some(len))
+    }
+}
+
+impl<T: Delta> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T: Delta> FusedIterator for Iter<'_, T> {}
+
+/// An iterator moving the elements out of a [`DeltaVec`], decoding them one after the other.
+pub struct IntoIter<T> {
+    checkpoints: Vec<Checkpoint<T>>,
+    bytes: Vec<u8>,
+    len: usize,
+    cursor: Cursor<T>,
+}
+
+impl<T: Delta> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        (self.cursor.index < self.len).then(|| self.cursor.next(&self.checkpoints, &self.bytes))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.cursor.index;
+
+        (len, Some(len))
+    }
+}
+
+impl<T: Delta> ExactSizeIterator for IntoIter<T> {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{DeltaVec, CHECKPOINT_INTERVAL};
+
+    /// Returns timestamps that mostly grow a little, with a jump back every so often.
+    fn timestamps(len: usize) -> Vec<u64> {
+        (0..len as u64)
+            .map(|index| 1_700_000_000 + index * 3 - if index % 10 == 9 { 500 } else { 0 })
+            .collect()
+    }
+
+    fn pushed<T: super::Delta>(values: &[T]) -> DeltaVec<T> {
+        let mut column = DeltaVec::new();
+        for &value in values {
+            column.push(value);
+        }
+
+        column
+    }
+
+    /// Checks the elements against `expected`, and that they're encoded just as if they had been
+    /// pushed one after the other, with a checkpoint every `CHECKPOINT_INTERVAL` of them.
+    fn assert_column(column: &DeltaVec<u64>, expected: &[u64]) {
+        assert_eq!(column.iter().collect::<Vec<_>>(), expected);
+        for (index, &value) in expected.iter().enumerate() {
+            assert_eq!(column.get(index), Some(value));
+        }
+        assert_eq!(column.get(expected.len()), None);
+
+        assert_eq!(
+            column.checkpoints.len(),
+            (expected.len() + CHECKPOINT_INTERVAL - 1) / CHECKPOINT_INTERVAL
+        );
+        assert_eq!(column.last, expected.last().copied());
+        assert!(*column == pushed(expected));
+    }
+
+    #[test]
+    fn test_push_and_pop_at_checkpoint_edges() {
+        for len in [63, 64, 65, 128, 129] {
+            let mut expected = timestamps(len);
+            let mut column = pushed(&expected);
+            assert_column(&column, &expected);
+            assert_eq!(column.clone().into_iter().collect::<Vec<_>>(), expected);
+
+            while let Some(value) = column.pop() {
+                assert_eq!(Some(value), expected.pop());
+                assert_column(&column, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_insert_and_remove_at_checkpoint_edges() {
+        for len in [63, 64, 65, 129] {
+            for index in [0, 1, 63, 64, 65, 127, 128, len] {
+                let index = index.min(len);
+                let mut expected = timestamps(len);
+                let mut column = pushed(&expected);
+
+                column.insert(index, 42);
+                expected.insert(index, 42);
+                assert_column(&column, &expected);
+
+                assert_eq!(column.remove(index), expected.remove(index));
+                assert_column(&column, &expected);
+                let index = index.min(len - 1);
+                assert_eq!(column.remove(index), expected.remove(index));
+                assert_column(&column, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_swap_remove() {
+        for len in [1, 63, 64, 65] {
+            let mut expected = timestamps(len);
+            let mut column = pushed(&expected);
+
+            // The last element is moved into its own place
+            assert_eq!(column.swap_remove(len - 1), expected.swap_remove(len - 1));
+            assert_column(&column, &expected);
+
+            for index in [0, 63] {
+                if index < expected.len() {
+                    assert_eq!(column.swap_remove(index), expected.swap_remove(index));
+                    assert_column(&column, &expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reverse() {
+        for len in [0, 1, 2, 63, 64, 65] {
+            let mut expected = timestamps(len);
+            let mut column = pushed(&expected);
+            column.reverse();
+            expected.reverse();
+            assert_column(&column, &expected);
+        }
+    }
+
+    #[test]
+    fn test_wrapping_differences() {
+        // The differences wrap around the bounds of the type, and still take a byte when small
+        let values = [i8::MAX, i8::MIN, i8::MAX, i8::MIN];
+        let column = pushed(&values);
+        assert_eq!(column.iter().collect::<Vec<_>>(), values);
+        assert_eq!(column.encoded_len(), 3);
+
+        let values = [0, u64::MAX, 1, u64::MAX / 2];
+        let column = pushed(&values);
+        assert_eq!(column.iter().collect::<Vec<_>>(), values);
+    }
+}
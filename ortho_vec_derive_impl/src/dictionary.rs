@@ -0,0 +1,576 @@
+//! Dictionary-encoded columns, which the fields given `#[ortho(dictionary)]` are stored in.
+//!
+//! Every column is a column of small codes, one for each element, and a [`Dictionary`] holding
+//! every distinct value once, the code of a value being its index in the dictionary.
+//! Values are encoded as they're pushed or inserted, looking them up in an index of the codes
+//! sorted by their values, and decoded by the views and the iterators, which borrow them from the
+//! dictionary.
+//! Values stay in the dictionary once added, until the column is cleared.
+
+use core::{fmt, hash::Hash, iter::FusedIterator};
+
+use crate::storage::{Column, Storage};
+
+/// The type of the codes of a dictionary-encoded column, picked with
+/// `#[ortho(dictionary = "...")]`.
+pub trait Code: Copy {
+    /// Returns the code numbering the value at `index` in the dictionary, or `None` if the code
+    /// can't number that many values.
+    fn from_index(index: usize) -> Option<Self>;
+
+    /// Returns the index of the value in the dictionary.
+    fn index(self) -> usize;
+}
+
+macro_rules! impl_code {
+    ($($code:ty),*) => {
+        $(
+            impl Code for $code {
+                fn from_index(index: usize) -> Option<Self> {
+                    Self::try_from(index).ok()
+                }
+
+                fn index(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_code!(u8, u16, u32);
+
+/// The distinct values of a dictionary-encoded column, in the order they were added.
+pub struct Dictionary<T, K, S: Storage> {
+    values: S::Column<T>,
+    /// The codes ordered by their values, which values are looked up in.
+    sorted: S::Column<K>,
+}
+
+impl<T, K: Code, S: Storage> Dictionary<T, K, S> {
+    fn new() -> Self {
+        Self {
+            values: Column::new(),
+            sorted: Column::new(),
+        }
+    }
+
+    /// Returns the number of distinct values.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if there are no values.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the values, the value of a code being at its index.
+    #[must_use]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the value of `code`, or `None` if no value has that code.
+    #[must_use]
+    pub fn get(&self, code: K) -> Option<&T> {
+        self.values.get(code.index())
+    }
+
+    /// Returns the code of `value`, or `None` if no element has ever had that value.
+    pub fn code(&self, value: &T) -> Option<K>
+    where
+        T: Ord,
+    {
+        self.search(value)
+            .ok()
+            .map(|position| self.sorted[position])
+    }
+
+    /// Returns the position of the code of `value` in the sorted codes, or the position to insert
+    /// it at if it isn't there.
+    fn search(&self, value: &T) -> Result<usize, usize>
+    where
+        T: Ord,
+    {
+        self.sorted
+            .binary_search_by(|code| self.values[code.index()].cmp(value))
+    }
+
+    /// Returns the code of `value`, adding it to the dictionary if it isn't there.
+    fn encode(&mut self, value: T) -> K
+    where
+        T: Ord,
+    {
+        match self.search(&value) {
+            Ok(position) => self.sorted[position],
+            Err(position) => {
+                let code = K::from_index(self.values.len()).unwrap_or_else(|| {
+                    panic!(
+                        "the dictionary is full, its codes can't number more than {} values",
+                        self.values.len()
+                    )
+                });
+                self.values.push(value);
+                self.sorted.insert(position, code);
+
+                code
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+        self.sorted.clear();
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.values.shrink_to_fit();
+        self.sorted.shrink_to_fit();
+    }
+}
+
+impl<T, K, S: Storage> Clone for Dictionary<T, K, S>
+where
+    S::Column<T>: Clone,
+    S::Column<K>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            sorted: self.sorted.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, K, S: Storage> fmt::Debug for Dictionary<T, K, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.values.iter()).finish()
+    }
+}
+
+/// A column of values held as a code for each of them and a [`Dictionary`] of the distinct
+/// values, both in the storage `S`.
+pub struct DictionaryVec<T, K, S: Storage> {
+    codes: S::Column<K>,
+    dictionary: Dictionary<T, K, S>,
+}
+
+impl<T, K: Code, S: Storage> DictionaryVec<T, K, S> {
+    /// Constructs a new, empty column.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            codes: Column::new(),
+            dictionary: Dictionary::new(),
+        }
+    }
+
+    /// Constructs a new, empty column with room for at least `capacity` codes, if its storage can
+    /// make room for them.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            codes: Column::with_capacity(capacity),
+            dictionary: Dictionary::new(),
+        }
+    }
+
+    /// Returns the number of elements.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    /// Returns the code of every element.
+    #[must_use]
+    pub fn codes(&self) -> &[K] {
+        &self.codes
+    }
+
+    /// Returns the dictionary the codes are the indices of.
+    #[must_use]
+    pub const fn dictionary(&self) -> &Dictionary<T, K, S> {
+        &self.dictionary
+    }
+
+    /// Returns the element at `index`, or `None` if it's out of bounds.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.dictionary.get(*self.codes.get(index)?)
+    }
+
+    /// Returns an iterator over the elements, decoded from their codes.
+    pub fn iter(&self) -> Iter<'_, T, K> {
+        Iter {
+            codes: self.codes.iter(),
+            values: &self.dictionary.values,
+        }
+    }
+
+    /// Returns the value of `code`, cloned out of the dictionary.
+    fn decode(&self, code: K) -> T
+    where
+        T: Clone,
+    {
+        self.dictionary.values[code.index()].clone()
+    }
+
+    /// Appends an element to the back, encoding it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is new and the codes can't number any more values.
+    pub fn push(&mut self, value: T)
+    where
+        T: Ord,
+    {
+        let code = self.dictionary.encode(value);
+        self.codes.push(code);
+    }
+
+    /// Removes the last element and returns it, or `None` if there are no elements.
+    pub fn pop(&mut self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let code = self.codes.pop()?;
+
+        Some(self.decode(code))
+    }
+
+    /// Inserts an element at `index`, encoding it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length, or if the value is new and the codes can't
+    /// number any more values.
+    pub fn insert(&mut self, index: usize, element: T)
+    where
+        T: Ord,
+    {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "insertion index (is {index}) should be <= len (is {len})"
+        );
+
+        let code = self.dictionary.encode(element);
+        self.codes.insert(index, code);
+    }
+
+    /// Removes the element at `index` and returns it, moving the elements after it down by one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T
+    where
+        T: Clone,
+    {
+        let code = self.codes.remove(index);
+
+        self.decode(code)
+    }
+
+    /// Removes the element at `index` and returns it, moving the last element into its place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T
+    where
+        T: Clone,
+    {
+        let code = self.codes.swap_remove(index);
+
+        self.decode(code)
+    }
+
+    /// Removes all elements, and all values from the dictionary.
+    pub fn clear(&mut self) {
+        self.codes.clear();
+        self.dictionary.clear();
+    }
+
+    /// Reverses the order of the elements.
+    pub fn reverse(&mut self) {
+        self.codes.reverse();
+    }
+
+    /// Shrinks the capacity as much as possible, if the storage has any to shrink.
+    pub fn shrink_to_fit(&mut self) {
+        self.codes.shrink_to_fit();
+        self.dictionary.shrink_to_fit();
+    }
+}
+
+impl<T, K: Code, S: Storage> Default for DictionaryVec<T, K, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, K, S: Storage> Clone for DictionaryVec<T, K, S>
+where
+    S::Column<T>: Clone,
+    S::Column<K>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            codes: self.codes.clone(),
+            dictionary: self.dictionary.clone(),
+        }
+    }
+}
+
+/// Compares the decoded elements, as the same elements can have different codes in different
+/// dictionaries.
+impl<T: PartialEq, K: Code, S: Storage> PartialEq for DictionaryVec<T, K, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, K: Code, S: Storage> Eq for DictionaryVec<T, K, S> {}
+
+impl<T: Hash, K: Code, S: Storage> Hash for DictionaryVec<T, K, S> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        self.iter().for_each(|value| value.hash(state));
+    }
+}
+
+impl<T: fmt::Debug, K: Code, S: Storage> fmt::Debug for DictionaryVec<T, K, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T, K: Code, S: Storage> IntoIterator for &'a DictionaryVec<T, K, S> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, K>;
+
+    fn into_iter(self) -> Iter<'a, T, K> {
+        self.iter()
+    }
+}
+
+impl<T: Clone, K: Code, S: Storage> IntoIterator for DictionaryVec<T, K, S> {
+    type Item = T;
+    type IntoIter = IntoIter<T, K, S>;
+
+    fn into_iter(self) -> IntoIter<T, K, S> {
+        IntoIter {
+            codes: self.codes.into_iter(),
+            values: self.dictionary.values,
+        }
+    }
+}
+
+/// An iterator over the elements of a [`DictionaryVec`], decoded from their codes.
+pub struct Iter<'a, T, K> {
+    codes: core::slice::Iter<'a, K>,
+    values: &'a [T],
+}
+
+impl<T, K> Clone for Iter<'_, T, K> {
+    fn clone(&self) -> Self {
+        Self {
+            codes: self.codes.clone(),
+            values: self.values,
+        }
+    }
+}
+
+impl<'a, T, K: Code> Iterator for Iter<'a, T, K> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let code = self.codes.next()?;
+
+        Some(&self.values[code.index()])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.codes.size_hint()
+    }
+}
+
+impl<T, K: Code> DoubleEndedIterator for Iter<'_, T, K> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let code = self.codes.next_back()?;
+
+        Some(&self.values[code.index()])
+    }
+}
+
+impl<T, K: Code> ExactSizeIterator for Iter<'_, T, K> {}
+
+impl<T, K: Code> FusedIterator for Iter<'_, T, K> {}
+
+/// An iterator moving the elements out of a [`DictionaryVec`], cloning them out of the
+/// dictionary.
+pub struct IntoIter<T, K, S: Storage> {
+    codes: <S::Column<K> as IntoIterator>::IntoIter,
+    values: S::Column<T>,
+}
+
+impl<T: Clone, K: Code, S: Storage> Iterator for IntoIter<T, K, S> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let code = self.codes.next()?;
+
+        Some(self.values[code.index()].clone())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.codes.size_hint()
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    use super::DictionaryVec;
+    use crate::storage::VecStorage;
+
+    type Names = DictionaryVec<String, u8, VecStorage>;
+
+    /// Returns a name out of 10, so every value repeats.
+    fn name(index: usize) -> String {
+        (index % 10).to_string()
+    }
+
+    fn filled(len: usize) -> (Names, Vec<String>) {
+        let expected = (0..len).map(name).collect::<Vec<_>>();
+        let mut names = Names::new();
+        for name in expected.iter().cloned() {
+            names.push(name);
+        }
+
+        (names, expected)
+    }
+
+    /// Checks the elements against `expected`, and that the dictionary holds every distinct value
+    /// once, coded by its index.
+    fn assert_names(names: &Names, expected: &[String]) {
+        assert_eq!(names.iter().cloned().collect::<Vec<_>>(), expected);
+        for (index, name) in expected.iter().enumerate() {
+            assert_eq!(names.get(index), Some(name));
+        }
+        assert_eq!(names.get(expected.len()), None);
+
+        let dictionary = names.dictionary();
+        for (index, value) in dictionary.values().iter().enumerate() {
+            assert_eq!(dictionary.code(value), Some(u8::try_from(index).unwrap()));
+            assert_eq!(
+                dictionary.values()[..index]
+                    .iter()
+                    .position(|other| other == value),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn test_encoding() {
+        let (names, expected) = filled(25);
+        assert_names(&names, &expected);
+
+        // The values are coded in the order they were first added
+        assert_eq!(names.dictionary().len(), 10);
+        assert_eq!(names.codes()[..12], [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1]);
+        assert_eq!(names.dictionary().code(&"10".to_string()), None);
+        assert_eq!(names.clone().into_iter().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_insert_and_remove_at_word_edges() {
+        for len in [63, 64, 65] {
+            for index in [0, 63, 64, len] {
+                let index = index.min(len);
+                let (mut names, mut expected) = filled(len);
+                names.insert(index, "new".to_string());
+                expected.insert(index, "new".to_string());
+                assert_names(&names, &expected);
+
+                // The value stays in the dictionary after its only element is removed
+                assert_eq!(names.remove(index), expected.remove(index));
+                assert_names(&names, &expected);
+                assert_eq!(names.dictionary().len(), 11);
+                assert_eq!(names.remove(len - 1), expected.remove(len - 1));
+                assert_names(&names, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_swap_remove() {
+        for len in [1, 63, 64, 65] {
+            let (mut names, mut expected) = filled(len);
+
+            // The last element is moved into its own place
+            assert_eq!(names.swap_remove(len - 1), expected.swap_remove(len - 1));
+            assert_names(&names, &expected);
+
+            if !expected.is_empty() {
+                assert_eq!(names.swap_remove(0), expected.swap_remove(0));
+                assert_names(&names, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reverse() {
+        for len in [0, 1, 2, 63, 64, 65] {
+            let (mut names, mut expected) = filled(len);
+            names.reverse();
+            expected.reverse();
+            assert_names(&names, &expected);
+        }
+    }
+
+    #[test]
+    fn test_clear_and_equality() {
+        let (mut names, expected) = filled(20);
+
+        // The same elements are equal whatever their codes
+        let mut reordered = Names::new();
+        reordered.push(name(5));
+        reordered.clear();
+        assert!(reordered.dictionary().is_empty());
+        for name in expected.iter().rev().cloned() {
+            reordered.insert(0, name);
+        }
+        assert_ne!(reordered.codes(), names.codes());
+        assert_eq!(reordered, names);
+
+        names.clear();
+        assert!(names.is_empty() && names.dictionary().is_empty());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "the dictionary is full, its codes can't number more than 256 values"
+    )]
+    fn test_full_dictionary() {
+        let mut names = Names::new();
+        for index in 0..=256 {
+            names.push(index.to_string());
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Iterating over a column in chunks of SIMD lanes, which the ortho-`Vec`s given
+//! `#[ortho(align = ...)]` do for each of their fields.
+//!
+//! The chunks are arrays of `LANES` elements, which `std::simd` and crates like `wide` load into
+//! vectors of `LANES` lanes, and the last elements of the column that don't fill a chunk are left
+//! as a remainder, to be handled one by one.
+//! When the size of a chunk divides the alignment of the column, every chunk is aligned to its
+//! size.
+
+use core::iter::FusedIterator;
+
+/// Returns a chunk of `LANES` elements as an array.
+fn lanes<T, const LANES: usize>(chunk: &[T]) -> &[T; LANES] {
+    chunk.try_into().expect("the chunks have `LANES` elements")
+}
+
+/// Returns a mutable chunk of `LANES` elements as an array.
+fn lanes_mut<T, const LANES: usize>(chunk: &mut [T]) -> &mut [T; LANES] {
+    chunk.try_into().expect("the chunks have `LANES` elements")
+}
+
+/// An iterator over a column in chunks of `LANES` elements, leaving out the
+/// [remainder](Lanes::remainder).
+///
+/// # Panics
+///
+/// Creating it panics if `LANES` is 0.
+pub struct Lanes<'a, T, const LANES: usize> {
+    chunks: core::slice::ChunksExact<'a, T>,
+    remainder: &'a [T],
+}
+
+impl<'a, T, const LANES: usize> Lanes<'a, T, LANES> {
+    /// Splits `column` into chunks of `LANES` elements from its start, and the remainder.
+    #[must_use]
+    pub fn new(column: &'a [T]) -> Self {
+        let (chunks, remainder) = column.split_at(column.len() - column.len() % LANES);
+
+        Self {
+            chunks: chunks.chunks_exact(LANES),
+            remainder,
+        }
+    }
+
+    /// Returns the last elements of the column, fewer than `LANES`, which aren't in any chunk.
+    #[must_use]
+    pub const fn remainder(&self) -> &'a [T] {
+        self.remainder
+    }
+}
+
+impl<T, const LANES: usize> Clone for Lanes<'_, T, LANES> {
+    fn clone(&self) -> Self {
+        Self {
+            chunks: self.chunks.clone(),
+            remainder: self.remainder,
+        }
+    }
+}
+
+impl<'a, T, const LANES: usize> Iterator for Lanes<'a, T, LANES> {
+    type Item = &'a [T; LANES];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(lanes)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<T, const LANES: usize> DoubleEndedIterator for Lanes<'_, T, LANES> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.chunks.next_back().map(lanes)
+    }
+}
+
+impl<T, const LANES: usize> ExactSizeIterator for Lanes<'_, T, LANES> {}
+
+impl<T, const LANES: usize> FusedIterator for Lanes<'_, T, LANES> {}
+
+/// An iterator over a column in mutable chunks of `LANES` elements, leaving out the
+/// [remainder](LanesMut::into_remainder).
+///
+/// # Panics
+///
+/// Creating it panics if `LANES` is 0.
+pub struct LanesMut<'a, T, const LANES: usize> {
+    chunks: core::slice::ChunksExactMut<'a, T>,
+    remainder: &'a mut [T],
+}
+
+impl<'a, T, const LANES: usize> LanesMut<'a, T, LANES> {
+    /// Splits `column` into chunks of `LANES` elements from its start, and the remainder.
+    #[must_use]
+    pub fn new(column: &'a mut [T]) -> Self {
+        let (chunks, remainder) = column.split_at_mut(column.len() - column.len() % LANES);
+
+        Self {
+            chunks: chunks.chunks_exact_mut(LANES),
+            remainder,
+        }
+    }
+
+    /// Returns the last elements of the column, fewer than `LANES`, which aren't in any chunk.
+    #[must_use]
+    pub const fn into_remainder(self) -> &'a mut [T] {
+        self.remainder
+    }
+}
+
+impl<'a, T, const LANES: usize> Iterator for LanesMut<'a, T, LANES> {
+    type Item = &'a mut [T; LANES];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(lanes_mut)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<T, const LANES: usize> DoubleEndedIterator for LanesMut<'_, T, LANES> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.chunks.next_back().map(lanes_mut)
+    }
+}
+
+impl<T, const LANES: usize> ExactSizeIterator for LanesMut<'_, T, LANES> {}
+
+impl<T, const LANES: usize> FusedIterator for LanesMut<'_, T, LANES> {}
@@ -1,3 +1,5 @@
+#![no_std]
+#![cfg_attr(feature = "nightly", feature(allocator_api))]
 #![warn(
     clippy::all,
     clippy::missing_const_for_fn,
@@ -7,3 +9,174 @@
     clippy::unused_self,
     clippy::needless_pass_by_value
 )]
+
+// Re-exported for the generated code, which can't count on the crate using it to declare `alloc`
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+pub mod bits;
+#[doc(hidden)]
+pub mod blocks;
+#[cfg(feature = "alloc")]
+pub mod delta;
+pub mod dictionary;
+pub mod lanes;
+pub mod nullable;
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub mod raw;
+#[cfg(feature = "alloc")]
+pub mod rle;
+pub mod storage;
+
+/// The `Allocator` trait and the `Vec` using it, which the columns of ortho-`Vec`s given
+/// `#[ortho(allocator = "...")]` are.
+///
+/// With the `nightly` feature these are the unstable ones of the standard library.
+#[cfg(feature = "alloc")]
+pub use allocator_api2;
+
+/// Expands to the items given to it only with the `alloc` feature, for the generated items that
+/// need a `Vec`, as the derive can't tell which features are enabled.
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_alloc {
+    ($($item:item)*) => {
+        $($item)*
+    };
+}
+
+/// Expands to the items given to it only with the `alloc` feature, for the generated items that
+/// need a `Vec`, as the derive can't tell which features are enabled.
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __with_alloc {
+    ($($item:item)*) => {};
+}
+
+/// Converts from a `Vec`, which every ortho-`Vec` does with the `alloc` feature.
+///
+/// Implemented for every type that is `From<Vec<T>>`, and without the `alloc` feature, where
+/// there are no `Vec`s to convert from, for every type.
+#[cfg(feature = "alloc")]
+pub trait FromVec<T>: From<Vec<T>> {}
+
+#[cfg(feature = "alloc")]
+impl<T, V: From<Vec<T>>> FromVec<T> for V {}
+
+/// Converts from a `Vec`, which every ortho-`Vec` does with the `alloc` feature.
+///
+/// Implemented for every type that is `From<Vec<T>>`, and without the `alloc` feature, where
+/// there are no `Vec`s to convert from, for every type.
+#[cfg(not(feature = "alloc"))]
+pub trait FromVec<T> {}
+
+#[cfg(not(feature = "alloc"))]
+impl<T, V> FromVec<T> for V {}
+
+/// The API shared by all of the ortho-`Vec`s, for code that works with any of them.
+///
+/// Implemented by the derive for every ortho-`Vec`, on top of the inherent methods of the same
+/// names, which are the ones called when the type is known.
+pub trait OrthoVec: IntoIterator + FromVec<Self::Item> {
+    /// The view of an element.
+    type Ref<'a>
+    where
+        Self: 'a;
+    /// The mutable view of an element.
+    type RefMut<'a>
+    where
+        Self: 'a;
+    /// The iterator over views of the elements.
+    type Iter<'a>: Iterator<Item = Self::Ref<'a>>
+    where
+        Self: 'a;
+    /// The iterator over mutable views of the elements.
+    type IterMut<'a>: Iterator<Item = Self::RefMut<'a>>
+    where
+        Self: 'a;
+
+    /// Constructs a new, empty ortho-`Vec`.
+    fn new() -> Self;
+
+    /// Constructs a new, empty ortho-`Vec` with room for at least `capacity` elements in every
+    /// column.
+    fn with_capacity(capacity: usize) -> Self;
+
+    /// Returns the number of elements.
+    fn len(&self) -> usize;
+
+    /// Returns whether there are no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends an element to the back.
+    fn push(&mut self, value: Self::Item);
+
+    /// Removes the last element and returns it, or `None` if there are no elements.
+    fn pop(&mut self) -> Option<Self::Item>;
+
+    /// Inserts an element at `index`, panics if `index` is greater than the length.
+    fn insert(&mut self, index: usize, element: Self::Item);
+
+    /// Same as `Vec::remove`, removes the element at `index` and returns it.
+    fn remove(&mut self, index: usize) -> Self::Item;
+
+    /// Same as `Vec::swap_remove`, removes the element at `index` and returns it.
+    fn swap_remove(&mut self, index: usize) -> Self::Item;
+
+    /// Removes all elements.
+    fn clear(&mut self);
+
+    /// Reverses the order of the elements.
+    fn reverse(&mut self);
+
+    /// Calls `Vec::shrink_to_fit` on every column.
+    fn shrink_to_fit(&mut self);
+
+    /// Returns an iterator over views of the elements.
+    fn iter(&self) -> Self::Iter<'_>;
+
+    /// Returns an iterator over mutable views of the elements.
+    fn iter_mut(&mut self) -> Self::IterMut<'_>;
+}
+
+/// Converts a `Vec` into the ortho-`Vec` of its elements.
+pub trait IntoOrtho {
+    /// The ortho-`Vec` this converts into.
+    type OrthoVec: OrthoVec;
+
+    /// Moves the elements into a new ortho-`Vec`.
+    fn into_ortho(self) -> Self::OrthoVec;
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Columnar> IntoOrtho for Vec<T> {
+    type OrthoVec = T::Vec;
+
+    fn into_ortho(self) -> Self::OrthoVec {
+        self.into()
+    }
+}
+
+/// Links a type deriving `OrthoVec` to the items generated for it, so they can be named from the
+/// type alone.
+///
+/// Implemented by the derive for every type, unless its generated items are given a narrower
+/// `vis` than its own, as the implementation can't name them then.
+/// This is what lets a `Vec` of it be converted with [`IntoOrtho`], and the columns of a
+/// `#[ortho(flatten)]` field be named.
+pub trait Columnar {
+    /// The ortho-`Vec` holding elements of this type, which names the rest of the items.
+    type Vec: OrthoVec<Item = Self>;
+}
+
+/// The ortho-`Vec` holding elements of type `T`.
+pub type Soa<T> = <T as Columnar>::Vec;
@@ -0,0 +1,537 @@
+//! Nullable columns of `Option`s, which the fields given `#[ortho(nullable)]` are stored in.
+//!
+//! Every column is a validity bitmap, with a bit set for every element that is `Some`, and a
+//! dense column holding only the values of those elements, in order, so the missing ones take a
+//! single bit and the present ones are reached without going over the missing ones.
+//! The value of the element at `index` is at the number of set bits before `index`, which is
+//! counted a word at a time.
+
+use core::{fmt, hash::Hash, iter::FusedIterator, mem};
+
+use crate::{
+    bits::{self, BitVec, Bits},
+    storage::{Column, Storage},
+};
+
+/// A column of `Option<T>`s, held as a validity bitmap and a column of the present values, both
+/// in the storage `S`.
+pub struct NullableVec<T, S: Storage> {
+    validity: BitVec<S::Column<u64>>,
+    values: S::Column<T>,
+}
+
+impl<T, S: Storage> NullableVec<T, S> {
+    /// Constructs a new, empty column.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            validity: BitVec::new(),
+            values: Column::new(),
+        }
+    }
+
+    /// Constructs a new, empty column with room for at least `capacity` elements, all of them
+    /// present, if its storage can make room for them.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            validity: BitVec::with_capacity(capacity),
+            values: Column::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of elements, present or not.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.validity.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.validity.is_empty()
+    }
+
+    /// Returns the validity bitmap, with a bit set for every element that is `Some`.
+    #[must_use]
+    pub fn validity(&self) -> Bits<'_> {
+        self.validity.bits()
+    }
+
+    /// Returns the values of the elements that are `Some`, in order.
+    #[must_use]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the values of the elements that are `Some`, in order, to be changed in place.
+    #[must_use]
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    /// Returns the element at `index`, or `None` if it's out of bounds.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<Option<&T>> {
+        let present = self.validity.bits().get(index)?;
+
+        Some(present.then(|| &self.values[self.rank(index)]))
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            validity: self.validity.iter(),
+            values: self.values.iter(),
+        }
+    }
+
+    /// Returns an iterator over the elements, with their values borrowed mutably.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            validity: self.validity.iter(),
+            values: self.values.iter_mut(),
+        }
+    }
+
+    /// Returns an iterator over the indices and values of the elements that are `Some`, skipping
+    /// the words of the bitmap without any.
+    pub fn present(&self) -> Present<'_, T> {
+        Present {
+            indices: self.validity.bits().ones(),
+            values: self.values.iter(),
+        }
+    }
+
+    /// Returns an iterator over the indices and values of the elements that are `Some`, with
+    /// their values borrowed mutably.
+    pub fn present_mut(&mut self) -> PresentMut<'_, T> {
+        PresentMut {
+            indices: self.validity.bits().ones(),
+            values: self.values.iter_mut(),
+        }
+    }
+
+    /// Returns the number of present values before the element at `index`, the index of its value
+    /// if it's present.
+    fn rank(&self, index: usize) -> usize {
+        let words = self.validity.bits().words();
+        let (full_words, bits) = (index / 64, index % 64);
+        let partial = words
+            .get(full_words)
+            .map_or(0, |word| (word & ((1 << bits) - 1)).count_ones());
+
+        words[..full_words.min(words.len())]
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum::<usize>()
+            + partial as usize
+    }
+
+    /// Appends an element to the back.
+    pub fn push(&mut self, value: Option<T>) {
+        self.validity.push(value.is_some());
+        if let Some(value) = value {
+            self.values.push(value);
+        }
+    }
+
+    /// Removes the last element and returns it, or `None` if there are no elements.
+    pub fn pop(&mut self) -> Option<Option<T>> {
+        let present = self.validity.pop()?;
+
+        Some(if present { self.values.pop() } else { None })
+    }
+
+    /// Inserts an element at `index`, moving the elements after it up by one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length.
+    pub fn insert(&mut self, index: usize, element: Option<T>) {
+        self.validity.insert(index, element.is_some());
+        if let Some(value) = element {
+            let rank = self.rank(index);
+            self.values.insert(rank, value);
+        }
+    }
+
+    /// Removes the element at `index` and returns it, moving the elements after it down by one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        let present = self.validity.remove(index);
+
+        present.then(|| self.values.remove(self.rank(index)))
+    }
+
+    /// Removes the element at `index` and returns it, moving the last element into its place.
+    ///
+    /// The values in between move by one when only one of the two elements is present.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> Option<T> {
+        let len = self.len();
+        assert!(
+            index < len,
+            "swap_remove index (is {index}) should be < len (is {len})"
+        );
+
+        let last = self.pop().flatten();
+        if index == len - 1 {
+            return last;
+        }
+
+        let rank = self.rank(index);
+        let present = self.validity.bits().get(index) == Some(true);
+        match (present, last) {
+            (true, Some(last)) => Some(mem::replace(&mut self.values[rank], last)),
+            (true, None) => {
+                self.validity.bits_mut().set(index, false);
+                Some(self.values.remove(rank))
+            }
+            (false, Some(last)) => {
+                self.validity.bits_mut().set(index, true);
+                self.values.insert(rank, last);
+                None
+            }
+            (false, None) => None,
+        }
+    }
+
+    /// Removes all elements.
+    pub fn clear(&mut self) {
+        self.validity.clear();
+        self.values.clear();
+    }
+
+    /// Reverses the order of the elements, and so of the present values.
+    pub fn reverse(&mut self) {
+        self.validity.reverse();
+        self.values.reverse();
+    }
+
+    /// Shrinks the capacity as much as possible, if the storage has any to shrink.
+    pub fn shrink_to_fit(&mut self) {
+        self.validity.shrink_to_fit();
+        self.values.shrink_to_fit();
+    }
+}
+
+impl<T, S: Storage> Default for NullableVec<T, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S: Storage> Clone for NullableVec<T, S>
+where
+    S::Column<u64>: Clone,
+    S::Column<T>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            validity: self.validity.clone(),
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<T: PartialEq, S: Storage> PartialEq for NullableVec<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.validity() == other.validity() && self.values() == other.values()
+    }
+}
+
+impl<T: Eq, S: Storage> Eq for NullableVec<T, S> {}
+
+impl<T: Hash, S: Storage> Hash for NullableVec<T, S> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        self.validity().words().hash(state);
+        self.values().hash(state);
+    }
+}
+
+impl<T: fmt::Debug, S: Storage> fmt::Debug for NullableVec<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T, S: Storage> IntoIterator for &'a NullableVec<T, S> {
+    type Item = Option<&'a T>;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, S: Storage> IntoIterator for &'a mut NullableVec<T, S> {
+    type Item = Option<&'a mut T>;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T, S: Storage> IntoIterator for NullableVec<T, S> {
+    type Item = Option<T>;
+    type IntoIter = IntoIter<T, S>;
+
+    fn into_iter(self) -> IntoIter<T, S> {
+        IntoIter {
+            validity: self.validity.into_iter(),
+            values: self.values.into_iter(),
+        }
+    }
+}
+
+/// An iterator over the elements of a [`NullableVec`].
+pub struct Iter<'a, T> {
+    validity: bits::Iter<'a>,
+    values: core::slice::Iter<'a, T>,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            validity: self.validity.clone(),
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = Option<&'a T>;
+
+    fn next(&mut self) -> Option<Option<&'a T>> {
+        let present = self.validity.next()?;
+
+        Some(if *present { self.values.next() } else { None })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.validity.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// An iterator over the elements of a [`NullableVec`], with their values borrowed mutably.
+pub struct IterMut<'a, T> {
+    validity: bits::Iter<'a>,
+    values: core::slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = Option<&'a mut T>;
+
+    fn next(&mut self) -> Option<Option<&'a mut T>> {
+        let present = self.validity.next()?;
+
+        Some(if *present { self.values.next() } else { None })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.validity.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+/// An iterator over the indices and values of the present elements of a [`NullableVec`].
+pub struct Present<'a, T> {
+    indices: bits::Ones<'a>,
+    values: core::slice::Iter<'a, T>,
+}
+
+impl<T> Clone for Present<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            indices: self.indices.clone(),
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Present<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<(usize, &'a T)> {
+        Some((self.indices.next()?, self.values.next()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Present<'_, T> {}
+
+impl<T> FusedIterator for Present<'_, T> {}
+
+/// An iterator over the indices and values of the present elements of a [`NullableVec`], with
+/// the values borrowed mutably.
+pub struct PresentMut<'a, T> {
+    indices: bits::Ones<'a>,
+    values: core::slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for PresentMut<'a, T> {
+    type Item = (usize, &'a mut T);
+
+    fn next(&mut self) -> Option<(usize, &'a mut T)> {
+        Some((self.indices.next()?, self.values.next()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for PresentMut<'_, T> {}
+
+impl<T> FusedIterator for PresentMut<'_, T> {}
+
+/// An iterator moving the elements out of a [`NullableVec`].
+pub struct IntoIter<T, S: Storage> {
+    validity: bits::IntoIter<S::Column<u64>>,
+    values: <S::Column<T> as IntoIterator>::IntoIter,
+}
+
+impl<T, S: Storage> Iterator for IntoIter<T, S> {
+    type Item = Option<T>;
+
+    fn next(&mut self) -> Option<Option<T>> {
+        let present = self.validity.next()?;
+
+        Some(if present { self.values.next() } else { None })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.validity.size_hint()
+    }
+}
+
+impl<T, S: Storage> ExactSizeIterator for IntoIter<T, S> {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    use super::NullableVec;
+    use crate::storage::VecStorage;
+
+    type Names = NullableVec<String, VecStorage>;
+
+    fn element(index: usize) -> Option<String> {
+        (index % 3 != 1).then(|| index.to_string())
+    }
+
+    fn filled(len: usize) -> (Names, Vec<Option<String>>) {
+        let expected = (0..len).map(element).collect::<Vec<_>>();
+        let mut names = Names::new();
+        for element in expected.iter().cloned() {
+            names.push(element);
+        }
+
+        (names, expected)
+    }
+
+    /// Checks the elements against `expected`, reached both in order and by their index, which
+    /// goes through the rank of the validity bitmap.
+    fn assert_names(names: &Names, expected: &[Option<String>]) {
+        assert_eq!(
+            names
+                .iter()
+                .map(Option::<&String>::cloned)
+                .collect::<Vec<_>>(),
+            expected
+        );
+        for (index, element) in expected.iter().enumerate() {
+            assert_eq!(names.get(index), Some(element.as_ref()));
+        }
+        assert_eq!(names.get(expected.len()), None);
+
+        assert!(names
+            .present()
+            .map(|(index, name)| (index, name.clone()))
+            .eq(expected
+                .iter()
+                .enumerate()
+                .filter_map(|(index, name)| Some((index, name.clone()?)))));
+        assert_eq!(names.values().len(), names.validity().count_ones());
+    }
+
+    #[test]
+    fn test_rank_at_word_edges() {
+        for len in [63, 64, 65, 129] {
+            let (names, expected) = filled(len);
+            assert_names(&names, &expected);
+            assert_eq!(names.clone().into_iter().collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn test_insert_and_remove_at_word_edges() {
+        for len in [63, 64, 65] {
+            for index in [0, 63, 64, len] {
+                let index = index.min(len);
+                for element in [Some("new".to_string()), None] {
+                    let (mut names, mut expected) = filled(len);
+                    names.insert(index, element.clone());
+                    expected.insert(index, element);
+                    assert_names(&names, &expected);
+
+                    assert_eq!(names.remove(index), expected.remove(index));
+                    assert_names(&names, &expected);
+                    let last = len - 1;
+                    assert_eq!(names.remove(last), expected.remove(last));
+                    assert_names(&names, &expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_swap_remove() {
+        for len in [1, 2, 63, 64, 65] {
+            let (mut names, mut expected) = filled(len);
+
+            // The last element is moved into its own place
+            assert_eq!(names.swap_remove(len - 1), expected.swap_remove(len - 1));
+            assert_names(&names, &expected);
+
+            // Every combination of a present or missing element and last element
+            while !expected.is_empty() {
+                let index = expected.len() / 2;
+                assert_eq!(names.swap_remove(index), expected.swap_remove(index));
+                assert_names(&names, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reverse() {
+        for len in [0, 1, 2, 63, 64, 65] {
+            let (mut names, mut expected) = filled(len);
+            names.reverse();
+            expected.reverse();
+            assert_names(&names, &expected);
+        }
+    }
+}
@@ -0,0 +1,640 @@
+//! The single allocation holding all of the columns of an ortho-`Vec` with
+//! `#[ortho(layout = "single")]`.
+//!
+//! Not meant to be used directly, it may change between versions.
+
+use core::{alloc::Layout, marker::PhantomData, ops::Range, ptr::NonNull};
+
+use allocator_api2::alloc::{handle_alloc_error, Allocator, Global};
+
+/// The layouts of the elements of each of the `N` columns of `Self` in a single allocation.
+///
+/// Implemented by the derive for the struct, so that the columns of a `RawColumns<L, N>` are
+/// always laid out for the elements of the ortho-`Vec` of `L`.
+pub trait ColumnLayouts<const N: usize> {
+    /// The layout of the elements of each column.
+    const LAYOUTS: [Layout; N];
+}
+
+/// Columns of elements of different types, all in one allocation with room for the same number
+/// of elements in every column, laid out by `L` and allocated with `A`.
+///
+/// Only the memory and the length are owned, the ortho-`Vec` moves the elements in and out of
+/// the columns and drops them. The first `len` elements of every column are initialized, which
+/// only the unsafe methods can break.
+pub struct RawColumns<L, const N: usize, A: Allocator = Global> {
+    ptr: NonNull<u8>,
+    capacity: usize,
+    len: usize,
+    /// The offset of each column from `ptr`, in bytes.
+    offsets: [usize; N],
+    /// The layout of the allocation, which is only allocated when it has a size.
+    layout: Layout,
+    alloc: A,
+    marker: PhantomData<fn() -> L>,
+}
+
+// SAFETY: Only the memory of the elements is owned, the ortho-`Vec`s owning the elements are
+// `Send` and `Sync` only if they are
+unsafe impl<L, const N: usize, A: Allocator + Send> Send for RawColumns<L, N, A> {}
+// SAFETY: See above
+unsafe impl<L, const N: usize, A: Allocator + Sync> Sync for RawColumns<L, N, A> {}
+
+/// Returns the layout of columns with room for `capacity` elements each, and their offsets.
+fn columns_layout<const N: usize>(
+    layouts: &[Layout; N],
+    capacity: usize,
+) -> Option<(Layout, [usize; N])> {
+    let mut offsets = [0; N];
+    let mut layout = Layout::from_size_align(0, 1).ok()?;
+
+    for (offset, element_layout) in offsets.iter_mut().zip(layouts) {
+        let column_layout = Layout::from_size_align(
+            element_layout.size().checked_mul(capacity)?,
+            element_layout.align(),
+        )
+        .ok()?;
+        (layout, *offset) = layout.extend(column_layout).ok()?;
+    }
+
+    Some((layout.pad_to_align(), offsets))
+}
+
+/// Returns `layout` aligned to at least `align`, which is a power of two, for the columns given
+/// an alignment.
+///
+/// The column starts at a multiple of `align`, and the padding up to the next column (or to the
+/// end of the allocation) is kept.
+#[must_use]
+pub const fn align_layout(layout: Layout, align: usize) -> Layout {
+    let align = if layout.align() > align {
+        layout.align()
+    } else {
+        align
+    };
+
+    match Layout::from_size_align(layout.size(), align) {
+        Ok(layout) => layout,
+        Err(_) => panic!("the alignment of a column has to be a power of two"),
+    }
+}
+
+/// Returns a dangling pointer aligned for every column.
+fn dangling<const N: usize>(layouts: &[Layout; N]) -> NonNull<u8> {
+    let align = layouts.iter().map(Layout::align).max().unwrap_or(1);
+
+    NonNull::new(core::ptr::null_mut::<u8>().wrapping_add(align)).unwrap_or(NonNull::dangling())
+}
+
+impl<L: ColumnLayouts<N>, const N: usize> RawColumns<L, N> {
+    /// Creates empty columns in the global allocator, without allocating.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
+}
+
+impl<L: ColumnLayouts<N>, const N: usize> Default for RawColumns<L, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L: ColumnLayouts<N>, const N: usize, A: Allocator> RawColumns<L, N, A> {
+    /// Creates empty columns in `alloc`, without allocating.
+    #[must_use]
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            ptr: dangling(&L::LAYOUTS),
+            capacity: 0,
+            len: 0,
+            offsets: [0; N],
+            layout: Layout::new::<()>(),
+            alloc,
+            marker: PhantomData,
+        }
+    }
+
+    /// Makes room for at least `additional` more elements in every column, growing all of them
+    /// together.
+    pub fn reserve(&mut self, additional: usize) {
+        if self.capacity - self.len >= additional {
+            return;
+        }
+
+        let required = self.len.checked_add(additional).expect("capacity overflow");
+        let capacity = required.max(self.capacity.saturating_mul(2)).max(4);
+        // SAFETY: The length is at most the capacity, which only grows
+        unsafe { self.reallocate(capacity) };
+    }
+
+    /// Shrinks every column to room for exactly its elements.
+    pub fn shrink_to_fit(&mut self) {
+        if self.capacity > self.len {
+            // SAFETY: The length is at most the capacity
+            unsafe { self.reallocate(self.len) };
+        }
+    }
+
+    /// Moves the elements of every column into a new allocation with room for `capacity`
+    /// elements.
+    ///
+    /// # Safety
+    ///
+    /// The length has to be at most the new capacity.
+    unsafe fn reallocate(&mut self, capacity: usize) {
+        let (layout, offsets) = columns_layout(&L::LAYOUTS, capacity).expect("capacity overflow");
+
+        let ptr = if layout.size() == 0 {
+            dangling(&L::LAYOUTS)
+        } else {
+            self.alloc
+                .allocate(layout)
+                .unwrap_or_else(|_| handle_alloc_error(layout))
+                .cast()
+        };
+
+        for ((element_layout, &old_offset), &new_offset) in
+            L::LAYOUTS.iter().zip(&self.offsets).zip(&offsets)
+        {
+            // SAFETY: Both columns have room for `len` elements, and are in different allocations
+            // unless they are empty
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    self.ptr.as_ptr().add(old_offset),
+                    ptr.as_ptr().add(new_offset),
+                    element_layout.size() * self.len,
+                );
+            }
+        }
+
+        self.deallocate();
+        self.ptr = ptr;
+        self.capacity = capacity;
+        self.offsets = offsets;
+        self.layout = layout;
+    }
+}
+
+impl<L, const N: usize, A: Allocator> RawColumns<L, N, A> {
+    /// Returns the number of elements every column has room for.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of initialized elements of every column.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the columns have no elements.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Sets the number of initialized elements of every column.
+    ///
+    /// # Safety
+    ///
+    /// `len` has to be at most the capacity, and the first `len` elements of every column have
+    /// to be initialized.
+    pub unsafe fn set_len(&mut self, len: usize) {
+        self.len = len;
+    }
+
+    fn deallocate(&mut self) {
+        if self.layout.size() != 0 {
+            // SAFETY: The memory was allocated by the allocator with this layout, as it has a size
+            unsafe { self.alloc.deallocate(self.ptr, self.layout) };
+        }
+    }
+
+    /// Returns a pointer to the start of the column at `index`.
+    #[must_use]
+    pub const fn column<T>(&self, index: usize) -> *mut T {
+        self.ptr.as_ptr().wrapping_add(self.offsets[index]).cast()
+    }
+
+    /// Returns the first `len` elements of the column at `index`.
+    ///
+    /// # Safety
+    ///
+    /// The column has to hold elements of type `T`, the first `len` of which are initialized.
+    #[must_use]
+    pub const unsafe fn slice<T>(&self, index: usize, len: usize) -> &[T] {
+        // SAFETY: Guaranteed by the caller
+        unsafe { core::slice::from_raw_parts(self.column(index), len) }
+    }
+
+    /// Returns the first `len` elements of the column at `index`, mutably.
+    ///
+    /// # Safety
+    ///
+    /// The column has to hold elements of type `T`, the first `len` of which are initialized.
+    pub unsafe fn slice_mut<T>(&mut self, index: usize, len: usize) -> &mut [T] {
+        // SAFETY: Guaranteed by the caller
+        unsafe { core::slice::from_raw_parts_mut(self.column(index), len) }
+    }
+
+    /// Moves `value` into the column at `index`, at `at`, without dropping what was there.
+    ///
+    /// # Safety
+    ///
+    /// The column has to hold elements of type `T`, and have room for more than `at` of them.
+    pub unsafe fn write<T>(&mut self, index: usize, at: usize, value: T) {
+        // SAFETY: Guaranteed by the caller
+        unsafe { self.column::<T>(index).add(at).write(value) };
+    }
+
+    /// Moves the element out of the column at `index`, at `at`, leaving it uninitialized.
+    ///
+    /// # Safety
+    ///
+    /// The column has to hold elements of type `T`, and the one at `at` has to be initialized.
+    #[must_use]
+    pub const unsafe fn read<T>(&self, index: usize, at: usize) -> T {
+        // SAFETY: Guaranteed by the caller
+        unsafe { self.column::<T>(index).add(at).read() }
+    }
+
+    /// Moves `value` into the column at `index`, at `at`, shifting the elements after it to the
+    /// right.
+    ///
+    /// # Safety
+    ///
+    /// The column has to hold elements of type `T`, the first `len` of which are initialized,
+    /// have room for more than `len` of them, and `at` has to be at most `len`.
+    pub unsafe fn insert<T>(&mut self, index: usize, len: usize, at: usize, value: T) {
+        let column = self.column::<T>(index);
+
+        // SAFETY: Guaranteed by the caller
+        unsafe {
+            core::ptr::copy(column.add(at), column.add(at + 1), len - at);
+            column.add(at).write(value);
+        }
+    }
+
+    /// Moves the element out of the column at `index`, at `at`, shifting the elements after it
+    /// to the left.
+    ///
+    /// # Safety
+    ///
+    /// The column has to hold elements of type `T`, the first `len` of which are initialized,
+    /// and `at` has to be less than `len`.
+    pub unsafe fn remove<T>(&mut self, index: usize, len: usize, at: usize) -> T {
+        let column = self.column::<T>(index);
+
+        // SAFETY: Guaranteed by the caller
+        unsafe {
+            let value = column.add(at).read();
+            core::ptr::copy(column.add(at + 1), column.add(at), len - at - 1);
+            value
+        }
+    }
+
+    /// Moves the element out of the column at `index`, at `at`, replacing it with the last one.
+    ///
+    /// # Safety
+    ///
+    /// The column has to hold elements of type `T`, the first `len` of which are initialized,
+    /// and `at` has to be less than `len`.
+    pub unsafe fn swap_remove<T>(&mut self, index: usize, len: usize, at: usize) -> T {
+        let column = self.column::<T>(index);
+
+        // SAFETY: Guaranteed by the caller
+        unsafe {
+            let value = column.add(at).read();
+            core::ptr::copy(column.add(len - 1), column.add(at), 1);
+            value
+        }
+    }
+}
+
+impl<L, const N: usize, A: Allocator> Drop for RawColumns<L, N, A> {
+    fn drop(&mut self) {
+        self.deallocate();
+    }
+}
+
+/// The columns of an ortho-`Vec` with a single allocation, whose elements are moved out from the
+/// front by its `IntoIterator`.
+///
+/// The elements from the index up to the length of the columns are initialized, and the index
+/// only moves forward, so every element is moved out at most once.
+pub struct RawIntoIter<L, const N: usize, A: Allocator = Global> {
+    columns: RawColumns<L, N, A>,
+    index: usize,
+}
+
+impl<L, const N: usize, A: Allocator> RawIntoIter<L, N, A> {
+    /// Moves the elements of `columns` out from the front.
+    #[must_use]
+    pub const fn new(columns: RawColumns<L, N, A>) -> Self {
+        Self { columns, index: 0 }
+    }
+
+    /// Moves past the next element and returns its index, or `None` if there are no more.
+    pub fn next_index(&mut self) -> Option<usize> {
+        if self.index >= self.columns.len {
+            return None;
+        }
+
+        self.index += 1;
+        Some(self.index - 1)
+    }
+
+    /// Moves past the rest of the elements and returns their indices.
+    pub fn skip_rest(&mut self) -> Range<usize> {
+        let rest = self.index..self.columns.len;
+        self.index = self.columns.len;
+
+        rest
+    }
+
+    /// Moves the element out of the column at `index`, at `at`, leaving it uninitialized.
+    ///
+    /// # Safety
+    ///
+    /// The column has to hold elements of type `T`, and `at` has to be an index returned by
+    /// [`next_index`](Self::next_index) that wasn't read before.
+    #[must_use]
+    pub const unsafe fn read<T>(&self, index: usize, at: usize) -> T {
+        // SAFETY: Guaranteed by the caller
+        unsafe { self.columns.read(index, at) }
+    }
+
+    /// Returns the first `len` elements of the column at `index`, mutably.
+    ///
+    /// # Safety
+    ///
+    /// The column has to hold elements of type `T`, and `len` has to be at most the length of the
+    /// columns. Only the elements that weren't moved past yet are initialized.
+    pub unsafe fn slice_mut<T>(&mut self, index: usize, len: usize) -> &mut [T] {
+        // SAFETY: Guaranteed by the caller
+        unsafe { self.columns.slice_mut(index, len) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+    use core::{
+        alloc::Layout,
+        mem::{align_of, size_of},
+    };
+
+    use super::{align_layout, columns_layout, ColumnLayouts, RawColumns, RawIntoIter};
+
+    /// Lays out columns of `u8`s, `u64`s and `String`s.
+    struct Element;
+
+    impl ColumnLayouts<3> for Element {
+        const LAYOUTS: [Layout; 3] = [
+            Layout::new::<u8>(),
+            Layout::new::<u64>(),
+            Layout::new::<String>(),
+        ];
+    }
+
+    /// Columns of `u8`s, `u64`s and `String`s, dropping them like the ortho-`Vec` does.
+    struct Columns {
+        raw: RawColumns<Element, 3>,
+    }
+
+    impl Columns {
+        fn new() -> Self {
+            Self {
+                raw: RawColumns::new(),
+            }
+        }
+
+        fn len(&self) -> usize {
+            self.raw.len()
+        }
+
+        fn push(&mut self, i: u8) {
+            let len = self.len();
+            self.raw.reserve(1);
+
+            // SAFETY: The first `len` elements are initialized, there is room for one more, and
+            // the columns hold the types of `Element::LAYOUTS` in order
+            unsafe {
+                self.raw.write(0, len, i);
+                self.raw.write(1, len, u64::from(i) << 40);
+                self.raw.write(2, len, i.to_string());
+                self.raw.set_len(len + 1);
+            }
+        }
+
+        fn insert(&mut self, at: usize, i: u8) {
+            let len = self.len();
+            self.raw.reserve(1);
+
+            // SAFETY: See `push`, and the callers insert at most at `len`
+            unsafe {
+                self.raw.insert(0, len, at, i);
+                self.raw.insert(1, len, at, u64::from(i) << 40);
+                self.raw.insert(2, len, at, i.to_string());
+                self.raw.set_len(len + 1);
+            }
+        }
+
+        fn remove(&mut self, at: usize) -> (u8, u64, String) {
+            let len = self.len();
+
+            // SAFETY: See `push`, and the callers remove below `len`
+            unsafe {
+                self.raw.set_len(len - 1);
+                (
+                    self.raw.remove(0, len, at),
+                    self.raw.remove(1, len, at),
+                    self.raw.remove(2, len, at),
+                )
+            }
+        }
+
+        fn swap_remove(&mut self, at: usize) -> (u8, u64, String) {
+            let len = self.len();
+
+            // SAFETY: See `remove`
+            unsafe {
+                self.raw.set_len(len - 1);
+                (
+                    self.raw.swap_remove(0, len, at),
+                    self.raw.swap_remove(1, len, at),
+                    self.raw.swap_remove(2, len, at),
+                )
+            }
+        }
+
+        fn elements(&self) -> Vec<(u8, u64, String)> {
+            let len = self.len();
+
+            // SAFETY: The first `len` elements of every column are initialized
+            let (bytes, words, strings) = unsafe {
+                (
+                    self.raw.slice::<u8>(0, len),
+                    self.raw.slice::<u64>(1, len),
+                    self.raw.slice::<String>(2, len),
+                )
+            };
+
+            bytes
+                .iter()
+                .zip(words)
+                .zip(strings)
+                .map(|((&byte, &word), string)| (byte, word, string.clone()))
+                .collect()
+        }
+    }
+
+    impl Drop for Columns {
+        fn drop(&mut self) {
+            // SAFETY: The first `len` strings are initialized, and dropped only here
+            unsafe {
+                core::ptr::drop_in_place(self.raw.slice_mut::<String>(2, self.raw.len()));
+            }
+        }
+    }
+
+    fn element(i: u8) -> (u8, u64, String) {
+        (i, u64::from(i) << 40, i.to_string())
+    }
+
+    #[test]
+    fn test_columns_layout() {
+        let (layout, offsets) = columns_layout(&Element::LAYOUTS, 3).unwrap();
+        assert_eq!(offsets[0], 0);
+        assert_eq!(offsets[1], 8);
+        assert_eq!(offsets[2], 8 + 3 * 8);
+        assert_eq!(layout.size(), offsets[2] + 3 * size_of::<String>());
+        assert_eq!(layout.align(), 8);
+
+        let (layout, offsets) = columns_layout(&Element::LAYOUTS, 0).unwrap();
+        assert_eq!((layout.size(), offsets), (0, [0, 0, 0]));
+        assert!(columns_layout(&Element::LAYOUTS, usize::MAX / 4).is_none());
+
+        let aligned = align_layout(Layout::new::<u8>(), 64);
+        assert_eq!((aligned.size(), aligned.align()), (1, 64));
+        let (layout, offsets) = columns_layout(&[aligned, Layout::new::<u16>()], 65).unwrap();
+        assert_eq!(offsets, [0, 65 + 1]);
+        assert_eq!(layout.align(), 64);
+        assert_eq!(layout.size(), 256);
+    }
+
+    #[test]
+    fn test_growing_past_word_lengths() {
+        let mut columns = Columns::new();
+        assert_eq!(columns.raw.capacity(), 0);
+
+        for i in 0..65 {
+            columns.push(i);
+            if matches!(columns.len(), 63..=65) {
+                assert!(columns.raw.capacity() >= columns.len());
+                assert_eq!(columns.elements(), (0..=i).map(element).collect::<Vec<_>>());
+            }
+        }
+        assert_eq!(columns.raw.capacity(), 128);
+
+        columns.raw.shrink_to_fit();
+        assert_eq!(columns.raw.capacity(), 65);
+        assert_eq!(columns.elements(), (0..65).map(element).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_insert_and_remove_at_the_edges() {
+        let mut columns = Columns::new();
+        let mut expected = Vec::new();
+        for i in 0..64 {
+            columns.push(i);
+            expected.push(element(i));
+        }
+
+        // The capacity is full, so inserting at either end grows the columns first
+        assert_eq!(columns.raw.capacity(), 64);
+        columns.insert(0, 100);
+        expected.insert(0, element(100));
+        columns.insert(65, 101);
+        expected.insert(65, element(101));
+        columns.insert(64, 102);
+        expected.insert(64, element(102));
+        assert_eq!(columns.elements(), expected);
+
+        assert_eq!(columns.remove(0), expected.remove(0));
+        assert_eq!(columns.remove(columns.len() - 1), expected.pop().unwrap());
+        assert_eq!(columns.swap_remove(10), expected.swap_remove(10));
+        assert_eq!(
+            columns.swap_remove(columns.len() - 1),
+            expected.pop().unwrap()
+        );
+        assert_eq!(columns.elements(), expected);
+
+        while columns.len() > 0 {
+            assert_eq!(columns.swap_remove(0), expected.swap_remove(0));
+        }
+        columns.raw.shrink_to_fit();
+        assert_eq!(columns.raw.capacity(), 0);
+        assert!(columns.elements().is_empty());
+    }
+
+    #[test]
+    fn test_zero_sized_columns() {
+        struct Unit;
+
+        impl ColumnLayouts<2> for Unit {
+            const LAYOUTS: [Layout; 2] = [Layout::new::<()>(), Layout::new::<u16>()];
+        }
+
+        let mut raw = RawColumns::<Unit, 2>::new();
+        for at in 0..3 {
+            raw.reserve(1);
+            // SAFETY: `at` elements are initialized in both columns, and there is room for one
+            // more
+            unsafe {
+                raw.write(0, at, ());
+                raw.write(1, at, u16::try_from(at).unwrap());
+                raw.set_len(at + 1);
+            }
+        }
+
+        // SAFETY: The first 3 elements are initialized
+        unsafe {
+            assert_eq!(raw.slice::<()>(0, raw.len()).len(), 3);
+            assert_eq!(raw.slice::<u16>(1, raw.len()), [0, 1, 2]);
+            assert_eq!(raw.column::<u16>(1).align_offset(align_of::<u16>()), 0);
+        }
+    }
+
+    #[test]
+    fn test_into_iter_moves_every_element_once() {
+        let mut columns = Columns::new();
+        for i in 0..5 {
+            columns.push(i);
+        }
+
+        // The strings are now owned by the iterator
+        let raw = core::mem::take(&mut columns.raw);
+        let mut into_iter = RawIntoIter::new(raw);
+        for i in 0..2_u8 {
+            let at = into_iter.next_index().unwrap();
+            assert_eq!(at, usize::from(i));
+            // SAFETY: `at` was just moved past, and the column holds `String`s
+            let string = unsafe { into_iter.read::<String>(2, at) };
+            assert_eq!(string, i.to_string());
+        }
+
+        let rest = into_iter.skip_rest();
+        assert_eq!(rest, 2..5);
+        assert_eq!(into_iter.next_index(), None);
+        // SAFETY: Only the strings in `rest` weren't moved out
+        unsafe {
+            core::ptr::drop_in_place(&mut into_iter.slice_mut::<String>(2, rest.end)[rest.start..]);
+        }
+    }
+}
@@ -0,0 +1,568 @@
+//! Run-length encoded columns, which the fields given `#[ortho(encoding = "rle")]` are stored in.
+//!
+//! Every run of equal elements is stored once, as its value and the index its run ends at, so a
+//! field that rarely changes takes a value for every change instead of one for every element.
+//! The ends are the checkpoints the element at an index is found by, with a binary search, and the
+//! iterators go over the runs in order.
+//! Changing an element in the middle splits or merges runs, moving the ends after it.
+
+use alloc::vec::{self, Vec};
+use core::{fmt, hash::Hash, iter::FusedIterator};
+
+/// A column of elements held as runs of equal elements.
+pub struct RleVec<T> {
+    values: Vec<T>,
+    /// The index after the last element of each run, in increasing order.
+    ends: Vec<usize>,
+}
+
+impl<T> RleVec<T> {
+    /// Constructs a new, empty column.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            ends: Vec::new(),
+        }
+    }
+
+    /// Constructs a new, empty column.
+    ///
+    /// Nothing is allocated up front, as the number of runs `capacity` elements make isn't known.
+    #[must_use]
+    pub const fn with_capacity(_capacity: usize) -> Self {
+        Self::new()
+    }
+
+    /// Returns the number of elements.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ends.last().copied().unwrap_or(0)
+    }
+
+    /// Returns `true` if there are no elements.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Returns the number of runs.
+    #[must_use]
+    pub fn run_count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns an iterator over the runs, as their value and their number of elements.
+    #[must_use]
+    pub fn runs(&self) -> Runs<'_, T> {
+        Runs {
+            values: self.values.iter(),
+            ends: self.ends.iter(),
+            start: 0,
+        }
+    }
+
+    /// Returns the element at `index`, or `None` if it's out of bounds.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.values.get(self.run_of(index))
+    }
+
+    /// Returns an iterator over the elements.
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            values: &self.values,
+            ends: &self.ends,
+            run: 0,
+            index: 0,
+        }
+    }
+
+    /// Returns the index of the run holding the element at `index`, the number of runs if it's out
+    /// of bounds.
+    fn run_of(&self, index: usize) -> usize {
+        self.ends.partition_point(|&end| end <= index)
+    }
+
+    /// Returns the index of the first element of `run`.
+    fn run_start(&self, run: usize) -> usize {
+        run.checked_sub(1).map_or(0, |previous| self.ends[previous])
+    }
+
+    /// Moves the ends of the runs from `run` on up by one element.
+    fn grow_from(&mut self, run: usize) {
+        for end in &mut self.ends[run..] {
+            *end += 1;
+        }
+    }
+
+    /// Moves the ends of the runs from `run` on down by one element.
+    fn shrink_from(&mut self, run: usize) {
+        for end in &mut self.ends[run..] {
+            *end -= 1;
+        }
+    }
+
+    /// Appends an element to the back, extending the last run if it's equal to it.
+    pub fn push(&mut self, value: T)
+    where
+        T: PartialEq,
+    {
+        match (self.values.last(), self.ends.last_mut()) {
+            (Some(last), Some(end)) if *last == value => *end += 1,
+            _ => {
+                let len = self.len();
+                self.values.push(value);
+                self.ends.push(len + 1);
+            }
+        }
+    }
+
+    /// Removes the last element and returns it, or `None` if there are no elements.
+    pub fn pop(&mut self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let run = self.ends.len().checked_sub(1)?;
+        self.ends[run] -= 1;
+
+        if self.ends[run] == self.run_start(run) {
+            self.ends.pop();
+            self.values.pop()
+        } else {
+            Some(self.values[run].clone())
+        }
+    }
+
+    /// Inserts an element at `index`, extending the run it lands in or next to if it's equal to
+    /// it, and splitting that run otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length.
+    pub fn insert(&mut self, index: usize, element: T)
+    where
+        T: PartialEq + Clone,
+    {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "insertion index (is {index}) should be <= len (is {len})"
+        );
+
+        if index == len {
+            self.push(element);
+            return;
+        }
+
+        let run = self.run_of(index);
+        let start = self.run_start(run);
+        if self.values[run] == element {
+            self.grow_from(run);
+        } else if index == start && run > 0 && self.values[run - 1] == element {
+            self.grow_from(run - 1);
+        } else if index == start {
+            self.values.insert(run, element);
+            self.ends.insert(run, index);
+            self.grow_from(run);
+        } else {
+            // The run is split in two around the element
+            let end = self.ends[run];
+            let value = self.values[run].clone();
+            self.values.insert(run + 1, element);
+            self.values.insert(run + 2, value);
+            self.ends.splice(run..=run, [index, index + 1, end + 1]);
+            self.grow_from(run + 3);
+        }
+    }
+
+    /// Removes the element at `index` and returns it, merging the runs around it if it was the
+    /// only element of its run.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T
+    where
+        T: PartialEq + Clone,
+    {
+        let len = self.len();
+        assert!(
+            index < len,
+            "removal index (is {index}) should be < len (is {len})"
+        );
+
+        let run = self.run_of(index);
+        self.shrink_from(run);
+        if self.ends[run] != self.run_start(run) {
+            return self.values[run].clone();
+        }
+
+        self.ends.remove(run);
+        let value = self.values.remove(run);
+        if run > 0 && run < self.values.len() && self.values[run - 1] == self.values[run] {
+            self.ends.remove(run - 1);
+            self.values.remove(run);
+        }
+
+        value
+    }
+
+    /// Removes the element at `index` and returns it, moving the last element into its place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn swap_remove(&mut self, index: usize) -> T
+    where
+        T: PartialEq + Clone,
+    {
+        let len = self.len();
+        assert!(
+            index < len,
+            "swap_remove index (is {index}) should be < len (is {len})"
+        );
+
+        // The length is checked above, so there's a last element
+        let last = self.pop().unwrap_or_else(|| unreachable!());
+        if index == len - 1 {
+            return last;
+        }
+
+        let value = self.remove(index);
+        self.insert(index, last);
+
+        value
+    }
+
+    /// Removes all elements.
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.ends.clear();
+    }
+
+    /// Reverses the order of the elements, and so of the runs.
+    pub fn reverse(&mut self) {
+        // The ends are turned into the lengths of the runs, reversed, and added up again
+        for run in (1..self.ends.len()).rev() {
+            self.ends[run] -= self.ends[run - 1];
+        }
+        self.ends.reverse();
+        for run in 1..self.ends.len() {
+            self.ends[run] += self.ends[run - 1];
+        }
+        self.values.reverse();
+    }
+
+    /// Shrinks the capacity of the runs as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.values.shrink_to_fit();
+        self.ends.shrink_to_fit();
+    }
+}
+
+impl<T> Default for RleVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for RleVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            ends: self.ends.clone(),
+        }
+    }
+}
+
+/// Compares the runs, which are the same for the same elements as equal neighbors always share a
+/// run.
+impl<T: PartialEq> PartialEq for RleVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ends == other.ends && self.values == other.values
+    }
+}
+
+impl<T: Eq> Eq for RleVec<T> {}
+
+impl<T: Hash> Hash for RleVec<T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.ends.hash(state);
+        self.values.hash(state);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RleVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RleVec<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: Clone> IntoIterator for RleVec<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            len: self.len(),
+            values: self.values.into_iter(),
+            ends: self.ends.into_iter(),
+            run: None,
+            index: 0,
+        }
+    }
+}
+
+/// An iterator over the elements of an [`RleVec`].
+pub struct Iter<'a, T> {
+    values: &'a [T],
+    ends: &'a [usize],
+    run: usize,
+    index: usize,
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values,
+            ends: self.ends,
+            run: self.run,
+            index: self.index,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.ends.get(self.run) == Some(&self.index) {
+            self.run += 1;
+        }
+        let value = self.values.get(self.run)?;
+        self.index += 1;
+
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.ends.last().copied().unwrap_or(0) - self.index;
+
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// An iterator over the runs of an [`RleVec`], as their value and their number of elements.
+pub struct Runs<'a, T> {
+    values: core::slice::Iter<'a, T>,
+    ends: core::slice::Iter<'a, usize>,
+    start: usize,
+}
+
+impl<T> Clone for Runs<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            ends: self.ends.clone(),
+            start: self.start,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Runs<'a, T> {
+    type Item = (&'a T, usize);
+
+    fn next(&mut self) -> Option<(&'a T, usize)> {
+        let end = *self.ends.next()?;
+        let len = end - self.start;
+        self.start = end;
+
+        Some((self.values.next()?, len))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.values.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Runs<'_, T> {}
+
+impl<T> FusedIterator for Runs<'_, T> {}
+
+/// An iterator moving the elements out of an [`RleVec`], cloning the value of every run but for
+/// its last element.
+pub struct IntoIter<T> {
+    len: usize,
+    values: vec::IntoIter<T>,
+    ends: vec::IntoIter<usize>,
+    /// The value of the current run and the index it ends at.
+    run: Option<(T, usize)>,
+    index: usize,
+}
+
+impl<T: Clone> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.run.is_none() {
+            self.run = Some((self.values.next()?, self.ends.next()?));
+        }
+        self.index += 1;
+
+        match self.run.take() {
+            Some((value, end)) if self.index < end => {
+                self.run = Some((value.clone(), end));
+                Some(value)
+            }
+            run => run.map(|(value, _)| value),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.index;
+
+        (len, Some(len))
+    }
+}
+
+impl<T: Clone> ExactSizeIterator for IntoIter<T> {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    use super::RleVec;
+
+    /// Returns the value of the element at `index`, in runs of 4.
+    fn value(index: usize) -> String {
+        (index / 4 % 3).to_string()
+    }
+
+    fn filled(len: usize) -> (RleVec<String>, Vec<String>) {
+        let expected = (0..len).map(value).collect::<Vec<_>>();
+        let mut column = RleVec::new();
+        for value in expected.iter().cloned() {
+            column.push(value);
+        }
+
+        (column, expected)
+    }
+
+    /// Checks the elements against `expected`, and that they're held in as few runs as they make,
+    /// with no empty runs and no equal neighbors.
+    fn assert_column(column: &RleVec<String>, expected: &[String]) {
+        assert_eq!(column.len(), expected.len());
+        assert_eq!(column.iter().cloned().collect::<Vec<_>>(), expected);
+        for (index, value) in expected.iter().enumerate() {
+            assert_eq!(column.get(index), Some(value));
+        }
+        assert_eq!(column.get(expected.len()), None);
+
+        let mut runs = Vec::<(&String, usize)>::new();
+        for value in expected {
+            match runs.last_mut() {
+                Some((last, count)) if *last == value => *count += 1,
+                _ => runs.push((value, 1)),
+            }
+        }
+        assert_eq!(column.runs().collect::<Vec<_>>(), runs);
+    }
+
+    #[test]
+    fn test_push_and_pop() {
+        for len in [63, 64, 65] {
+            let (mut column, mut expected) = filled(len);
+            assert_column(&column, &expected);
+            assert_eq!(column.run_count(), (len + 3) / 4);
+            assert_eq!(column.clone().into_iter().collect::<Vec<_>>(), expected);
+
+            while let Some(value) = column.pop() {
+                assert_eq!(Some(value), expected.pop());
+                assert_column(&column, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_insert_at_run_edges() {
+        // Every index is at the start, in the middle or at the end of a run, and every value
+        // either extends the run it lands in, extends the one before it or splits it
+        for len in [63, 64, 65] {
+            for index in 0..=len {
+                for value in ["0", "1", "new"] {
+                    let (mut column, mut expected) = filled(len);
+                    column.insert(index, value.to_string());
+                    expected.insert(index, value.to_string());
+                    assert_column(&column, &expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_remove_at_run_edges() {
+        for len in [63, 64, 65] {
+            for index in 0..len {
+                let (mut column, mut expected) = filled(len);
+                assert_eq!(column.remove(index), expected.remove(index));
+                assert_column(&column, &expected);
+            }
+        }
+
+        // Removing the only element of a run merges the runs around it
+        let mut column = RleVec::new();
+        for value in ["a", "a", "b", "a", "a"] {
+            column.push(value.to_string());
+        }
+        assert_eq!(column.remove(2), "b");
+        assert_eq!(column.run_count(), 1);
+        assert_column(&column, &vec!["a".to_string(); 4]);
+    }
+
+    #[test]
+    fn test_swap_remove() {
+        for len in [1, 63, 64, 65] {
+            let (mut column, mut expected) = filled(len);
+
+            // The last element is moved into its own place
+            assert_eq!(column.swap_remove(len - 1), expected.swap_remove(len - 1));
+            assert_column(&column, &expected);
+
+            for index in [0, 4, 5] {
+                if index < expected.len() {
+                    assert_eq!(column.swap_remove(index), expected.swap_remove(index));
+                    assert_column(&column, &expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reverse() {
+        for len in [0, 1, 2, 5, 63, 64, 65] {
+            let (mut column, mut expected) = filled(len);
+            column.reverse();
+            expected.reverse();
+            assert_column(&column, &expected);
+        }
+    }
+}
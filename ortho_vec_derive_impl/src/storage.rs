@@ -0,0 +1,618 @@
+//! The storages backing the columns of an ortho-`Vec`, picked with `#[ortho(storage = "...")]`.
+//!
+//! Every column of the ortho-`Vec` is a [`Column`] of its field's type, which the [`Storage`]
+//! names for every type.
+//! Without the attribute the columns are `Vec`s, the same as `VecStorage` gives.
+//! `VecStorage` and `BoxedStorage` need the `alloc` feature, and `ArrayVecStorage` is the only
+//! storage that never allocates.
+//!
+//! A single field can be given a column of its own instead, with `#[ortho(column = ...)]`, which
+//! is any [`ColumnStorage`] of the field's type.
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
+use core::ops::DerefMut;
+
+/// Names the type of the columns holding elements of any type.
+///
+/// # Safety
+///
+/// The generated code reaches into the columns without checking their bounds, trusting them to
+/// uphold the invariants of [`Column`], so the columns named here have to uphold them for every
+/// `T`.
+pub unsafe trait Storage {
+    /// The column holding elements of type `T`.
+    type Column<T>: Column<T>;
+}
+
+/// A column of an ortho-`Vec`, holding one field of each of its elements.
+///
+/// Columns are slices underneath, which the views and the iterators borrow from, and these are
+/// the methods of `Vec` the ortho-`Vec` needs on top of that.
+///
+/// # Safety
+///
+/// The iterators of the ortho-`Vec` access the columns without checking their bounds, up to the
+/// length of the shortest one, so a column has to behave like a `Vec`:
+/// + `deref` and `deref_mut` return the same elements, as a slice whose length is the number of
+///   elements in the column, and any `len` of the column's own returns that same length.
+/// + `into_iter` yields every element of that slice, in order, and nothing else.
+/// + `from_elements` holds every element it is given, in order, and nothing else.
+/// + `push` and `insert` add a single element, and `pop`, `remove` and `swap_remove` remove a
+///   single one (`pop` only when the column isn't empty), as the methods of `Vec` do.
+/// + The slice returned by `deref_mut` doesn't alias anything else reachable through the column,
+///   as the mutable views borrow from it for as long as the ortho-`Vec` is borrowed.
+pub unsafe trait Column<T>: DerefMut<Target = [T]> + IntoIterator<Item = T> {
+    /// Constructs a new, empty column.
+    fn new() -> Self;
+
+    /// Constructs a new, empty column with room for at least `capacity` elements, if the column
+    /// can make room for them.
+    fn with_capacity(capacity: usize) -> Self;
+
+    /// Constructs a column holding `elements`, in order, which the ortho-`Vec` is converted from a
+    /// `Vec` with.
+    ///
+    /// Pushes them one by one, unless the column can take them all at once.
+    fn from_elements(elements: impl IntoIterator<Item = T>) -> Self
+    where
+        Self: Sized,
+    {
+        let elements = elements.into_iter();
+        let mut column = Self::with_capacity(elements.size_hint().0);
+        for element in elements {
+            column.push(element);
+        }
+
+        column
+    }
+
+    /// Appends an element to the back.
+    fn push(&mut self, value: T);
+
+    /// Removes the last element and returns it, or `None` if there are no elements.
+    fn pop(&mut self) -> Option<T>;
+
+    /// Inserts an element at `index`, panics if `index` is greater than the length.
+    fn insert(&mut self, index: usize, element: T);
+
+    /// Same as `Vec::remove`, removes the element at `index` and returns it.
+    fn remove(&mut self, index: usize) -> T;
+
+    /// Same as `Vec::swap_remove`, removes the element at `index` and returns it.
+    fn swap_remove(&mut self, index: usize) -> T;
+
+    /// Removes all elements.
+    fn clear(&mut self);
+
+    /// Shrinks the capacity as much as possible, if the column has any to shrink.
+    fn shrink_to_fit(&mut self) {}
+
+    /// Reverses the order of the elements.
+    fn reverse(&mut self) {
+        self.deref_mut().reverse();
+    }
+}
+
+/// Stores every column in a `Vec`, the default.
+#[cfg(feature = "alloc")]
+pub struct VecStorage;
+
+// SAFETY: `Vec` is a `Column`
+#[cfg(feature = "alloc")]
+unsafe impl Storage for VecStorage {
+    type Column<T> = Vec<T>;
+}
+
+// SAFETY: Every method is the one of `Vec` itself
+#[cfg(feature = "alloc")]
+unsafe impl<T> Column<T> for Vec<T> {
+    fn new() -> Self {
+        Vec::new()
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        Vec::with_capacity(capacity)
+    }
+
+    fn from_elements(elements: impl IntoIterator<Item = T>) -> Self {
+        elements.into_iter().collect()
+    }
+
+    fn push(&mut self, value: T) {
+        self.push(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn insert(&mut self, index: usize, element: T) {
+        self.insert(index, element);
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        self.remove(index)
+    }
+
+    fn swap_remove(&mut self, index: usize) -> T {
+        self.swap_remove(index)
+    }
+
+    fn clear(&mut self) {
+        self.clear();
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.shrink_to_fit();
+    }
+}
+
+/// Stores every column in a boxed slice, without any spare capacity.
+///
+/// Meant for ortho-`Vec`s that are converted from a `Vec` once and then only read or changed in
+/// place, as every change to their length reallocates all of the columns and copies every element.
+/// Each `push`, `pop`, `insert` or `remove` takes time proportional to the length, so building an
+/// ortho-`Vec` of `n` elements by pushing them takes time proportional to `n²`, while converting
+/// it from a `Vec` collects every column at once.
+#[cfg(feature = "alloc")]
+pub struct BoxedStorage;
+
+// SAFETY: `Box<[T]>` is a `Column`
+#[cfg(feature = "alloc")]
+unsafe impl Storage for BoxedStorage {
+    type Column<T> = Box<[T]>;
+}
+
+/// Changes the length of a boxed slice through a `Vec`, reallocating it.
+///
+/// The `Vec` has no spare capacity to begin with, so growing it reallocates, and shrinking it back
+/// into a boxed slice reallocates again, copying every element both times.
+/// The elements are put back even if `f` panics, so the column keeps them like the other columns
+/// of the ortho-`Vec` do.
+#[cfg(feature = "alloc")]
+fn with_vec<T, R>(boxed: &mut Box<[T]>, f: impl FnOnce(&mut Vec<T>) -> R) -> R {
+    /// Puts the elements of the `Vec` back into the boxed slice when dropped.
+    struct Restore<'a, T> {
+        boxed: &'a mut Box<[T]>,
+        vec: Vec<T>,
+    }
+
+    impl<T> Drop for Restore<'_, T> {
+        fn drop(&mut self) {
+            *self.boxed = core::mem::take(&mut self.vec).into_boxed_slice();
+        }
+    }
+
+    let vec = core::mem::take(boxed).into_vec();
+    let mut restore = Restore { boxed, vec };
+
+    f(&mut restore.vec)
+}
+
+// SAFETY: The elements are the boxed slice itself, changed through the methods of `Vec`
+#[cfg(feature = "alloc")]
+unsafe impl<T> Column<T> for Box<[T]> {
+    fn new() -> Self {
+        Box::default()
+    }
+
+    fn with_capacity(_capacity: usize) -> Self {
+        Box::default()
+    }
+
+    fn from_elements(elements: impl IntoIterator<Item = T>) -> Self {
+        elements.into_iter().collect()
+    }
+
+    fn push(&mut self, value: T) {
+        with_vec(self, |vec| vec.push(value));
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        with_vec(self, Vec::pop)
+    }
+
+    fn insert(&mut self, index: usize, element: T) {
+        with_vec(self, |vec| vec.insert(index, element));
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        with_vec(self, |vec| vec.remove(index))
+    }
+
+    fn swap_remove(&mut self, index: usize) -> T {
+        with_vec(self, |vec| vec.swap_remove(index))
+    }
+
+    fn clear(&mut self) {
+        *self = Box::default();
+    }
+}
+
+/// Stores every column in a `Vec` whose memory is aligned to `ALIGN` bytes, and padded to a whole
+/// number of them, picked with `#[ortho(align = ALIGN)]`.
+///
+/// The padding lets the last chunk of a column be loaded whole with an aligned SIMD load, without
+/// reading past the allocation.
+#[cfg(feature = "alloc")]
+pub struct AlignedStorage<const ALIGN: usize>;
+
+// SAFETY: A `Vec` in `AlignedAlloc` is a `Column`
+#[cfg(feature = "alloc")]
+unsafe impl<const ALIGN: usize> Storage for AlignedStorage<ALIGN> {
+    type Column<T> = allocator_api2::vec::Vec<T, AlignedAlloc<ALIGN>>;
+}
+
+/// An allocator aligning the memory it allocates to `ALIGN` bytes, and padding it to a whole
+/// number of them, on top of the global allocator.
+///
+/// `ALIGN` has to be a power of two.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AlignedAlloc<const ALIGN: usize>;
+
+#[cfg(feature = "alloc")]
+impl<const ALIGN: usize> AlignedAlloc<ALIGN> {
+    /// Returns the layout actually allocated for `layout`.
+    fn aligned(layout: core::alloc::Layout) -> Option<core::alloc::Layout> {
+        Some(layout.align_to(ALIGN).ok()?.pad_to_align())
+    }
+}
+
+// SAFETY: Every block is allocated and deallocated by `Global`, with the layout it is given
+// aligned and padded the same way both times
+#[cfg(feature = "alloc")]
+unsafe impl<const ALIGN: usize> allocator_api2::alloc::Allocator for AlignedAlloc<ALIGN> {
+    fn allocate(
+        &self,
+        layout: core::alloc::Layout,
+    ) -> Result<core::ptr::NonNull<[u8]>, allocator_api2::alloc::AllocError> {
+        let layout = Self::aligned(layout).ok_or(allocator_api2::alloc::AllocError)?;
+        allocator_api2::alloc::Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: core::ptr::NonNull<u8>, layout: core::alloc::Layout) {
+        // The layout fits the block, which is between the size that was asked for and the padded
+        // size, both of which are padded to the same size
+        if let Some(layout) = Self::aligned(layout) {
+            // SAFETY: `ptr` was allocated by `Global` with the same aligned and padded layout
+            unsafe { allocator_api2::alloc::Global.deallocate(ptr, layout) };
+        }
+    }
+}
+
+// SAFETY: Every method is the one of the `Vec` itself
+#[cfg(feature = "alloc")]
+unsafe impl<T, const ALIGN: usize> Column<T> for allocator_api2::vec::Vec<T, AlignedAlloc<ALIGN>> {
+    fn new() -> Self {
+        allocator_api2::vec::Vec::new_in(AlignedAlloc)
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        allocator_api2::vec::Vec::with_capacity_in(capacity, AlignedAlloc)
+    }
+
+    fn push(&mut self, value: T) {
+        self.push(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn insert(&mut self, index: usize, element: T) {
+        self.insert(index, element);
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        self.remove(index)
+    }
+
+    fn swap_remove(&mut self, index: usize) -> T {
+        self.swap_remove(index)
+    }
+
+    fn clear(&mut self) {
+        self.clear();
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.shrink_to_fit();
+    }
+}
+
+/// Stores every column in a `SmallVec`, holding up to `N` elements inline before it allocates.
+#[cfg(feature = "smallvec")]
+pub struct SmallVecStorage<const N: usize>;
+
+// SAFETY: `SmallVec` is a `Column`
+#[cfg(feature = "smallvec")]
+unsafe impl<const N: usize> Storage for SmallVecStorage<N> {
+    type Column<T> = smallvec::SmallVec<[T; N]>;
+}
+
+// SAFETY: Every method is the one of `SmallVec` itself, which behaves like the one of `Vec`
+#[cfg(feature = "smallvec")]
+unsafe impl<T, const N: usize> Column<T> for smallvec::SmallVec<[T; N]> {
+    fn new() -> Self {
+        smallvec::SmallVec::new()
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        smallvec::SmallVec::with_capacity(capacity)
+    }
+
+    fn push(&mut self, value: T) {
+        self.push(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn insert(&mut self, index: usize, element: T) {
+        self.insert(index, element);
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        self.remove(index)
+    }
+
+    fn swap_remove(&mut self, index: usize) -> T {
+        self.swap_remove(index)
+    }
+
+    fn clear(&mut self) {
+        self.clear();
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.shrink_to_fit();
+    }
+}
+
+/// Stores every column in an `ArrayVec`, holding up to `N` elements inline and never allocating.
+///
+/// Adding more than `N` elements panics, and the capacity given to `with_capacity` is ignored.
+#[cfg(feature = "arrayvec")]
+pub struct ArrayVecStorage<const N: usize>;
+
+// SAFETY: `ArrayVec` is a `Column`
+#[cfg(feature = "arrayvec")]
+unsafe impl<const N: usize> Storage for ArrayVecStorage<N> {
+    type Column<T> = arrayvec::ArrayVec<T, N>;
+}
+
+// SAFETY: Every method is the one of `ArrayVec` itself, which behaves like the one of `Vec` but
+// panics instead of growing past `N`
+#[cfg(feature = "arrayvec")]
+unsafe impl<T, const N: usize> Column<T> for arrayvec::ArrayVec<T, N> {
+    fn new() -> Self {
+        arrayvec::ArrayVec::new()
+    }
+
+    fn with_capacity(_capacity: usize) -> Self {
+        arrayvec::ArrayVec::new()
+    }
+
+    fn push(&mut self, value: T) {
+        self.push(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn insert(&mut self, index: usize, element: T) {
+        self.insert(index, element);
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        self.remove(index)
+    }
+
+    fn swap_remove(&mut self, index: usize) -> T {
+        self.swap_remove(index)
+    }
+
+    fn clear(&mut self) {
+        self.clear();
+    }
+}
+
+/// The column of a field given `#[ortho(column = ...)]`, which the ortho-`Vec` hands the field to
+/// instead of storing it in a [`Column`] of its storage.
+///
+/// Unlike a `Column` it doesn't have to be a slice, so it can hold its elements in any encoding,
+/// and it names what the views of the field hold, as [`Ref`](Self::Ref) and
+/// [`RefMut`](Self::RefMut), which don't have to be references either.
+/// The ortho-`Vec` keeps its length, so every method changing the length has to change it by
+/// exactly one element.
+pub trait ColumnStorage<T>: IntoIterator<Item = T> + Sized {
+    /// What the views hold for an element.
+    type Ref<'a>
+    where
+        Self: 'a;
+    /// What the mutable views hold for an element.
+    type RefMut<'a>
+    where
+        Self: 'a;
+    /// The iterator the views are taken from.
+    type Iter<'a>: Iterator<Item = Self::Ref<'a>>
+    where
+        Self: 'a;
+    /// The iterator the mutable views are taken from.
+    type IterMut<'a>: Iterator<Item = Self::RefMut<'a>>
+    where
+        Self: 'a;
+
+    /// Constructs a new, empty column.
+    #[must_use]
+    fn new() -> Self;
+
+    /// Constructs a new, empty column with room for at least `capacity` elements.
+    #[must_use]
+    fn with_capacity(capacity: usize) -> Self {
+        let mut column = Self::new();
+        column.reserve(capacity);
+        column
+    }
+
+    /// Returns the number of elements.
+    fn len(&self) -> usize;
+
+    /// Returns whether there are no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Makes room for at least `additional` more elements, if the column can.
+    fn reserve(&mut self, additional: usize);
+
+    /// Returns the element at `index`, or `None` if it's out of bounds.
+    fn get(&self, index: usize) -> Option<Self::Ref<'_>>;
+
+    /// Returns the element at `index` to be changed, or `None` if it's out of bounds.
+    fn get_mut(&mut self, index: usize) -> Option<Self::RefMut<'_>>;
+
+    /// Returns an iterator over the elements.
+    fn iter(&self) -> Self::Iter<'_>;
+
+    /// Returns an iterator over the elements, to be changed.
+    fn iter_mut(&mut self) -> Self::IterMut<'_>;
+
+    /// Appends an element to the back.
+    fn push(&mut self, value: T);
+
+    /// Removes the last element and returns it, or `None` if there are no elements.
+    fn pop(&mut self) -> Option<T>;
+
+    /// Inserts an element at `index`, panics if `index` is greater than the length.
+    fn insert(&mut self, index: usize, element: T);
+
+    /// Same as `Vec::remove`, removes the element at `index` and returns it.
+    fn remove(&mut self, index: usize) -> T;
+
+    /// Same as `Vec::swap_remove`, removes the element at `index` and returns it.
+    ///
+    /// Removes it and moves the last element into its place by default.
+    fn swap_remove(&mut self, index: usize) -> T {
+        let value = self.remove(index);
+        if index < self.len() {
+            if let Some(last) = self.pop() {
+                self.insert(index, last);
+            }
+        }
+
+        value
+    }
+
+    /// Removes all elements.
+    ///
+    /// Pops them one at a time by default.
+    fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
+
+    /// Shrinks the capacity as much as possible, if the column has any to shrink.
+    fn shrink_to_fit(&mut self) {}
+
+    /// Reverses the order of the elements.
+    ///
+    /// Moves the last element to the front of the rest, one at a time, by default, so it's worth
+    /// implementing for columns that can do it faster.
+    fn reverse(&mut self) {
+        for index in 0..self.len() {
+            if let Some(last) = self.pop() {
+                self.insert(index, last);
+            }
+        }
+    }
+}
+
+/// A `Vec` holds the field like the columns of `VecStorage` do, and is the simplest column to
+/// build another one on top of.
+#[cfg(feature = "alloc")]
+impl<T> ColumnStorage<T> for Vec<T> {
+    type Ref<'a>
+        = &'a T
+    where
+        T: 'a;
+    type RefMut<'a>
+        = &'a mut T
+    where
+        T: 'a;
+    type Iter<'a>
+        = core::slice::Iter<'a, T>
+    where
+        T: 'a;
+    type IterMut<'a>
+        = core::slice::IterMut<'a, T>
+    where
+        T: 'a;
+
+    fn new() -> Self {
+        Vec::new()
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        Vec::with_capacity(capacity)
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.reserve(additional);
+    }
+
+    fn get(&self, index: usize) -> Option<&T> {
+        self.as_slice().get(index)
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.as_mut_slice().get_mut(index)
+    }
+
+    fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+
+    fn iter_mut(&mut self) -> core::slice::IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
+    }
+
+    fn push(&mut self, value: T) {
+        self.push(value);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop()
+    }
+
+    fn insert(&mut self, index: usize, element: T) {
+        self.insert(index, element);
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        self.remove(index)
+    }
+
+    fn swap_remove(&mut self, index: usize) -> T {
+        self.swap_remove(index)
+    }
+
+    fn clear(&mut self) {
+        self.clear();
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.shrink_to_fit();
+    }
+
+    fn reverse(&mut self) {
+        self.as_mut_slice().reverse();
+    }
+}
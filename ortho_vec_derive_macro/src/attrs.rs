@@ -0,0 +1,568 @@
+//! Parsing of the `#[ortho(...)]` attributes.
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    meta::ParseNestedMeta, parse_quote, punctuated::Punctuated, spanned::Spanned, Attribute,
+    Fields, Ident, Lit, LitInt, LitStr, Meta, Path, Token, Type, Visibility,
+};
+
+use crate::combine_errors;
+
+fn ortho_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("ortho"))
+}
+
+fn attr_path(meta: &ParseNestedMeta) -> String {
+    meta.path.to_token_stream().to_string().replace(' ', "")
+}
+
+fn unsupported_attr_error(meta: &ParseNestedMeta) -> syn::Error {
+    meta.error(format!("unsupported ortho attribute `{}`", attr_path(meta)))
+}
+
+/// Sets `option` to `value`, each option can only be given once.
+fn set_once<T>(meta: &ParseNestedMeta, option: &mut Option<T>, value: T) -> syn::Result<()> {
+    if option.is_some() {
+        return Err(meta.error(format!("duplicate ortho attribute `{}`", attr_path(meta))));
+    }
+
+    *option = Some(value);
+    Ok(())
+}
+
+/// Parses `= "..."` into an [`Ident`] spanning the string.
+fn parse_ident_value(meta: &ParseNestedMeta) -> syn::Result<Ident> {
+    let ident: LitStr = meta.value()?.parse()?;
+    ident
+        .parse()
+        .map_err(|_| syn::Error::new_spanned(&ident, "expected an identifier, like \"Particles\""))
+}
+
+/// Parses `(...)` into the paths of the derives in it.
+fn parse_derives(meta: &ParseNestedMeta) -> syn::Result<Vec<Path>> {
+    let mut derives = Vec::new();
+    meta.parse_nested_meta(|derive| {
+        derives.push(derive.path);
+        Ok(())
+    })?;
+
+    Ok(derives)
+}
+
+/// Parses `(...)` into the attributes in it.
+fn parse_attrs(meta: &ParseNestedMeta) -> syn::Result<Vec<Meta>> {
+    let content;
+    syn::parenthesized!(content in meta.input);
+
+    Ok(Punctuated::<Meta, Token![,]>::parse_terminated(&content)?
+        .into_iter()
+        .collect())
+}
+
+/// Returns the attributes putting `derives` and `attrs` on an item.
+fn forwarded_attrs_ts(derives: &[Path], attrs: &[Meta]) -> TokenStream {
+    let derive_attr = (!derives.is_empty()).then(|| quote!(#[derive(#(#derives),*)]));
+
+    quote! {
+        #derive_attr
+        #(#[#attrs])*
+    }
+}
+
+/// Derives and attributes forwarded onto the generated types.
+#[derive(Clone, Default)]
+pub(crate) struct ForwardedAttrs {
+    /// `#[ortho(derive(...))]`, derived by the ortho-`Vec`.
+    derives: Vec<Path>,
+    /// `#[ortho(attr(...))]`, put on the ortho-`Vec`.
+    attrs: Vec<Meta>,
+    /// `#[ortho(view_derive(...))]`, derived by the view.
+    view_derives: Vec<Path>,
+    /// `#[ortho(view_attr(...))]`, put on the view.
+    view_attrs: Vec<Meta>,
+    /// `#[ortho(view_mut_derive(...))]`, derived by the mutable view.
+    view_mut_derives: Vec<Path>,
+    /// `#[ortho(view_mut_attr(...))]`, put on the mutable view.
+    view_mut_attrs: Vec<Meta>,
+}
+
+impl ForwardedAttrs {
+    /// Parses `meta` if it's one of the forwarding options, returns whether it was.
+    fn parse(&mut self, meta: &ParseNestedMeta) -> syn::Result<bool> {
+        let Some(option) = meta.path.get_ident() else {
+            return Ok(false);
+        };
+
+        match option.to_string().as_str() {
+            "derive" => self.derives.extend(parse_derives(meta)?),
+            "attr" => self.attrs.extend(parse_attrs(meta)?),
+            "view_derive" => self.view_derives.extend(parse_derives(meta)?),
+            "view_attr" => self.view_attrs.extend(parse_attrs(meta)?),
+            "view_mut_derive" => self.view_mut_derives.extend(parse_derives(meta)?),
+            "view_mut_attr" => self.view_mut_attrs.extend(parse_attrs(meta)?),
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    /// The attributes of the ortho-`Vec`.
+    pub(crate) fn ortho_vec_ts(&self) -> TokenStream {
+        forwarded_attrs_ts(&self.derives, &self.attrs)
+    }
+
+    /// The attributes of the structs held in the columns of the ortho-`Vec`, which have to derive
+    /// the same traits for the ortho-`Vec` to derive them.
+    pub(crate) fn column_ts(&self) -> TokenStream {
+        forwarded_attrs_ts(&self.derives, &[])
+    }
+
+    /// The attributes of the view.
+    pub(crate) fn view_ts(&self) -> TokenStream {
+        forwarded_attrs_ts(&self.view_derives, &self.view_attrs)
+    }
+
+    /// The attributes of the mutable view.
+    pub(crate) fn view_mut_ts(&self) -> TokenStream {
+        forwarded_attrs_ts(&self.view_mut_derives, &self.view_mut_attrs)
+    }
+}
+
+/// How the columns of the ortho-`Vec` are laid out in memory.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum ColumnsLayout {
+    /// Every column in a storage of its own, `#[ortho(layout = "columns")]`.
+    #[default]
+    Columns,
+    /// All of the columns in a single allocation, `#[ortho(layout = "single")]`.
+    Single,
+    /// The columns in blocks of a fixed number of elements, each block holding an array of them
+    /// for every column, `#[ortho(chunk = N)]`.
+    Blocks,
+}
+
+/// Parses `= "..."` into a [`ColumnsLayout`].
+fn parse_layout_value(meta: &ParseNestedMeta) -> syn::Result<ColumnsLayout> {
+    let layout: LitStr = meta.value()?.parse()?;
+
+    match layout.value().as_str() {
+        "columns" => Ok(ColumnsLayout::Columns),
+        "single" => Ok(ColumnsLayout::Single),
+        _ => Err(syn::Error::new_spanned(
+            &layout,
+            "expected a layout, either \"columns\" or \"single\"",
+        )),
+    }
+}
+
+/// Parses `= N` into the alignment of the columns in bytes, which has to be a power of two.
+fn parse_align_value(meta: &ParseNestedMeta) -> syn::Result<LitInt> {
+    let align: Lit = meta.value()?.parse()?;
+
+    match &align {
+        Lit::Int(int) if int.base10_parse().is_ok_and(usize::is_power_of_two) => Ok(int.clone()),
+        _ => Err(syn::Error::new_spanned(
+            &align,
+            "expected an alignment in bytes, a power of two like 64",
+        )),
+    }
+}
+
+/// Parses `= N` into the number of elements in a block, which can't be 0.
+fn parse_chunk_value(meta: &ParseNestedMeta) -> syn::Result<LitInt> {
+    let chunk: Lit = meta.value()?.parse()?;
+
+    match &chunk {
+        Lit::Int(int) if int.base10_parse().is_ok_and(|chunk: usize| chunk > 0) => Ok(int.clone()),
+        _ => Err(syn::Error::new_spanned(
+            &chunk,
+            "expected the number of elements in a block, like 8",
+        )),
+    }
+}
+
+/// Parses the optional `= "..."` of `dictionary` into the type of the codes, `u32` if it isn't
+/// given.
+///
+/// The type is spanned by `dictionary` itself, which the errors about the field point at.
+fn parse_dictionary_value(meta: &ParseNestedMeta) -> syn::Result<Ident> {
+    if !meta.input.peek(Token![=]) {
+        return Ok(Ident::new("u32", meta.path.span()));
+    }
+
+    let code: LitStr = meta.value()?.parse()?;
+
+    match code.value().as_str() {
+        code @ ("u8" | "u16" | "u32") => Ok(Ident::new(code, meta.path.span())),
+        _ => Err(syn::Error::new_spanned(
+            &code,
+            "expected the type of the codes, either \"u8\", \"u16\" or \"u32\"",
+        )),
+    }
+}
+
+/// How the elements of a field given `#[ortho(encoding = "...")]` are stored, with the path of the
+/// option, which the errors about the field point at.
+#[derive(Clone)]
+pub(crate) enum Encoding {
+    /// `"rle"`, as runs of equal elements.
+    RunLength(Path),
+    /// `"delta"`, as the differences between them.
+    Delta(Path),
+}
+
+impl ToTokens for Encoding {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Encoding::RunLength(path) | Encoding::Delta(path) => path.to_tokens(tokens),
+        }
+    }
+}
+
+/// Parses `= "..."` into an [`Encoding`].
+fn parse_encoding_value(meta: &ParseNestedMeta) -> syn::Result<Encoding> {
+    let encoding: LitStr = meta.value()?.parse()?;
+
+    match encoding.value().as_str() {
+        "rle" => Ok(Encoding::RunLength(meta.path.clone())),
+        "delta" => Ok(Encoding::Delta(meta.path.clone())),
+        _ => Err(syn::Error::new_spanned(
+            &encoding,
+            "expected an encoding, either \"rle\" or \"delta\"",
+        )),
+    }
+}
+
+/// Parses `= MyColumn` or `= "MyColumn"` into the type of the column of a field.
+fn parse_column_value(meta: &ParseNestedMeta) -> syn::Result<Type> {
+    let value = meta.value()?;
+    if !value.peek(LitStr) {
+        return value.parse();
+    }
+
+    let column: LitStr = value.parse()?;
+    column.parse().map_err(|_| {
+        syn::Error::new_spanned(
+            &column,
+            "expected a column type, like `QuantizedColumn` or `Vec<u32>`",
+        )
+    })
+}
+
+/// The options given with `#[ortho(...)]` on the struct (or enum) itself.
+#[derive(Default)]
+pub(crate) struct ContainerAttrs {
+    /// `#[ortho(vis = "...")]`, the visibility of the generated types.
+    pub(crate) vis: Option<Visibility>,
+    /// `#[ortho(name = "...")]`, the name of the ortho-`Vec`.
+    pub(crate) name: Option<Ident>,
+    /// `#[ortho(view = "...")]`, the name of the view.
+    pub(crate) view: Option<Ident>,
+    /// `#[ortho(view_mut = "...")]`, the name of the mutable view.
+    pub(crate) view_mut: Option<Ident>,
+    /// `#[ortho(iter = "...")]`, the name of the iterator over views.
+    pub(crate) iter: Option<Ident>,
+    /// `#[ortho(iter_mut = "...")]`, the name of the iterator over mutable views.
+    pub(crate) iter_mut: Option<Ident>,
+    /// `#[ortho(into_iter = "...")]`, the name of the iterator moving the elements out.
+    pub(crate) into_iter: Option<Ident>,
+    /// `#[ortho(tag = "...")]`, the name of the tag of an enum.
+    pub(crate) tag: Option<Ident>,
+    /// `#[ortho(storage = "...")]`, the `Storage` naming the type of the columns.
+    pub(crate) storage: Option<Type>,
+    /// `#[ortho(layout = "...")]`, how the columns are laid out in memory.
+    pub(crate) layout: Option<ColumnsLayout>,
+    /// `#[ortho(allocator = "...")]`, the name of the type param of the allocator of the columns.
+    pub(crate) allocator: Option<Ident>,
+    /// `#[ortho(align = N)]`, the alignment of the columns in bytes.
+    pub(crate) align: Option<LitInt>,
+    /// `#[ortho(chunk = N)]`, the number of elements in every block of the columns.
+    pub(crate) chunk: Option<LitInt>,
+    /// The derives and attributes forwarded onto the generated types, these can be given more
+    /// than once.
+    pub(crate) forwarded: ForwardedAttrs,
+}
+
+impl ContainerAttrs {
+    pub(crate) fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut container_attrs = Self::default();
+
+        for attr in ortho_attrs(attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("vis") {
+                    let vis: LitStr = meta.value()?.parse()?;
+                    let vis = vis.parse().map_err(|_| {
+                        syn::Error::new_spanned(
+                            &vis,
+                            "expected a visibility, like `pub` or `pub(crate)`",
+                        )
+                    })?;
+                    set_once(&meta, &mut container_attrs.vis, vis)
+                } else if meta.path.is_ident("storage") {
+                    let storage: LitStr = meta.value()?.parse()?;
+                    let storage = storage.parse().map_err(|_| {
+                        syn::Error::new_spanned(
+                            &storage,
+                            "expected a storage type, like `VecStorage` or `SmallVecStorage<8>`",
+                        )
+                    })?;
+                    set_once(&meta, &mut container_attrs.storage, storage)
+                } else if meta.path.is_ident("layout") {
+                    let layout = parse_layout_value(&meta)?;
+                    set_once(&meta, &mut container_attrs.layout, layout)
+                } else if meta.path.is_ident("allocator") {
+                    let allocator: LitStr = meta.value()?.parse()?;
+                    let allocator = allocator.parse().map_err(|_| {
+                        syn::Error::new_spanned(
+                            &allocator,
+                            "expected the name of a type param, like \"A\"",
+                        )
+                    })?;
+                    set_once(&meta, &mut container_attrs.allocator, allocator)
+                } else if meta.path.is_ident("align") {
+                    let align = parse_align_value(&meta)?;
+                    set_once(&meta, &mut container_attrs.align, align)
+                } else if meta.path.is_ident("chunk") {
+                    let chunk = parse_chunk_value(&meta)?;
+                    set_once(&meta, &mut container_attrs.chunk, chunk)
+                } else if let Some(name_option) = container_attrs.name_option(&meta) {
+                    let name = parse_ident_value(&meta)?;
+                    set_once(&meta, name_option, name)
+                } else if container_attrs.forwarded.parse(&meta)? {
+                    Ok(())
+                } else {
+                    Err(unsupported_attr_error(&meta))
+                }
+            })?;
+        }
+
+        crate::combine_errors(
+            [
+                container_attrs.check_single_layout(),
+                container_attrs.check_allocator(),
+                container_attrs.check_align(),
+                container_attrs.check_chunk(),
+            ]
+            .into_iter()
+            .filter_map(Result::err),
+        )?;
+
+        Ok(container_attrs)
+    }
+
+    /// Returns the layout of the columns, separate columns unless asked otherwise.
+    pub(crate) fn columns_layout(&self) -> ColumnsLayout {
+        if self.chunk.is_some() {
+            ColumnsLayout::Blocks
+        } else {
+            self.layout.unwrap_or_default()
+        }
+    }
+
+    /// Returns the storage of the columns, the one aligning them when they're kept apart and given
+    /// an `align`, which a single allocation does by itself.
+    pub(crate) fn columns_storage(&self) -> Option<Type> {
+        match &self.align {
+            Some(align) if self.columns_layout() == ColumnsLayout::Columns => Some(parse_quote!(
+                ::ortho_vec_derive::storage::AlignedStorage<#align>
+            )),
+            _ => self.storage.clone(),
+        }
+    }
+
+    /// Returns an error for the options a single allocation can't be combined with: it isn't made
+    /// of storages, and the derives would need every column to implement them on its own.
+    fn check_single_layout(&self) -> syn::Result<()> {
+        if self.columns_layout() != ColumnsLayout::Single {
+            return Ok(());
+        }
+
+        let storage_error = self.storage.as_ref().map(|storage| {
+            syn::Error::new_spanned(
+                storage,
+                "a single allocation holds all of the columns, so `storage` can't be given with `layout = \"single\"`",
+            )
+        });
+        let derive_error = self.forwarded.derives.first().map(|derive| {
+            syn::Error::new_spanned(
+                derive,
+                "a single allocation holds all of the columns, so `derive` can't be given with `layout = \"single\"`",
+            )
+        });
+
+        crate::combine_errors(storage_error.into_iter().chain(derive_error))
+    }
+
+    /// Returns an error for a storage given along with an allocator, the columns in an allocator
+    /// are always `Vec`s.
+    fn check_allocator(&self) -> syn::Result<()> {
+        match (&self.allocator, &self.storage) {
+            (Some(_), Some(storage)) => Err(syn::Error::new_spanned(
+                storage,
+                "the columns in an allocator are `Vec`s, so `storage` can't be given with `allocator`",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns an error for the options the alignment can't be combined with, as the columns kept
+    /// apart are aligned by a storage of their own, which is in an allocator of its own.
+    fn check_align(&self) -> syn::Result<()> {
+        if self.align.is_none() {
+            return Ok(());
+        }
+
+        let storage_error = self.storage.as_ref().map(|storage| {
+            syn::Error::new_spanned(
+                storage,
+                "the aligned columns are in a storage of their own, so `storage` can't be given with `align`",
+            )
+        });
+        let allocator_error = self
+            .allocator
+            .as_ref()
+            .filter(|_| self.columns_layout() == ColumnsLayout::Columns)
+            .map(|allocator| {
+                syn::Error::new_spanned(
+                    allocator,
+                    "the aligned columns are in an allocator of their own, so `allocator` can't be given with `align`, unless they're in a single allocation with `layout = \"single\"`",
+                )
+            });
+
+        crate::combine_errors(storage_error.into_iter().chain(allocator_error))
+    }
+
+    /// Returns an error for the options the blocks can't be combined with: they are a layout of
+    /// their own, their elements aren't aligned, and the derives would need every column to
+    /// implement them on its own.
+    fn check_chunk(&self) -> syn::Result<()> {
+        let Some(chunk) = &self.chunk else {
+            return Ok(());
+        };
+
+        let layout_error = self.layout.map(|_| {
+            syn::Error::new_spanned(
+                chunk,
+                "blocks of elements are a layout of their own, so `layout` can't be given with `chunk`",
+            )
+        });
+        let align_error = self.align.as_ref().map(|align| {
+            syn::Error::new_spanned(
+                align,
+                "the columns in blocks are split at every block, so `align` can't be given with `chunk`",
+            )
+        });
+        let derive_error = self.forwarded.derives.first().map(|derive| {
+            syn::Error::new_spanned(
+                derive,
+                "the blocks hold elements that may be uninitialized, so `derive` can't be given with `chunk`",
+            )
+        });
+
+        crate::combine_errors(
+            layout_error
+                .into_iter()
+                .chain(align_error)
+                .chain(derive_error),
+        )
+    }
+
+    /// Returns the option holding the name of a generated item, if `meta` is one.
+    fn name_option(&mut self, meta: &ParseNestedMeta) -> Option<&mut Option<Ident>> {
+        let name_option = match meta.path.get_ident()?.to_string().as_str() {
+            "name" => &mut self.name,
+            "view" => &mut self.view,
+            "view_mut" => &mut self.view_mut,
+            "iter" => &mut self.iter,
+            "iter_mut" => &mut self.iter_mut,
+            "into_iter" => &mut self.into_iter,
+            "tag" => &mut self.tag,
+            _ => return None,
+        };
+
+        Some(name_option)
+    }
+}
+
+/// The options given with `#[ortho(...)]` on a field of the struct.
+#[derive(Default)]
+pub(crate) struct FieldAttrs {
+    /// `#[ortho(group = "...")]`, the group of fields the field is stored with.
+    pub(crate) group: Option<Ident>,
+    /// `#[ortho(flatten)]`, the field is stored in the columns of its own ortho-`Vec`.
+    pub(crate) flatten: Option<Path>,
+    /// `#[ortho(bitpacked)]`, the `bool` field is stored in a column of bits.
+    pub(crate) bitpacked: Option<Path>,
+    /// `#[ortho(nullable)]`, the `Option` field is stored in a validity bitmap and a column of the
+    /// present values.
+    pub(crate) nullable: Option<Path>,
+    /// `#[ortho(dictionary)]` or `#[ortho(dictionary = "...")]`, the field is stored in a column
+    /// of codes of this type and a dictionary of its distinct values.
+    pub(crate) dictionary: Option<Ident>,
+    /// `#[ortho(encoding = "...")]`, the field is stored encoded in a column of its own.
+    pub(crate) encoding: Option<Encoding>,
+    /// `#[ortho(column = ...)]`, the field is stored in a `ColumnStorage` of this type.
+    pub(crate) column: Option<Type>,
+}
+
+impl FieldAttrs {
+    pub(crate) fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut field_attrs = Self::default();
+
+        for attr in ortho_attrs(attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("group") {
+                    let group = parse_ident_value(&meta)?;
+                    set_once(&meta, &mut field_attrs.group, group)
+                } else if meta.path.is_ident("flatten") {
+                    set_once(&meta, &mut field_attrs.flatten, meta.path.clone())
+                } else if meta.path.is_ident("bitpacked") {
+                    set_once(&meta, &mut field_attrs.bitpacked, meta.path.clone())
+                } else if meta.path.is_ident("nullable") {
+                    set_once(&meta, &mut field_attrs.nullable, meta.path.clone())
+                } else if meta.path.is_ident("dictionary") {
+                    let code = parse_dictionary_value(&meta)?;
+                    set_once(&meta, &mut field_attrs.dictionary, code)
+                } else if meta.path.is_ident("encoding") {
+                    let encoding = parse_encoding_value(&meta)?;
+                    set_once(&meta, &mut field_attrs.encoding, encoding)
+                } else if meta.path.is_ident("column") {
+                    let column = parse_column_value(&meta)?;
+                    set_once(&meta, &mut field_attrs.column, column)
+                } else {
+                    Err(unsupported_attr_error(&meta))
+                }
+            })?;
+        }
+
+        Ok(field_attrs)
+    }
+
+    /// Parses the options given on each of `fields`, in the order of the fields.
+    pub(crate) fn from_fields(fields: &Fields) -> syn::Result<Vec<Self>> {
+        let mut all_field_attrs = Vec::new();
+        let mut errors = Vec::new();
+
+        for field in fields {
+            match Self::from_attrs(&field.attrs) {
+                Ok(field_attrs) => all_field_attrs.push(field_attrs),
+                Err(err) => errors.push(err),
+            }
+        }
+
+        combine_errors(errors)?;
+
+        Ok(all_field_attrs)
+    }
+}
+
+/// Enum variants take no options, as they all share the options of the enum.
+pub(crate) fn check_variant_attrs(attrs: &[Attribute]) -> syn::Result<()> {
+    ortho_attrs(attrs).next().map_or(Ok(()), |attr| {
+        Err(syn::Error::new_spanned(
+            attr,
+            "ortho attributes go on the enum itself, not on its variants",
+        ))
+    })
+}
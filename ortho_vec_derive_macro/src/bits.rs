@@ -0,0 +1,113 @@
+//! The columns of the `bool` fields given `#[ortho(bitpacked)]`, which pack the fields in bits.
+//!
+//! Every such field gets a `BitVec` column of its own, holding the words the bits are packed in in
+//! the storage of the ortho-`Vec`.
+//! As single bits can't be borrowed, the views hold a `BitRef` or a `BitMut` for these fields, and
+//! the ortho-`Vec` hands out the whole column for the operations going over a word at a time.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Type;
+
+use crate::{
+    attrs::FieldAttrs, columns, combine_errors, flattened_error, storage_column_ty_ts,
+    stored_apart_layout_error, Column, OrthoStructInput,
+};
+
+/// Returns whether `ty` is `bool`, the only type of field that can be bit-packed.
+fn is_bool(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.qself.is_none() && type_path.path.is_ident("bool"))
+}
+
+/// Returns an error for every bit-packed field that isn't a `bool`, is flattened, or is in an
+/// ortho-`Vec` that can't hold a column of bits.
+pub(crate) fn check_fields(input: &OrthoStructInput) -> syn::Result<()> {
+    combine_errors(input.fields().flat_map(|(field, field_attrs)| {
+        let FieldAttrs {
+            bitpacked: Some(bitpacked),
+            flatten,
+            ..
+        } = field_attrs
+        else {
+            return Vec::new();
+        };
+
+        let ty_error = (!is_bool(&field.ty))
+            .then(|| syn::Error::new_spanned(&field.ty, "only `bool` fields can be bit-packed"));
+        let flatten_error = flatten
+            .as_ref()
+            .map(|flatten| flattened_error(flatten, "bit-packed"));
+        let layout_error = stored_apart_layout_error("bit-packed fields", input, bitpacked);
+
+        ty_error
+            .into_iter()
+            .chain(flatten_error)
+            .chain(layout_error)
+            .collect()
+    }))
+}
+
+/// Returns the type of a column of bits, holding its words in the storage of the ortho-`Vec`.
+pub(crate) fn vec_ty_ts(input: &OrthoStructInput) -> TokenStream {
+    let words_ty = storage_column_ty_ts(input.storage.as_ref(), None, &quote!(u64));
+
+    quote!(::ortho_vec_derive::bits::BitVec<#words_ty>)
+}
+
+/// Returns the methods handing out the column of each bit-packed field, which the ortho-`Vec` has
+/// when it has any.
+pub(crate) fn build_ortho_vec_bits_impl(input: &OrthoStructInput) -> TokenStream {
+    let OrthoStructInput {
+        names,
+        where_clause,
+        ..
+    } = input;
+
+    let bits_methods = columns(input)
+        .into_iter()
+        .filter_map(|column| {
+            let Column::Bits { ident, .. } = &column else {
+                return None;
+            };
+
+            let bits = format_ident!("{}_bits", ident);
+            let bits_mut = format_ident!("{}_bits_mut", ident);
+            let bits_doc = format!(
+                "Returns the bits of the `{ident}` column, to count them or go over the set ones a \
+                word at a time."
+            );
+            let bits_mut_doc = format!(
+                "Returns the bits of the `{ident}` column, to be set in place or combined with \
+                other bits a word at a time."
+            );
+
+            Some(quote! {
+                #[doc = #bits_doc]
+                pub fn #bits(&self) -> ::ortho_vec_derive::bits::Bits<'_> {
+                    self.#column.bits()
+                }
+
+                #[doc = #bits_mut_doc]
+                pub fn #bits_mut(&mut self) -> ::ortho_vec_derive::bits::BitsMut<'_> {
+                    self.#column.bits_mut()
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if bits_methods.is_empty() {
+        return quote! {};
+    }
+
+    let ortho_vec_name = &names.ortho_vec;
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (ortho_vec_impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    quote! {
+        impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause
+        {
+            #(#bits_methods)*
+        }
+    }
+}
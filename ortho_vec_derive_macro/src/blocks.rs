@@ -0,0 +1,1041 @@
+//! The ortho-`Vec` of `#[ortho(chunk = N)]`, holding its columns in blocks of `N` elements (an
+//! array of structs of arrays).
+//!
+//! Every block is a struct with an array of `N` elements for each column, which may be
+//! uninitialized, and the blocks are kept in a single column of the storage of the ortho-`Vec`.
+//! Only the last block isn't full, so the element at `index` is at `index % N` in the block at
+//! `index / N`, and the ortho-`Vec` moves the elements in and out of the blocks itself.
+//! The column of blocks is kept along with the length in a `Blocks`, so code in the module of the
+//! struct can't change either of them without `unsafe`.
+//! The views and the iterators over them borrow the initialized elements of each block as slices.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Ident, Lifetime, LitInt};
+
+use crate::{
+    add_lifetime_to_generics, attrs::ColumnsLayout, columns, combine_errors, doc_attrs_ts,
+    fields_values_ts, storage_column_ts, storage_column_ty_ts, transform_columns_into_ts,
+    view_marker_value_ts, Column, OrthoStructInput,
+};
+
+/// Returns an error for every flattened field of a struct with its columns in blocks.
+pub(crate) fn check_fields(input: &OrthoStructInput) -> syn::Result<()> {
+    if input.layout != ColumnsLayout::Blocks {
+        return Ok(());
+    }
+
+    combine_errors(input.field_attrs.iter().filter_map(|field_attrs| {
+        let flatten = field_attrs.flatten.as_ref()?;
+
+        Some(syn::Error::new_spanned(
+            flatten,
+            "flattened fields are stored in the columns of their own type, so they can't be in blocks",
+        ))
+    }))
+}
+
+/// Returns the number of elements in every block.
+fn chunk(input: &OrthoStructInput) -> &LitInt {
+    input
+        .chunk
+        .as_ref()
+        .expect("the columns are in blocks only when given a chunk")
+}
+
+/// Returns the name of the blocks, which are named after the ortho-`Vec`.
+fn block_name(input: &OrthoStructInput) -> Ident {
+    format_ident!("{}Block", input.names.ortho_vec)
+}
+
+/// Returns the type of the blocks, a struct holding an array of elements for every column.
+fn block_ty_ts(input: &OrthoStructInput) -> TokenStream {
+    let block_name = block_name(input);
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    quote!(#block_name #ty_generics)
+}
+
+/// Returns the type of the column holding the blocks.
+fn blocks_ty_ts(input: &OrthoStructInput) -> TokenStream {
+    storage_column_ty_ts(
+        input.storage.as_ref(),
+        input.allocator.as_ref(),
+        &block_ty_ts(input),
+    )
+}
+
+/// Returns the type `ty` of the `blocks` module, holding the column of blocks.
+fn ortho_blocks_ty_ts(input: &OrthoStructInput, ty: &str) -> TokenStream {
+    let ty = Ident::new(ty, proc_macro2::Span::call_site());
+    let blocks_ty = blocks_ty_ts(input);
+    let chunk = chunk(input);
+
+    quote!(::ortho_vec_derive::blocks::#ty<#blocks_ty, #chunk>)
+}
+
+/// Returns the implementation the column holding the blocks is changed through.
+fn blocks_ops_ts(input: &OrthoStructInput) -> TokenStream {
+    storage_column_ts(
+        input.storage.as_ref(),
+        input.allocator.as_ref(),
+        &block_ty_ts(input),
+    )
+}
+
+/// Returns the function reaching the elements of a column in a block.
+fn column_fn_ts(column: &Column) -> TokenStream {
+    quote!(|block| &mut block.#column)
+}
+
+/// Returns the statements pushing a new block without any elements onto the blocks of `self`,
+/// which reach the storage of the blocks in an `unsafe` block.
+fn push_block_ts(input: &OrthoStructInput) -> TokenStream {
+    let block_name = block_name(input);
+    let blocks_ops = blocks_ops_ts(input);
+    let uninit_columns = transform_columns_into_ts(input, &|column| {
+        quote! {
+            #column: ::ortho_vec_derive::blocks::uninit(),
+        }
+    });
+
+    quote! {
+        #blocks_ops::push(self.__blocks.storage_mut(), #block_name {
+            #uninit_columns
+            __marker: ::core::marker::PhantomData,
+        });
+    }
+}
+
+fn build_block_struct(input: &OrthoStructInput) -> TokenStream {
+    let OrthoStructInput {
+        name,
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    let block_name = block_name(input);
+    let chunk = chunk(input);
+    let (_, ty_generics, _) = generics.split_for_impl();
+
+    let columns_decl = transform_columns_into_ts(input, &|column| {
+        let ty = column.ty_ts();
+
+        quote! {
+            #column: [::core::mem::MaybeUninit<#ty>; #chunk],
+        }
+    });
+
+    // The marker makes sure all generics are used, even if only marker fields use them
+    quote! {
+        struct #block_name #generics
+        #where_clause
+        {
+            #columns_decl
+            __marker: ::core::marker::PhantomData<#name #ty_generics>,
+        }
+    }
+}
+
+pub(crate) fn build_ortho_vec_struct(input: &OrthoStructInput) -> (Ident, TokenStream) {
+    let OrthoStructInput {
+        name,
+        generics,
+        where_clause,
+        vis,
+        names,
+        ..
+    } = input;
+
+    let ortho_vec_name = &names.ortho_vec;
+    let ortho_vec_attrs = input.forwarded_attrs.ortho_vec_ts();
+    let chunk = chunk(input);
+    let block_struct = build_block_struct(input);
+    let ortho_blocks_ty = ortho_blocks_ty_ts(input, "Blocks");
+
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (ortho_vec_impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    let ortho_vec_doc = format!(
+        "An orthogonal `Vec` of [`{name}`], holding its fields in blocks of {chunk} elements, with an array for each field in every block."
+    );
+
+    // Converted `Vec`s are in the global allocator, so their elements are too, and there are no
+    // `Vec`s to convert without `alloc`
+    (
+        ortho_vec_name.clone(),
+        quote!(
+            #block_struct
+
+            #[doc = #ortho_vec_doc]
+            #ortho_vec_attrs
+            #vis struct #ortho_vec_name #ortho_vec_generics
+            #where_clause
+            {
+                __blocks: #ortho_blocks_ty,
+                __marker: ::core::marker::PhantomData<#name #ty_generics>,
+            }
+
+            impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+            #where_clause
+            {
+                /// Returns the number of elements.
+                pub fn len(&self) -> usize {
+                    self.__blocks.len()
+                }
+            }
+
+            ::ortho_vec_derive::__with_alloc! {
+                impl #impl_generics ::core::convert::From<::ortho_vec_derive::alloc::vec::Vec<#name #ty_generics>> for #ortho_vec_name #ty_generics
+                #where_clause
+                {
+                    fn from(value: ::ortho_vec_derive::alloc::vec::Vec<#name #ty_generics>) -> Self {
+                        let mut v = Self::with_capacity(value.len());
+
+                        for p in value {
+                            v.push(p);
+                        }
+
+                        v
+                    }
+                }
+            }
+
+            impl #ortho_vec_impl_generics ::core::ops::Drop for #ortho_vec_name #ortho_vec_ty_generics
+            #where_clause
+            {
+                fn drop(&mut self) {
+                    self.clear();
+                }
+            }
+        ),
+    )
+}
+
+fn impl_vec_push(input: &OrthoStructInput, ortho_vec_name: &Ident) -> TokenStream {
+    let OrthoStructInput {
+        name: struct_name,
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    let chunk = chunk(input);
+    let push_block = push_block_ts(input);
+    let write_value_into_columns = transform_columns_into_ts(input, &|column| {
+        let element = column.element_ts(&quote!(value));
+
+        quote! {
+            block.#column[len % #chunk].write(#element);
+        }
+    });
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    // SAFETY: The first `len` elements of every column are initialized, after adding a block to
+    // full blocks there's room for one more, and it's initialized before the length is set
+    quote! {
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            /// Appends an element to the back, adding a block if the last one is full.
+            pub fn push(&mut self, value: #struct_name #ty_generics) {
+                let len = self.__blocks.len();
+
+                unsafe {
+                    if len % #chunk == 0 {
+                        #push_block
+                    }
+                    let block = &mut self.__blocks.blocks_mut()[len / #chunk];
+                    #write_value_into_columns
+                    self.__blocks.set_len(len + 1);
+                }
+            }
+        }
+    }
+}
+
+fn impl_vec_pop(input: &OrthoStructInput, ortho_vec_name: &Ident) -> TokenStream {
+    let OrthoStructInput {
+        name: struct_name,
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    let chunk = chunk(input);
+    let blocks_ops = blocks_ops_ts(input);
+
+    // SAFETY: The element at the new length is initialized, and is left out of it before it is
+    // read, after which the last block is popped if it holds no elements left
+    let (bind_popped_groups, read_columns_assign_member) = fields_values_ts(
+        input,
+        &|column| quote!(unsafe { block.#column[len % #chunk].assume_init_read() }),
+        &quote!(),
+    );
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            /// Removes the last element and returns it, or `None` if there are no elements.
+            pub fn pop(&mut self) -> ::core::option::Option<#struct_name #ty_generics> {
+                let len = match self.__blocks.len() {
+                    0 => return ::core::option::Option::None,
+                    len => len - 1,
+                };
+
+                unsafe { self.__blocks.set_len(len) };
+                let block = &self.__blocks.blocks()[len / #chunk];
+                #bind_popped_groups
+                let value = #struct_name {
+                    #read_columns_assign_member
+                };
+
+                if len % #chunk == 0 {
+                    unsafe { #blocks_ops::pop(self.__blocks.storage_mut()) };
+                }
+                ::core::option::Option::Some(value)
+            }
+        }
+    }
+}
+
+fn impl_vec_clear(input: &OrthoStructInput, ortho_vec_name: &Ident) -> TokenStream {
+    let OrthoStructInput { where_clause, .. } = input;
+
+    let blocks_ops = blocks_ops_ts(input);
+    let drop_columns = transform_columns_into_ts(input, &|column| {
+        let column_fn = column_fn_ts(column);
+
+        quote! {
+            ::ortho_vec_derive::blocks::drop_range(blocks, #column_fn, 0, len);
+        }
+    });
+
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    // SAFETY: The first `len` elements of every column are initialized, and they are left out of
+    // the length before they are dropped, so a panicking drop leaks the rest instead of dropping
+    // them twice, after which no block holds any elements
+    quote! {
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            /// Removes all elements, keeping the capacity.
+            pub fn clear(&mut self) {
+                let len = self.__blocks.len();
+
+                unsafe {
+                    self.__blocks.set_len(0);
+                    let blocks = self.__blocks.blocks_mut();
+                    #drop_columns
+                    #blocks_ops::clear(self.__blocks.storage_mut());
+                }
+            }
+        }
+    }
+}
+
+fn impl_vec_reverse(input: &OrthoStructInput, ortho_vec_name: &Ident) -> TokenStream {
+    let OrthoStructInput { where_clause, .. } = input;
+
+    let reverse_columns = transform_columns_into_ts(input, &|column| {
+        let column_fn = column_fn_ts(column);
+
+        quote! {
+            ::ortho_vec_derive::blocks::reverse(blocks, #column_fn, len);
+        }
+    });
+
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    // SAFETY: Reversing the first `len` elements of every column keeps them initialized
+    quote! {
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            /// Reverses the order of the elements.
+            pub fn reverse(&mut self) {
+                let len = self.__blocks.len();
+
+                let blocks = unsafe { self.__blocks.blocks_mut() };
+                #reverse_columns
+            }
+        }
+    }
+}
+
+fn impl_vec_insert(input: &OrthoStructInput, ortho_vec_name: &Ident) -> TokenStream {
+    let OrthoStructInput {
+        name: struct_name,
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    let chunk = chunk(input);
+    let push_block = push_block_ts(input);
+    let insert_element_into_columns = transform_columns_into_ts(input, &|column| {
+        let element = column.element_ts(&quote!(element));
+        let column_fn = column_fn_ts(column);
+
+        quote! {
+            ::ortho_vec_derive::blocks::insert(blocks, #column_fn, len, index, #element);
+        }
+    });
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    // SAFETY: The first `len` elements of every column are initialized, `index` is at most `len`,
+    // after adding a block to full blocks there's room for one more, and one more is initialized
+    // before the length is set
+    quote! {
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            /// Inserts an element at `index`, panics if `index` is greater than the length.
+            pub fn insert(&mut self, index: usize, element: #struct_name #ty_generics) {
+                let len = self.__blocks.len();
+                ::core::assert!(index <= len, "insertion index (is {index}) should be <= len (is {len})");
+
+                unsafe {
+                    if len % #chunk == 0 {
+                        #push_block
+                    }
+                    let blocks = self.__blocks.blocks_mut();
+                    #insert_element_into_columns
+                    self.__blocks.set_len(len + 1);
+                }
+            }
+        }
+    }
+}
+
+fn impl_vec_method_mut_self_index_ret_struct(
+    input: &OrthoStructInput,
+    method_name: &str,
+    ortho_vec_name: &Ident,
+) -> TokenStream {
+    let OrthoStructInput {
+        name: struct_name,
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    let doc =
+        format!("Same as `Vec::{method_name}`, removes the element at `index` and returns it.");
+    let method_name = Ident::new(method_name, proc_macro2::Span::call_site());
+    let chunk = chunk(input);
+    let blocks_ops = blocks_ops_ts(input);
+
+    // SAFETY: The first `len` elements of every column are initialized and `index` is less than
+    // `len`, the element moved into the place of the removed one is left out of the new length,
+    // and the last block is popped if it holds no elements left
+    let (bind_removed_groups, remove_from_columns_assign_member) = fields_values_ts(
+        input,
+        &|column| {
+            let column_fn = column_fn_ts(column);
+            quote!(unsafe { ::ortho_vec_derive::blocks::#method_name(blocks, #column_fn, len, index) })
+        },
+        &quote!(),
+    );
+    let index_out_of_bounds_message =
+        format!("{method_name} index (is {{index}}) should be < len (is {{len}})");
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            #[doc = #doc]
+            pub fn #method_name(&mut self, index: usize) -> #struct_name #ty_generics {
+                let len = self.__blocks.len();
+                ::core::assert!(index < len, #index_out_of_bounds_message);
+
+                let blocks = unsafe {
+                    self.__blocks.set_len(len - 1);
+                    self.__blocks.blocks_mut()
+                };
+                #bind_removed_groups
+                let value = #struct_name {
+                    #remove_from_columns_assign_member
+                };
+
+                if (len - 1) % #chunk == 0 {
+                    unsafe { #blocks_ops::pop(self.__blocks.storage_mut()) };
+                }
+                value
+            }
+        }
+    }
+}
+
+fn impl_vec_new(input: &OrthoStructInput, ortho_vec_name: &Ident) -> TokenStream {
+    let OrthoStructInput {
+        generics,
+        where_clause,
+        allocator,
+        ..
+    } = input;
+
+    let chunk = chunk(input);
+    let blocks_ops = blocks_ops_ts(input);
+
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (ortho_vec_impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    // With an allocator the constructors are implemented for any allocator, and the ones without
+    // it construct the ortho-`Vec` in the global allocator
+    let (constructors_impl_generics, constructors_ty_generics, new, with_capacity, global_ctors) =
+        if let Some(allocator) = allocator {
+            (
+                &ortho_vec_impl_generics,
+                &ortho_vec_ty_generics,
+                quote!(new_in(alloc: #allocator)),
+                quote!(with_capacity_in(capacity: usize, alloc: #allocator)),
+                Some(quote! {
+                    impl #impl_generics #ortho_vec_name #ty_generics
+                    #where_clause {
+                        /// Constructs a new, empty orthogonal `Vec` in the global allocator,
+                        /// without allocating.
+                        pub fn new() -> Self {
+                            Self::new_in(::ortho_vec_derive::allocator_api2::alloc::Global)
+                        }
+
+                        /// Constructs a new, empty orthogonal `Vec` in the global allocator,
+                        /// with room for at least `capacity` elements in its blocks.
+                        pub fn with_capacity(capacity: usize) -> Self {
+                            Self::with_capacity_in(
+                                capacity,
+                                ::ortho_vec_derive::allocator_api2::alloc::Global,
+                            )
+                        }
+                    }
+                }),
+            )
+        } else {
+            (
+                &impl_generics,
+                &ty_generics,
+                quote!(new()),
+                quote!(with_capacity(capacity: usize)),
+                None,
+            )
+        };
+    let (new_blocks, with_capacity_blocks) = if allocator.is_some() {
+        (
+            quote!(#blocks_ops::new_in(alloc)),
+            quote!(#blocks_ops::with_capacity_in(::ortho_vec_derive::blocks::block_count::<#chunk>(capacity), alloc)),
+        )
+    } else {
+        (
+            quote!(#blocks_ops::new()),
+            quote!(#blocks_ops::with_capacity(::ortho_vec_derive::blocks::block_count::<#chunk>(capacity))),
+        )
+    };
+    let in_alloc = if allocator.is_some() {
+        " in `alloc`"
+    } else {
+        ""
+    };
+    let new_doc = format!("Constructs a new, empty orthogonal `Vec`{in_alloc}.");
+    let with_capacity_doc = format!(
+        "Constructs a new, empty orthogonal `Vec`{in_alloc} with room for at least `capacity` elements in its blocks."
+    );
+
+    // SAFETY: Shrinking keeps the blocks
+    quote! {
+        #global_ctors
+
+        impl #constructors_impl_generics #ortho_vec_name #constructors_ty_generics
+        #where_clause {
+            #[doc = #new_doc]
+            pub fn #new -> Self {
+                #ortho_vec_name {
+                    __blocks: ::ortho_vec_derive::blocks::Blocks::new(#new_blocks),
+                    __marker: ::core::marker::PhantomData,
+                }
+            }
+
+            #[doc = #with_capacity_doc]
+            pub fn #with_capacity -> Self {
+                #ortho_vec_name {
+                    __blocks: ::ortho_vec_derive::blocks::Blocks::new(#with_capacity_blocks),
+                    __marker: ::core::marker::PhantomData,
+                }
+            }
+        }
+
+        impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            /// Shrinks the blocks to fit the elements.
+            pub fn shrink_to_fit(&mut self) {
+                #blocks_ops::shrink_to_fit(unsafe { self.__blocks.storage_mut() });
+            }
+        }
+    }
+}
+
+pub(crate) fn build_ortho_vec_impl_vec_methods(
+    input: &OrthoStructInput,
+    ortho_vec_name: &Ident,
+) -> TokenStream {
+    let push = impl_vec_push(input, ortho_vec_name);
+
+    let pop = impl_vec_pop(input, ortho_vec_name);
+
+    let clear = impl_vec_clear(input, ortho_vec_name);
+
+    let reverse = impl_vec_reverse(input, ortho_vec_name);
+
+    let insert = impl_vec_insert(input, ortho_vec_name);
+
+    let mut_self_index_ret_struct_methods = ["remove", "swap_remove"].iter().map(|method_name| {
+        impl_vec_method_mut_self_index_ret_struct(input, method_name, ortho_vec_name)
+    });
+
+    let new = impl_vec_new(input, ortho_vec_name);
+
+    quote! {
+        #push
+        #pop
+        #clear
+        #reverse
+        #insert
+        #(#mut_self_index_ret_struct_methods)*
+        #new
+    }
+}
+
+/// Returns the iterator over views of the elements, which borrows the elements of one block at a
+/// time as slices.
+pub(crate) fn build_ortho_vec_iter_struct(
+    input: &OrthoStructInput,
+    ortho_struct_name: &Ident,
+    ortho_vec_name: &Ident,
+    ortho_lifetime: &Lifetime,
+) -> (Ident, TokenStream) {
+    build_ortho_vec_iter_struct_of(
+        input,
+        ortho_struct_name,
+        ortho_vec_name,
+        ortho_lifetime,
+        false,
+    )
+}
+
+/// Returns the iterator over mutable views of the elements, which borrows the elements of one
+/// block at a time as slices.
+pub(crate) fn build_ortho_vec_iter_mut_struct(
+    input: &OrthoStructInput,
+    ortho_struct_mut_name: &Ident,
+    ortho_vec_name: &Ident,
+    ortho_lifetime: &Lifetime,
+) -> (Ident, TokenStream) {
+    build_ortho_vec_iter_struct_of(
+        input,
+        ortho_struct_mut_name,
+        ortho_vec_name,
+        ortho_lifetime,
+        true,
+    )
+}
+
+/// Returns the iterator over views of the elements, mutable views if `mutable`.
+// Both iterators are built from the same pieces, borrowed mutably or not
+#[allow(clippy::too_many_lines)]
+fn build_ortho_vec_iter_struct_of(
+    input: &OrthoStructInput,
+    view_name: &Ident,
+    ortho_vec_name: &Ident,
+    ortho_lifetime: &Lifetime,
+    mutable: bool,
+) -> (Ident, TokenStream) {
+    let OrthoStructInput {
+        name,
+        generics,
+        where_clause,
+        vis,
+        names,
+        ..
+    } = input;
+
+    let chunk = chunk(input);
+    let block_ty = block_ty_ts(input);
+    // SAFETY: The views only reach the initialized elements, which they can't uninitialize
+    let (iter_name, mut_token, slice_iter, filled, slice_fn, split_first, method, doc) = if mutable
+    {
+        (
+            &names.iter_mut,
+            Some(quote!(mut)),
+            quote!(IterMut),
+            quote!(unsafe { self.__blocks.filled_mut() }),
+            quote!(slice_mut),
+            quote!(split_first_mut),
+            quote!(iter_mut),
+            "mutable views",
+        )
+    } else {
+        (
+            &names.iter,
+            None,
+            quote!(Iter),
+            quote!(self.__blocks.filled()),
+            quote!(slice),
+            quote!(split_first),
+            quote!(iter),
+            "views",
+        )
+    };
+
+    let ortho_generics = add_lifetime_to_generics(generics, ortho_lifetime);
+    // The method borrowing the ortho-`Vec` is implemented for it in any allocator
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (_, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+    let ortho_vec_ortho_generics = add_lifetime_to_generics(&ortho_vec_generics, ortho_lifetime);
+    let (ortho_vec_impl_generics, _, _) = ortho_vec_ortho_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let (ortho_impl_generics, ortho_ty_generics, _) = ortho_generics.split_for_impl();
+
+    let iter_define_props = transform_columns_into_ts(input, &|column| {
+        let ty = column.ty_ts();
+
+        quote! {
+            #column: &#ortho_lifetime #mut_token [#ty],
+        }
+    });
+    let iter_assign_empty_props = transform_columns_into_ts(input, &|column| {
+        quote! {
+            #column: &#mut_token [],
+        }
+    });
+
+    // SAFETY: The first `block_len` elements of every filled block are initialized
+    let assign_block_slices = transform_columns_into_ts(input, &|column| {
+        quote! {
+            self.#column = unsafe {
+                ::ortho_vec_derive::blocks::#slice_fn(&#mut_token block.#column[..block_len])
+            };
+        }
+    });
+
+    // The slices shrink with every call, until the next block is borrowed in their place
+    let take_slice = if mutable {
+        |column: &Column| quote!(::core::mem::take(&mut self.#column))
+    } else {
+        |column: &Column| quote!(self.#column)
+    };
+    let (bind_iter_groups, iter_props_assign_iter) = fields_values_ts(
+        input,
+        &|column| {
+            let slice = take_slice(column);
+
+            quote!({
+                let (first, rest) = #slice.#split_first()?;
+                self.#column = rest;
+                first
+            })
+        },
+        &quote!(&#mut_token),
+    );
+    let view_marker_value = view_marker_value_ts(input);
+    // Every column has as many elements left in the block, so the next block is borrowed once the
+    // first column has none
+    let first_column = columns(input)
+        .into_iter()
+        .next()
+        .expect("the columns are in blocks only when there are columns");
+
+    let struct_doc = format!("An iterator over {doc} of the elements of [`{ortho_vec_name}`].");
+    let method_doc = format!("Returns an iterator over {doc} of the elements.");
+
+    (
+        iter_name.clone(),
+        quote!(
+            #[doc = #struct_doc]
+            #vis struct #iter_name #ortho_generics
+            #where_clause
+            {
+                #iter_define_props
+                __blocks: ::ortho_vec_derive::blocks::Filled<::core::slice::#slice_iter<#ortho_lifetime, #block_ty>, #chunk>,
+                __marker: ::core::marker::PhantomData<&#ortho_lifetime #mut_token #name #ty_generics>,
+            }
+
+            impl #ortho_impl_generics ::core::iter::Iterator for #iter_name #ortho_ty_generics
+            #where_clause
+            {
+                type Item = #view_name #ortho_ty_generics;
+
+                #[inline]
+                fn next(&mut self) -> ::core::option::Option<Self::Item> {
+                    if self.#first_column.is_empty() {
+                        let (block, block_len) = self.__blocks.next()?;
+                        #assign_block_slices
+                    }
+                    #bind_iter_groups
+                    ::core::option::Option::Some(#view_name {
+                        #iter_props_assign_iter
+                        #view_marker_value
+                    })
+                }
+            }
+
+            impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+            #where_clause
+            {
+                #[doc = #method_doc]
+                pub fn #method(&#ortho_lifetime #mut_token self) -> #iter_name #ortho_ty_generics {
+                    #iter_name {
+                        #iter_assign_empty_props
+                        __blocks: #filled,
+                        __marker: ::core::marker::PhantomData,
+                    }
+                }
+            }
+        ),
+    )
+}
+
+pub(crate) fn build_ortho_vec_into_iter_struct(
+    input: &OrthoStructInput,
+    ortho_vec_name: &Ident,
+) -> (Ident, TokenStream) {
+    let OrthoStructInput {
+        name,
+        generics,
+        where_clause,
+        vis,
+        names,
+        ..
+    } = input;
+
+    let ortho_vec_into_iter_name = &names.into_iter;
+    let chunk = chunk(input);
+    let blocks_into_iter_ty = ortho_blocks_ty_ts(input, "BlocksIntoIter");
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    // SAFETY: The elements that weren't moved past are initialized, and every element is read once,
+    // right after moving past it
+    let (bind_read_groups, read_columns_assign_member) = fields_values_ts(
+        input,
+        &|column| quote!(unsafe { block.#column[index % #chunk].assume_init_read() }),
+        &quote!(),
+    );
+
+    let drop_rest_of_columns = transform_columns_into_ts(input, &|column| {
+        let column_fn = column_fn_ts(column);
+
+        quote! {
+            ::ortho_vec_derive::blocks::drop_range(blocks, #column_fn, rest.start, rest.end);
+        }
+    });
+
+    let doc = format!("An iterator moving the elements out of [`{ortho_vec_name}`].");
+
+    // SAFETY: The ortho-`Vec` is never dropped, so its elements are only dropped by the iterator
+    (
+        ortho_vec_into_iter_name.clone(),
+        quote!(
+            #[doc = #doc]
+            #vis struct #ortho_vec_into_iter_name #ortho_vec_generics
+            #where_clause
+            {
+                __blocks: #blocks_into_iter_ty,
+                __marker: ::core::marker::PhantomData<#name #ty_generics>,
+            }
+
+            impl #impl_generics ::core::iter::Iterator for #ortho_vec_into_iter_name #ortho_vec_ty_generics
+            #where_clause
+            {
+                type Item = #name #ty_generics;
+
+                #[inline]
+                fn next(&mut self) -> ::core::option::Option<Self::Item> {
+                    let index = self.__blocks.next_index()?;
+                    let block = &self.__blocks.blocks()[index / #chunk];
+                    #bind_read_groups
+                    ::core::option::Option::Some(#name {
+                        #read_columns_assign_member
+                    })
+                }
+            }
+
+            impl #impl_generics ::core::ops::Drop for #ortho_vec_into_iter_name #ortho_vec_ty_generics
+            #where_clause
+            {
+                fn drop(&mut self) {
+                    let rest = self.__blocks.skip_rest();
+
+                    unsafe {
+                        let blocks = self.__blocks.blocks_mut();
+                        #drop_rest_of_columns
+                    }
+                }
+            }
+
+            impl #impl_generics ::core::iter::IntoIterator for #ortho_vec_name #ortho_vec_ty_generics
+            #where_clause
+            {
+                type Item = #name #ty_generics;
+                type IntoIter = #ortho_vec_into_iter_name #ortho_vec_ty_generics;
+
+                fn into_iter(self) -> #ortho_vec_into_iter_name #ortho_vec_ty_generics {
+                    let ortho_vec = ::core::mem::ManuallyDrop::new(self);
+
+                    #ortho_vec_into_iter_name {
+                        __blocks: ::ortho_vec_derive::blocks::BlocksIntoIter::new(unsafe {
+                            ::core::ptr::read(&ortho_vec.__blocks)
+                        }),
+                        __marker: ::core::marker::PhantomData,
+                    }
+                }
+            }
+        ),
+    )
+}
+
+/// Returns the views of the blocks and the methods iterating over them, which the ortho-`Vec` has
+/// when its columns are in blocks.
+///
+/// Only the columns of single fields are in the views, the elements of groups are in a struct of
+/// their own, which can't be named outside of the generated items.
+// Both views and their methods are built from the same pieces, borrowed mutably or not
+#[allow(clippy::too_many_lines)]
+pub(crate) fn build_ortho_vec_blocks_views(input: &OrthoStructInput) -> TokenStream {
+    if input.layout != ColumnsLayout::Blocks {
+        return TokenStream::new();
+    }
+
+    let OrthoStructInput {
+        name,
+        generics,
+        where_clause,
+        vis,
+        names,
+        ..
+    } = input;
+
+    let ortho_lifetime = &Lifetime::new("'ortho", proc_macro2::Span::call_site());
+    let chunk = chunk(input);
+    let block_view_name = format_ident!("OrthoBlock{}", name);
+    let block_view_mut_name = format_ident!("OrthoBlockMut{}", name);
+    let ortho_vec_name = &names.ortho_vec;
+
+    let ortho_generics = add_lifetime_to_generics(generics, ortho_lifetime);
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (_, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+    let ortho_vec_ortho_generics = add_lifetime_to_generics(&ortho_vec_generics, ortho_lifetime);
+    let (ortho_vec_impl_generics, _, _) = ortho_vec_ortho_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let (_, ortho_ty_generics, _) = ortho_generics.split_for_impl();
+
+    let field_columns = columns(input)
+        .into_iter()
+        .filter(|column| matches!(column, Column::Field { .. }))
+        .collect::<Vec<_>>();
+
+    let view_props = |mut_token: Option<TokenStream>| {
+        field_columns.iter().map(move |column| {
+            let Column::Field { ident, field, .. } = column else {
+                unreachable!("only the columns of single fields are in the views of blocks");
+            };
+            let ty = &field.ty;
+            let field_vis = &field.vis;
+            let field_docs = doc_attrs_ts(&field.attrs);
+
+            quote! {
+                #field_docs
+                #field_vis #ident: &#ortho_lifetime #mut_token [#ty],
+            }
+        })
+    };
+    let view_props_decl = view_props(None);
+    let view_mut_props_decl = view_props(Some(quote!(mut)));
+
+    // SAFETY: The first `block_len` elements of every filled block are initialized
+    let view_props_assign = |slice_fn: TokenStream, mut_token: Option<TokenStream>| {
+        field_columns.iter().map(move |column| {
+            quote! {
+                #column: unsafe {
+                    ::ortho_vec_derive::blocks::#slice_fn(&#mut_token block.#column[..block_len])
+                },
+            }
+        })
+    };
+    let view_props_assign_block = view_props_assign(quote!(slice), None);
+    let view_mut_props_assign_block = view_props_assign(quote!(slice_mut), Some(quote!(mut)));
+
+    let view_doc = format!(
+        "A view of a block of up to {chunk} [`{name}`]s stored in an orthogonal `Vec`, with a slice of the elements of each of its fields."
+    );
+    let view_mut_doc = format!(
+        "A mutable view of a block of up to {chunk} [`{name}`]s stored in an orthogonal `Vec`, with a slice of the elements of each of its fields."
+    );
+    let blocks_doc = format!(
+        "Returns an iterator over views of the blocks of {chunk} elements, every one of them full but the last one."
+    );
+    let blocks_mut_doc = format!(
+        "Returns an iterator over mutable views of the blocks of {chunk} elements, every one of them full but the last one."
+    );
+
+    // Not every field of a view has to be read, the view is there to reach any of them
+    // SAFETY: The mutable views only reach the initialized elements, which they can't uninitialize
+    quote! {
+        #[doc = #view_doc]
+        #[allow(dead_code)]
+        #vis struct #block_view_name #ortho_generics
+        #where_clause
+        {
+            #(#view_props_decl)*
+            __marker: ::core::marker::PhantomData<&#ortho_lifetime #name #ty_generics>,
+        }
+
+        #[doc = #view_mut_doc]
+        #[allow(dead_code)]
+        #vis struct #block_view_mut_name #ortho_generics
+        #where_clause
+        {
+            #(#view_mut_props_decl)*
+            __marker: ::core::marker::PhantomData<&#ortho_lifetime mut #name #ty_generics>,
+        }
+
+        impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause
+        {
+            #[doc = #blocks_doc]
+            pub fn blocks(&#ortho_lifetime self) -> impl ::core::iter::DoubleEndedIterator<Item = #block_view_name #ortho_ty_generics> + ::core::iter::ExactSizeIterator {
+                self.__blocks.filled().map(|(block, block_len)| {
+                    #block_view_name {
+                        #(#view_props_assign_block)*
+                        __marker: ::core::marker::PhantomData,
+                    }
+                })
+            }
+
+            #[doc = #blocks_mut_doc]
+            pub fn blocks_mut(&#ortho_lifetime mut self) -> impl ::core::iter::DoubleEndedIterator<Item = #block_view_mut_name #ortho_ty_generics> + ::core::iter::ExactSizeIterator {
+                unsafe { self.__blocks.filled_mut() }.map(|(block, block_len)| {
+                    #block_view_mut_name {
+                        #(#view_mut_props_assign_block)*
+                        __marker: ::core::marker::PhantomData,
+                    }
+                })
+            }
+        }
+    }
+}
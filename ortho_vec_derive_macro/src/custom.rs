@@ -0,0 +1,149 @@
+//! The columns of the fields given `#[ortho(column = ...)]`, held in a column type of the user's.
+//!
+//! Every such field gets the given `ColumnStorage` as its column, which the ortho-`Vec` changes
+//! and iterates over only through the methods of that trait, so the column can hold its elements
+//! in any encoding.
+//! The views hold what the column names as its `Ref` and `RefMut`, and the ortho-`Vec` hands out
+//! the column itself to reach an element by its index.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Lifetime, Type};
+
+use crate::{
+    attrs::FieldAttrs, columns, combine_errors, flattened_error, stored_apart_layout_error, Column,
+    OrthoStructInput,
+};
+
+/// Returns an error for every field given a `column` that is flattened, given an encoding, or in
+/// an ortho-`Vec` that can't hold a column of its own.
+pub(crate) fn check_fields(input: &OrthoStructInput) -> syn::Result<()> {
+    combine_errors(input.field_attrs.iter().flat_map(|field_attrs| {
+        let FieldAttrs {
+            column: Some(column),
+            flatten,
+            bitpacked,
+            nullable,
+            dictionary,
+            encoding,
+            ..
+        } = field_attrs
+        else {
+            return Vec::new();
+        };
+
+        let flatten_error = flatten
+            .as_ref()
+            .map(|flatten| flattened_error(flatten, "given a `column`"));
+        let encoding = bitpacked
+            .as_ref()
+            .or(nullable.as_ref())
+            .map(|encoding| quote!(#encoding))
+            .or_else(|| dictionary.as_ref().map(|dictionary| quote!(#dictionary)))
+            .or_else(|| encoding.as_ref().map(|encoding| quote!(#encoding)));
+        let encoding_error = encoding.map(|encoding| {
+            syn::Error::new_spanned(
+                encoding,
+                "fields given a `column` are stored however that column stores them, so they can't be given an encoding",
+            )
+        });
+        let layout_error = stored_apart_layout_error("fields given a `column`", input, column);
+
+        flatten_error
+            .into_iter()
+            .chain(encoding_error)
+            .chain(layout_error)
+            .collect()
+    }))
+}
+
+/// Returns the `ColumnStorage` implementation of `column_ty` for elements of type `ty`, which
+/// the column is only ever changed through, so that methods of its own can't shadow the ones of
+/// the trait.
+pub(crate) fn ops_ts(column_ty: &Type, ty: &Type) -> TokenStream {
+    quote!(<#column_ty as ::ortho_vec_derive::storage::ColumnStorage<#ty>>)
+}
+
+/// Returns the type of the iterator over the column, borrowed mutably if `mutable`.
+pub(crate) fn iter_ty_ts(
+    column_ty: &Type,
+    ty: &Type,
+    lifetime: &Lifetime,
+    mutable: bool,
+) -> TokenStream {
+    let ops = ops_ts(column_ty, ty);
+
+    if mutable {
+        quote!(#ops::IterMut<#lifetime>)
+    } else {
+        quote!(#ops::Iter<#lifetime>)
+    }
+}
+
+/// Returns the type of the field of the view for a field stored in `column_ty`, or of the
+/// mutable view if `mutable`.
+pub(crate) fn view_field_ty_ts(
+    column_ty: &Type,
+    ty: &Type,
+    lifetime: &Lifetime,
+    mutable: bool,
+) -> TokenStream {
+    let ops = ops_ts(column_ty, ty);
+
+    if mutable {
+        quote!(#ops::RefMut<#lifetime>)
+    } else {
+        quote!(#ops::Ref<#lifetime>)
+    }
+}
+
+/// Returns the methods handing out the column of each field given a `column`, which the
+/// ortho-`Vec` has when it has any.
+pub(crate) fn build_ortho_vec_custom_impl(input: &OrthoStructInput) -> TokenStream {
+    let OrthoStructInput {
+        names,
+        where_clause,
+        ..
+    } = input;
+
+    let column_methods = columns(input)
+        .into_iter()
+        .filter_map(|column| {
+            let Column::Custom {
+                ident, column_ty, ..
+            } = &column
+            else {
+                return None;
+            };
+
+            let method = format_ident!("{}_column", ident);
+            let doc = format!(
+                "Returns the column the `{ident}` of the elements is stored in, to reach an \
+                element by its index."
+            );
+
+            Some(quote! {
+                #[doc = #doc]
+                pub fn #method(&self) -> &#column_ty {
+                    &self.#column
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if column_methods.is_empty() {
+        return quote! {};
+    }
+
+    let ortho_vec_name = &names.ortho_vec;
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (ortho_vec_impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    quote! {
+        impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause
+        {
+            #(#column_methods)*
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! The columns of the fields given `#[ortho(dictionary)]`, held as a column of small codes and a
+//! dictionary of the distinct values.
+//!
+//! Every such field gets a `DictionaryVec` column of its own, keeping both in the storage of the
+//! ortho-`Vec`.
+//! As the values are shared by every element with the same code, both views hold a shared
+//! reference to the value, and the ortho-`Vec` hands out the codes to group or filter the
+//! elements by.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::{
+    attrs::FieldAttrs, columns, combine_errors, flattened_error, stored_apart_layout_error, Column,
+    OrthoStructInput,
+};
+
+/// Returns an error for every dictionary-encoded field that is flattened, bit-packed, nullable, or
+/// in an ortho-`Vec` that can't hold a dictionary.
+pub(crate) fn check_fields(input: &OrthoStructInput) -> syn::Result<()> {
+    combine_errors(input.field_attrs.iter().flat_map(|field_attrs| {
+        let FieldAttrs {
+            dictionary: Some(dictionary),
+            flatten,
+            bitpacked,
+            nullable,
+            ..
+        } = field_attrs
+        else {
+            return Vec::new();
+        };
+
+        let flatten_error = flatten
+            .as_ref()
+            .map(|flatten| flattened_error(flatten, "dictionary-encoded"));
+        let encoding_error = bitpacked.as_ref().or(nullable.as_ref()).map(|encoding| {
+            syn::Error::new_spanned(
+                encoding,
+                "dictionary-encoded fields are stored in a column of codes, so they can't be given another encoding",
+            )
+        });
+        let layout_error =
+            stored_apart_layout_error("dictionary-encoded fields", input, dictionary);
+
+        flatten_error
+            .into_iter()
+            .chain(encoding_error)
+            .chain(layout_error)
+            .collect()
+    }))
+}
+
+/// Returns the storage the codes and the dictionary are kept in, the one of the ortho-`Vec`.
+fn storage_ts(input: &OrthoStructInput) -> TokenStream {
+    input.storage.as_ref().map_or_else(
+        || quote!(::ortho_vec_derive::storage::VecStorage),
+        |storage| quote!(#storage),
+    )
+}
+
+/// Returns the type of a dictionary-encoded column of elements of type `ty` with codes of type
+/// `code`, in the storage of the ortho-`Vec`.
+pub(crate) fn vec_ty_ts(input: &OrthoStructInput, ty: &syn::Type, code: &Ident) -> TokenStream {
+    let storage = storage_ts(input);
+
+    quote!(::ortho_vec_derive::dictionary::DictionaryVec<#ty, #code, #storage>)
+}
+
+/// Returns the methods handing out the codes and the dictionary of each dictionary-encoded field,
+/// which the ortho-`Vec` has when it has any.
+pub(crate) fn build_ortho_vec_dictionary_impl(input: &OrthoStructInput) -> TokenStream {
+    let OrthoStructInput {
+        names,
+        where_clause,
+        ..
+    } = input;
+
+    let dictionary_methods = columns(input)
+        .into_iter()
+        .filter_map(|column| {
+            let Column::Dictionary {
+                ident, field, code, ..
+            } = &column
+            else {
+                return None;
+            };
+
+            let ty = &field.ty;
+            let storage = storage_ts(input);
+            let codes = format_ident!("{}_codes", ident);
+            let dictionary = format_ident!("{}_dictionary", ident);
+            let codes_doc = format!(
+                "Returns the code of the `{ident}` of every element, the same for equal values, to \
+                group or filter the elements by without comparing the values themselves."
+            );
+            let dictionary_doc = format!(
+                "Returns the dictionary of the `{ident}` column, holding its distinct values and \
+                looking up their codes."
+            );
+
+            Some(quote! {
+                #[doc = #codes_doc]
+                pub fn #codes(&self) -> &[#code] {
+                    self.#column.codes()
+                }
+
+                #[doc = #dictionary_doc]
+                pub fn #dictionary(&self) -> &::ortho_vec_derive::dictionary::Dictionary<#ty, #code, #storage> {
+                    self.#column.dictionary()
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if dictionary_methods.is_empty() {
+        return quote! {};
+    }
+
+    let ortho_vec_name = &names.ortho_vec;
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (ortho_vec_impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    quote! {
+        impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause
+        {
+            #(#dictionary_methods)*
+        }
+    }
+}
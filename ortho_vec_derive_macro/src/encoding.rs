@@ -0,0 +1,142 @@
+//! The columns of the fields given `#[ortho(encoding = "...")]`, held run-length or delta encoded.
+//!
+//! Every such field gets an `RleVec` or a `DeltaVec` column of its own, which keeps what it
+//! encodes into in `Vec`s whatever the storage of the ortho-`Vec`.
+//! The views hold a shared reference to the value of the run for run-length encoded fields, and
+//! the decoded value itself for delta-encoded ones, and the ortho-`Vec` hands out the encoded
+//! column to reach an element by its index.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Lifetime, Type};
+
+use crate::{
+    attrs::{Encoding, FieldAttrs},
+    columns, combine_errors, flattened_error, stored_apart_layout_error, Column, OrthoStructInput,
+};
+
+/// Returns an error for every encoded field that is flattened, given another encoding, or in an
+/// ortho-`Vec` that can't hold an encoded column.
+pub(crate) fn check_fields(input: &OrthoStructInput) -> syn::Result<()> {
+    combine_errors(input.field_attrs.iter().flat_map(|field_attrs| {
+        let FieldAttrs {
+            encoding: Some(encoding),
+            flatten,
+            bitpacked,
+            nullable,
+            dictionary,
+            ..
+        } = field_attrs
+        else {
+            return Vec::new();
+        };
+
+        let flatten_error = flatten
+            .as_ref()
+            .map(|flatten| flattened_error(flatten, "encoded"));
+        let other_encoding = bitpacked
+            .as_ref()
+            .or(nullable.as_ref())
+            .map(|other| quote!(#other))
+            .or_else(|| dictionary.as_ref().map(|dictionary| quote!(#dictionary)));
+        let encoding_error = other_encoding.map(|other| {
+            syn::Error::new_spanned(
+                other,
+                "encoded fields are stored in an encoded column, so they can't be given another encoding",
+            )
+        });
+        let layout_error = stored_apart_layout_error("encoded fields", input, encoding);
+
+        flatten_error
+            .into_iter()
+            .chain(encoding_error)
+            .chain(layout_error)
+            .collect()
+    }))
+}
+
+/// Returns the type of an encoded column of elements of type `ty`.
+pub(crate) fn vec_ty_ts(encoding: &Encoding, ty: &Type) -> TokenStream {
+    match encoding {
+        Encoding::RunLength(_) => quote!(::ortho_vec_derive::rle::RleVec<#ty>),
+        Encoding::Delta(_) => quote!(::ortho_vec_derive::delta::DeltaVec<#ty>),
+    }
+}
+
+/// Returns the type of the iterator over an encoded column of elements of type `ty`, which the
+/// mutable views are taken from too.
+pub(crate) fn iter_ty_ts(encoding: &Encoding, ty: &Type, lifetime: &Lifetime) -> TokenStream {
+    match encoding {
+        Encoding::RunLength(_) => quote!(::ortho_vec_derive::rle::Iter<#lifetime, #ty>),
+        Encoding::Delta(_) => quote!(::ortho_vec_derive::delta::Iter<#lifetime, #ty>),
+    }
+}
+
+/// Returns the type of the field of both views for an encoded field of type `ty`.
+pub(crate) fn view_field_ty_ts(encoding: &Encoding, ty: &Type, lifetime: &Lifetime) -> TokenStream {
+    match encoding {
+        Encoding::RunLength(_) => quote!(&#lifetime #ty),
+        Encoding::Delta(_) => quote!(#ty),
+    }
+}
+
+/// Returns the methods handing out the column of each encoded field, which the ortho-`Vec` has
+/// when it has any.
+pub(crate) fn build_ortho_vec_encoding_impl(input: &OrthoStructInput) -> TokenStream {
+    let OrthoStructInput {
+        names,
+        where_clause,
+        ..
+    } = input;
+
+    let encoding_methods = columns(input)
+        .into_iter()
+        .filter_map(|column| {
+            let Column::Encoded {
+                ident,
+                field,
+                encoding,
+                ..
+            } = &column
+            else {
+                return None;
+            };
+
+            let vec_ty = vec_ty_ts(encoding, &field.ty);
+            let encoded = format_ident!("{}_encoded", ident);
+            let encoded_doc = match encoding {
+                Encoding::RunLength(_) => format!(
+                    "Returns the run-length encoded `{ident}` column, to reach an element by its \
+                    index or go over the runs."
+                ),
+                Encoding::Delta(_) => format!(
+                    "Returns the delta-encoded `{ident}` column, to reach an element by its index, \
+                    decoded from the checkpoint before it."
+                ),
+            };
+
+            Some(quote! {
+                #[doc = #encoded_doc]
+                pub fn #encoded(&self) -> &#vec_ty {
+                    &self.#column
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if encoding_methods.is_empty() {
+        return quote! {};
+    }
+
+    let ortho_vec_name = &names.ortho_vec;
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (ortho_vec_impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    quote! {
+        impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause
+        {
+            #(#encoding_methods)*
+        }
+    }
+}
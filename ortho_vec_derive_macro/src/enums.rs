@@ -0,0 +1,920 @@
+//! Deriving `OrthoVec` on enums.
+//!
+//! Every variant with fields gets a struct holding these fields, which goes through the same code
+//! generation as any other struct.
+//! The ortho-`Vec` of the enum holds a tag column, saying which variant each element is, and the
+//! ortho-`Vec` of every variant's struct.
+//! The elements of each variant are kept in the same order they have in the tag column, so
+//! iterating only needs to walk the tags and take the next element of the matching variant.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, DataEnum, DataStruct, Generics, Ident, Lifetime, Member, Visibility, WhereClause,
+};
+
+use crate::{
+    add_lifetime_to_generics, allocator_generics,
+    attrs::{check_variant_attrs, ContainerAttrs, FieldAttrs},
+    build_ortho_vec_items, build_ortho_vec_trait_impl, check_allocator_name, check_generics,
+    columns_layout, combine_errors, doc_attrs_ts, field_member,
+    generics::used_generics,
+    groups::field_groups,
+    storage_column_ts, storage_column_ty_ts, struct_body_ts, OrthoNames, OrthoStructInput,
+};
+
+/// A variant of the enum, along with the struct generated for its fields.
+struct VariantInfo {
+    ident: Ident,
+    /// The doc comments of the variant, repeated on the variants of the views and the tag.
+    docs: TokenStream,
+    /// `None` for variants without fields, these only live in the tag column.
+    variant_struct: Option<VariantStruct>,
+}
+
+struct VariantStruct {
+    /// The column in the enum's ortho-`Vec` holding the ortho-`Vec` of the variant's struct.
+    column: Ident,
+    input: OrthoStructInput,
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut snake_case = String::with_capacity(name.len());
+
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i != 0 {
+                snake_case.push('_');
+            }
+            snake_case.extend(c.to_lowercase());
+        } else {
+            snake_case.push(c);
+        }
+    }
+
+    snake_case
+}
+
+/// Returns an error for every variant whose column is named the same as the column of an earlier
+/// variant, like `HttpError` and `Http_error`.
+fn check_variant_columns(variants: &[VariantInfo]) -> syn::Result<()> {
+    let with_columns: Vec<(&Ident, &Ident)> = variants
+        .iter()
+        .filter_map(|variant| {
+            let variant_struct = variant.variant_struct.as_ref()?;
+            Some((&variant.ident, &variant_struct.column))
+        })
+        .collect();
+
+    combine_errors(with_columns.iter().enumerate().filter_map(|(i, (ident, column))| {
+        let (earlier, _) = with_columns[..i].iter().find(|(_, other)| other == column)?;
+        Some(syn::Error::new_spanned(
+            ident,
+            format!("the fields of this variant would be stored in `{column}`, like the fields of `{earlier}`, rename one of the variants"),
+        ))
+    }))
+}
+
+fn build_variant_info(
+    name: &Ident,
+    variant: &syn::Variant,
+    generics: &Generics,
+    where_clause: &Option<WhereClause>,
+    vis: &Visibility,
+    container_attrs: &ContainerAttrs,
+) -> syn::Result<VariantInfo> {
+    check_variant_attrs(&variant.attrs)?;
+
+    let docs = doc_attrs_ts(&variant.attrs);
+
+    if variant.fields.is_empty() {
+        return Ok(VariantInfo {
+            ident: variant.ident.clone(),
+            docs,
+            variant_struct: None,
+        });
+    }
+
+    // The fields of an enum are as visible as the enum itself, their ortho attributes are kept for
+    // the code generation of the variant's struct
+    let mut fields = variant.fields.clone();
+    fields.iter_mut().for_each(|field| {
+        field
+            .attrs
+            .retain(|attr| attr.path().is_ident("doc") || attr.path().is_ident("ortho"));
+        field.vis = parse_quote!(pub);
+    });
+
+    let (generics, where_clause) = used_generics(generics, where_clause, &fields);
+    let variant_struct_name = format_ident!("{}{}", name, variant.ident);
+    let data_struct = DataStruct {
+        struct_token: syn::token::Struct::default(),
+        fields,
+        semi_token: None,
+    };
+    let field_attrs = FieldAttrs::from_fields(&data_struct.fields)?;
+
+    Ok(VariantInfo {
+        ident: variant.ident.clone(),
+        docs,
+        variant_struct: Some(VariantStruct {
+            column: format_ident!("{}_variant", to_snake_case(&variant.ident.to_string())),
+            input: OrthoStructInput {
+                names: OrthoNames::new(&variant_struct_name, &ContainerAttrs::default()),
+                groups: field_groups(
+                    &variant_struct_name,
+                    &data_struct,
+                    &field_attrs,
+                    &generics,
+                    &where_clause,
+                )?,
+                // The items of the enum hold the items of the variant's struct, which have to
+                // derive the same traits and are stored the same way
+                forwarded_attrs: container_attrs.forwarded.clone(),
+                storage: container_attrs.columns_storage(),
+                layout: columns_layout(container_attrs, &data_struct),
+                allocator: container_attrs.allocator.clone(),
+                align: container_attrs.align.clone(),
+                chunk: container_attrs.chunk.clone(),
+                name: variant_struct_name,
+                data_struct,
+                field_attrs,
+                generics,
+                where_clause,
+                vis: vis.clone(),
+            },
+        }),
+    })
+}
+
+/// Returns the members of the fields of a variant's struct and the bindings used for them when
+/// moving a value between the enum and the struct.
+fn members_and_bindings(data_struct: &DataStruct) -> (Vec<Member>, Vec<Ident>) {
+    data_struct
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let binding = field.ident.as_ref().map_or_else(
+                || format_ident!("field_{}", index),
+                |field_ident| format_ident!("field_{}", field_ident),
+            );
+            (field_member(index, field), binding)
+        })
+        .unzip()
+}
+
+/// Returns [`proc_macro2::TokenStream`] (not [`proc_macro::TokenStream`]).
+///
+/// `transform_variant_fn` gets the variant and the tag of the variant.
+fn transform_variants_into_ts(
+    variants: &[VariantInfo],
+    tag_name: &Ident,
+    transform_variant_fn: &dyn Fn(&VariantInfo, TokenStream) -> TokenStream,
+) -> TokenStream {
+    let variants_ts_iter = variants.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        transform_variant_fn(variant, quote!(#tag_name::#variant_ident))
+    });
+
+    quote! {
+        #(#variants_ts_iter)*
+    }
+}
+
+// The items share most of their pieces, so they are all built in one place
+#[allow(clippy::too_many_lines)]
+pub(crate) fn build_ortho_vec_enum_items(
+    name: &Ident,
+    data_enum: &DataEnum,
+    generics: &Generics,
+    where_clause: &Option<WhereClause>,
+    vis: &Visibility,
+    container_attrs: &ContainerAttrs,
+) -> syn::Result<TokenStream> {
+    let ortho_lifetime = Lifetime::new("'ortho", proc_macro2::Span::call_site());
+
+    check_generics(generics, &ortho_lifetime)?;
+    check_allocator_name(generics, container_attrs.allocator.as_ref())?;
+
+    let variants: Vec<VariantInfo> = data_enum
+        .variants
+        .iter()
+        .map(|variant| {
+            build_variant_info(name, variant, generics, where_clause, vis, container_attrs)
+        })
+        .collect::<syn::Result<_>>()?;
+
+    check_variant_columns(&variants)?;
+
+    if variants
+        .iter()
+        .all(|variant| variant.variant_struct.is_none())
+    {
+        return Err(syn::Error::new_spanned(
+            name,
+            "OrthoVec needs at least one enum variant with fields, a `Vec` of a fieldless enum is already a single column",
+        ));
+    }
+
+    let names = OrthoNames::new(name, container_attrs);
+    let OrthoNames {
+        ortho_vec: ortho_vec_name,
+        view: ortho_enum_name,
+        view_mut: ortho_enum_mut_name,
+        iter: ortho_vec_iter_name,
+        iter_mut: ortho_vec_iter_mut_name,
+        into_iter: ortho_vec_into_iter_name,
+    } = &names;
+    let tag_name = container_attrs
+        .tag
+        .clone()
+        .unwrap_or_else(|| format_ident!("OrthoTag{}", name));
+
+    let allocator = container_attrs.allocator.as_ref();
+    let storage = container_attrs.columns_storage();
+    let tags_ty = storage_column_ty_ts(storage.as_ref(), allocator, &quote!(#tag_name));
+    let tags_ops = storage_column_ts(storage.as_ref(), allocator, &quote!(#tag_name));
+
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let ortho_generics = add_lifetime_to_generics(generics, &ortho_lifetime);
+    let (ortho_impl_generics, ortho_ty_generics, _) = ortho_generics.split_for_impl();
+    // The ortho-`Vec` and the iterator moving out of it take the allocator, if there is one
+    let ortho_vec_generics = allocator_generics(generics, allocator);
+    let (ortho_vec_impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+    let ortho_vec_ortho_generics = add_lifetime_to_generics(&ortho_vec_generics, &ortho_lifetime);
+    let (ortho_vec_ortho_impl_generics, _, _) = ortho_vec_ortho_generics.split_for_impl();
+
+    // Every variant's struct, with all of the items generated for it
+    let variant_structs_ts = variants
+        .iter()
+        .filter_map(|variant| variant.variant_struct.as_ref())
+        .map(|variant_struct| {
+            let OrthoStructInput {
+                name: variant_struct_name,
+                data_struct,
+                generics: variant_generics,
+                where_clause: variant_where_clause,
+                ..
+            } = &variant_struct.input;
+
+            // The ortho attributes are only read by the derive, the struct doesn't derive it
+            let fields_decl_ts = data_struct.fields.iter().map(|field| {
+                let mut field = field.clone();
+                field.attrs.retain(|attr| attr.path().is_ident("doc"));
+                quote!(#field,)
+            });
+            let fields_decl_ts = quote!(#(#fields_decl_ts)*);
+            let struct_body = struct_body_ts(data_struct, variant_where_clause, &fields_decl_ts);
+            let items_ts = build_ortho_vec_items(&variant_struct.input)?;
+            let doc = format!("The fields of a [`{name}`] that is a `{variant_struct_name}`.");
+
+            Ok(quote! {
+                #[doc = #doc]
+                #vis struct #variant_struct_name #variant_generics
+                #struct_body
+
+                #items_ts
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    // The types related to each variant's struct, `lifetime` is added to the generics of the
+    // views and the iterators, and the allocator to those of the ortho-`Vec` and the iterator
+    // moving out of it
+    let variant_type = |variant_struct: &VariantStruct, type_name: fn(&OrthoNames) -> &Ident| {
+        let type_name = type_name(&variant_struct.input.names);
+        let ortho_vec_generics = variant_struct.input.ortho_vec_generics();
+        let (_, type_generics, _) = ortho_vec_generics.split_for_impl();
+        quote!(#type_name #type_generics)
+    };
+    let variant_ortho_type = |variant_struct: &VariantStruct,
+                              type_name: fn(&OrthoNames) -> &Ident| {
+        let type_name = type_name(&variant_struct.input.names);
+        let ortho_generics =
+            add_lifetime_to_generics(&variant_struct.input.generics, &ortho_lifetime);
+        let (_, type_generics, _) = ortho_generics.split_for_impl();
+        quote!(#type_name #type_generics)
+    };
+
+    let tag_variants = variants.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        let variant_docs = &variant.docs;
+        quote!(#variant_docs #variant_ident)
+    });
+    let tag_doc = format!("The variant of each element of [`{ortho_vec_name}`].");
+
+    let tag_decl = quote! {
+        #[doc = #tag_doc]
+        #[derive(::core::clone::Clone, ::core::marker::Copy, ::core::fmt::Debug, ::core::cmp::PartialEq, ::core::cmp::Eq, ::core::hash::Hash)]
+        #vis enum #tag_name {
+            #(#tag_variants,)*
+        }
+    };
+
+    let variant_columns_decl = transform_variants_into_ts(&variants, &tag_name, &|variant, _| {
+        variant.variant_struct.as_ref().map_or_else(
+            || quote!(),
+            |variant_struct| {
+                let column = &variant_struct.column;
+                let variant_ortho_vec = variant_type(variant_struct, |names| &names.ortho_vec);
+                quote!(#column: #variant_ortho_vec,)
+            },
+        )
+    });
+
+    let ortho_vec_doc = format!(
+        "An orthogonal `Vec` of [`{name}`], holding the variant of each element and an orthogonal `Vec` for each variant."
+    );
+
+    let ortho_vec_attrs = container_attrs.forwarded.ortho_vec_ts();
+    let ortho_vec_struct_decl = quote! {
+        #[doc = #ortho_vec_doc]
+        #ortho_vec_attrs
+        #vis struct #ortho_vec_name #ortho_vec_generics
+        #where_clause
+        {
+            tags: #tags_ty,
+            #variant_columns_decl
+        }
+    };
+
+    // With an allocator every column is constructed in a clone of it
+    let alloc_arg = allocator.map(|_| quote!(::core::clone::Clone::clone(&alloc)));
+    let variant_columns_new = transform_variants_into_ts(&variants, &tag_name, &|variant, _| {
+        variant.variant_struct.as_ref().map_or_else(
+            || quote!(),
+            |variant_struct| {
+                let column = &variant_struct.column;
+                let variant_ortho_vec = variant_type(variant_struct, |names| &names.ortho_vec);
+                if allocator.is_some() {
+                    quote!(#column: <#variant_ortho_vec>::new_in(#alloc_arg),)
+                } else {
+                    quote!(#column: <#variant_ortho_vec>::new(),)
+                }
+            },
+        )
+    });
+
+    let variant_columns_call_method = |method_name: &str| {
+        let method_name = Ident::new(method_name, proc_macro2::Span::call_site());
+        transform_variants_into_ts(&variants, &tag_name, &|variant, _| {
+            variant.variant_struct.as_ref().map_or_else(
+                || quote!(),
+                |variant_struct| {
+                    let column = &variant_struct.column;
+                    quote!(self.#column.#method_name();)
+                },
+            )
+        })
+    };
+    let variant_columns_clear = variant_columns_call_method("clear");
+    let variant_columns_shrink_to_fit = variant_columns_call_method("shrink_to_fit");
+    let variant_columns_reverse = variant_columns_call_method("reverse");
+
+    // Moves the value of a variant into its column using `store_value_ts`, and its tag into the
+    // tag column using `store_tag_ts`
+    let store_value_arms =
+        |store_value_ts: &dyn Fn(&Ident, &TokenStream, &TokenStream) -> TokenStream,
+         store_tag_ts: &dyn Fn(&TokenStream) -> TokenStream| {
+            transform_variants_into_ts(&variants, &tag_name, &|variant, tag| {
+                let variant_ident = &variant.ident;
+                let store_tag = store_tag_ts(&tag);
+
+                variant.variant_struct.as_ref().map_or_else(
+                    || quote!(#name::#variant_ident { .. } => #store_tag,),
+                    |variant_struct| {
+                        let variant_struct_name = &variant_struct.input.name;
+                        let (members, bindings) =
+                            members_and_bindings(&variant_struct.input.data_struct);
+                        let store_value = store_value_ts(
+                            &variant_struct.column,
+                            &quote!(#variant_struct_name { #(#members: #bindings),* }),
+                            &tag,
+                        );
+
+                        quote! {
+                            #name::#variant_ident { #(#members: #bindings),* } => {
+                                #store_value
+                                #store_tag;
+                            }
+                        }
+                    },
+                )
+            })
+        };
+
+    let push_value_arms = store_value_arms(
+        &|column, value, _| quote!(self.#column.push(#value);),
+        &|tag| quote!(#tags_ops::push(&mut self.tags, #tag)),
+    );
+
+    let insert_value_arms = store_value_arms(
+        &|column, value, tag| {
+            quote! {
+                let row = self.tags[..index].iter().filter(|t| **t == #tag).count();
+                self.#column.insert(row, #value);
+            }
+        },
+        &|tag| quote!(#tags_ops::insert(&mut self.tags, index, #tag)),
+    );
+
+    // Takes the value of a variant out of its column using `method_call_ts`
+    let take_value_from_column = |method_call_ts: TokenStream| {
+        transform_variants_into_ts(&variants, &tag_name, &|variant, tag| {
+            let variant_ident = &variant.ident;
+
+            variant.variant_struct.as_ref().map_or_else(
+                || quote!(#tag => #name::#variant_ident {},),
+                |variant_struct| {
+                    let variant_struct_name = &variant_struct.input.name;
+                    let column = &variant_struct.column;
+                    let (members, bindings) = members_and_bindings(&variant_struct.input.data_struct);
+
+                    quote! {
+                        #tag => {
+                            let #variant_struct_name { #(#members: #bindings),* } = self.#column.#method_call_ts;
+                            #name::#variant_ident { #(#members: #bindings),* }
+                        }
+                    }
+                },
+            )
+        })
+    };
+
+    // The tags in a storage and the values of each variant are collected from `Vec`s all at once,
+    // as pushing into a column that can't grow in place copies all of it, while the tags in an
+    // allocator grow in place and are pushed into as the elements are
+    let from_vec_body = if allocator.is_some() {
+        quote! {
+            let mut v = Self::with_capacity(value.len());
+
+            for p in value {
+                v.push(p);
+            }
+
+            v
+        }
+    } else {
+        let collect_value_arms = store_value_arms(
+            &|column, value, _| quote!(#column.push(#value);),
+            &|tag| quote!(tags.push(#tag)),
+        );
+        let variant_columns_elements =
+            transform_variants_into_ts(&variants, &tag_name, &|variant, _| {
+                variant.variant_struct.as_ref().map_or_else(
+                    || quote!(),
+                    |variant_struct| {
+                        let column = &variant_struct.column;
+                        quote!(let mut #column = ::ortho_vec_derive::alloc::vec::Vec::new();)
+                    },
+                )
+            });
+        let variant_columns_collect =
+            transform_variants_into_ts(&variants, &tag_name, &|variant, _| {
+                variant.variant_struct.as_ref().map_or_else(
+                    || quote!(),
+                    |variant_struct| {
+                        let column = &variant_struct.column;
+                        quote!(#column: ::core::convert::From::from(#column),)
+                    },
+                )
+            });
+
+        quote! {
+            let mut tags = ::ortho_vec_derive::alloc::vec::Vec::with_capacity(value.len());
+            #variant_columns_elements
+
+            for value in value {
+                match value {
+                    #collect_value_arms
+                }
+            }
+
+            #ortho_vec_name {
+                tags: #tags_ops::from_elements(tags),
+                #variant_columns_collect
+            }
+        }
+    };
+
+    let pop_value_arms = take_value_from_column(quote!(pop()?));
+    let remove_value_arms = take_value_from_column(quote!(remove(row)));
+    let into_iter_value_arms = take_value_from_column(quote!(next()?));
+
+    let count_rows_before_index = quote!(self.tags[..index].iter().filter(|t| **t == tag).count());
+
+    let variant_accessors = transform_variants_into_ts(&variants, &tag_name, &|variant, _| {
+        variant.variant_struct.as_ref().map_or_else(
+            || quote!(),
+            |variant_struct| {
+                let column = &variant_struct.column;
+                let column_iter_mut = format_ident!("{}_iter_mut", column);
+                let variant_ortho_vec = variant_type(variant_struct, |names| &names.ortho_vec);
+                let variant_iter_mut = variant_ortho_type(variant_struct, |names| &names.iter_mut);
+
+                quote! {
+                    /// The elements of this variant, in the order they have in the whole ortho-`Vec`
+                    pub fn #column(&self) -> &#variant_ortho_vec {
+                        &self.#column
+                    }
+
+                    /// Iterates mutably over the elements of this variant only
+                    pub fn #column_iter_mut<#ortho_lifetime>(&#ortho_lifetime mut self) -> #variant_iter_mut {
+                        self.#column.iter_mut()
+                    }
+                }
+            },
+        )
+    });
+
+    let constructors = if let Some(allocator) = allocator {
+        quote! {
+            impl #impl_generics #ortho_vec_name #ty_generics
+            #where_clause
+            {
+                /// Constructs a new, empty orthogonal `Vec` in the global allocator.
+                pub fn new() -> Self {
+                    Self::new_in(::ortho_vec_derive::allocator_api2::alloc::Global)
+                }
+
+                /// Constructs a new, empty orthogonal `Vec` with room for at least `capacity`
+                /// tags, in the global allocator.
+                pub fn with_capacity(capacity: usize) -> Self {
+                    Self::with_capacity_in(capacity, ::ortho_vec_derive::allocator_api2::alloc::Global)
+                }
+            }
+
+            impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+            #where_clause
+            {
+                /// Constructs a new, empty orthogonal `Vec` in `alloc`.
+                pub fn new_in(alloc: #allocator) -> Self
+                where
+                    #allocator: ::core::clone::Clone,
+                {
+                    #ortho_vec_name {
+                        tags: #tags_ops::new_in(#alloc_arg),
+                        #variant_columns_new
+                    }
+                }
+
+                /// Constructs a new, empty orthogonal `Vec` with room for at least `capacity`
+                /// tags, in `alloc`.
+                pub fn with_capacity_in(capacity: usize, alloc: #allocator) -> Self
+                where
+                    #allocator: ::core::clone::Clone,
+                {
+                    #ortho_vec_name {
+                        tags: #tags_ops::with_capacity_in(capacity, #alloc_arg),
+                        #variant_columns_new
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics #ortho_vec_name #ty_generics
+            #where_clause
+            {
+                /// Constructs a new, empty orthogonal `Vec`.
+                pub fn new() -> #ortho_vec_name #ty_generics {
+                    #ortho_vec_name {
+                        tags: #tags_ops::new(),
+                        #variant_columns_new
+                    }
+                }
+
+                /// Constructs a new, empty orthogonal `Vec` with room for at least `capacity` tags.
+                pub fn with_capacity(capacity: usize) -> #ortho_vec_name #ty_generics {
+                    #ortho_vec_name {
+                        tags: #tags_ops::with_capacity(capacity),
+                        #variant_columns_new
+                    }
+                }
+            }
+        }
+    };
+
+    // The `Vec` of the tags in an allocator is reversed as the slice it is
+    let tags_reverse = if allocator.is_some() {
+        quote!(<[#tag_name]>::reverse(&mut self.tags))
+    } else {
+        quote!(#tags_ops::reverse(&mut self.tags))
+    };
+
+    let ortho_vec_impl = quote! {
+        #constructors
+
+        impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause
+        {
+            /// Returns the number of elements.
+            pub fn len(&self) -> usize {
+                self.tags.len()
+            }
+
+            /// The variant of each element
+            pub fn tags(&self) -> &[#tag_name] {
+                &self.tags
+            }
+
+            #variant_accessors
+
+            /// Appends an element to the back.
+            pub fn push(&mut self, value: #name #ty_generics) {
+                match value {
+                    #push_value_arms
+                }
+            }
+
+            /// Removes the last element and returns it, or `None` if there are no elements.
+            pub fn pop(&mut self) -> ::core::option::Option<#name #ty_generics> {
+                ::core::option::Option::Some(match #tags_ops::pop(&mut self.tags)? {
+                    #pop_value_arms
+                })
+            }
+
+            /// Removes all elements.
+            pub fn clear(&mut self) {
+                #tags_ops::clear(&mut self.tags);
+                #variant_columns_clear
+            }
+
+            /// Calls `Vec::shrink_to_fit` on every column.
+            pub fn shrink_to_fit(&mut self) {
+                #tags_ops::shrink_to_fit(&mut self.tags);
+                #variant_columns_shrink_to_fit
+            }
+
+            /// Reverses the order of the elements.
+            pub fn reverse(&mut self) {
+                // Reversing every column keeps the elements of each variant in order
+                #tags_reverse;
+                #variant_columns_reverse
+            }
+
+            /// Inserts an element at `index`, panics if `index` is greater than the length.
+            pub fn insert(&mut self, index: usize, element: #name #ty_generics) {
+                ::core::assert!(index <= self.len(), "insertion index (is {index}) should be <= len (is {})", self.len());
+
+                match element {
+                    #insert_value_arms
+                }
+            }
+
+            /// Same as `Vec::remove`, removes the element at `index` and returns it.
+            pub fn remove(&mut self, index: usize) -> #name #ty_generics {
+                let tag = #tags_ops::remove(&mut self.tags, index);
+                let row = #count_rows_before_index;
+
+                match tag {
+                    #remove_value_arms
+                }
+            }
+
+            /// Same as `Vec::swap_remove`, removes the element at `index` and returns it.
+            pub fn swap_remove(&mut self, index: usize) -> #name #ty_generics {
+                // The last element may be of a different variant, so move it with a pop and an insert
+                let value = self.remove(index);
+                if index < self.len() {
+                    if let ::core::option::Option::Some(last) = self.pop() {
+                        self.insert(index, last);
+                    }
+                }
+
+                value
+            }
+        }
+
+        ::ortho_vec_derive::__with_alloc! {
+            impl #impl_generics ::core::convert::From<::ortho_vec_derive::alloc::vec::Vec<#name #ty_generics>> for #ortho_vec_name #ty_generics
+            #where_clause
+            {
+                fn from(value: ::ortho_vec_derive::alloc::vec::Vec<#name #ty_generics>) -> Self {
+                    #from_vec_body
+                }
+            }
+        }
+    };
+
+    let ortho_enum_variants = |view_name: fn(&OrthoNames) -> &Ident| {
+        transform_variants_into_ts(&variants, &tag_name, &|variant, _| {
+            let variant_ident = &variant.ident;
+            let variant_docs = &variant.docs;
+            variant.variant_struct.as_ref().map_or_else(
+                || quote!(#variant_docs #variant_ident,),
+                |variant_struct| {
+                    let variant_view = variant_ortho_type(variant_struct, view_name);
+                    quote!(#variant_docs #variant_ident(#variant_view),)
+                },
+            )
+        })
+    };
+    let ortho_enum_variants_decl = ortho_enum_variants(|names| &names.view);
+    let ortho_enum_mut_variants_decl = ortho_enum_variants(|names| &names.view_mut);
+
+    let ortho_enum_doc = format!("A view of a [`{name}`] stored in an orthogonal `Vec`.");
+    let ortho_enum_mut_doc =
+        format!("A mutable view of a [`{name}`] stored in an orthogonal `Vec`.");
+
+    // Like the views of structs, not every variant has to be matched on
+    let view_attrs = container_attrs.forwarded.view_ts();
+    let view_mut_attrs = container_attrs.forwarded.view_mut_ts();
+    let ortho_enums_decl = quote! {
+        #[doc = #ortho_enum_doc]
+        #[allow(dead_code)]
+        #view_attrs
+        #vis enum #ortho_enum_name #ortho_generics
+        #where_clause
+        {
+            #ortho_enum_variants_decl
+        }
+
+        #[doc = #ortho_enum_mut_doc]
+        #[allow(dead_code)]
+        #view_mut_attrs
+        #vis enum #ortho_enum_mut_name #ortho_generics
+        #where_clause
+        {
+            #ortho_enum_mut_variants_decl
+        }
+    };
+
+    // The iterators hold an iterator over the tags and an iterator for each variant
+    let iter_columns_decl = |iter_name: fn(&OrthoNames) -> &Ident, with_lifetime: bool| {
+        transform_variants_into_ts(&variants, &tag_name, &|variant, _| {
+            variant.variant_struct.as_ref().map_or_else(
+                || quote!(),
+                |variant_struct| {
+                    let column = &variant_struct.column;
+                    let variant_iter = if with_lifetime {
+                        variant_ortho_type(variant_struct, iter_name)
+                    } else {
+                        variant_type(variant_struct, iter_name)
+                    };
+                    quote!(#column: #variant_iter,)
+                },
+            )
+        })
+    };
+    let iter_columns_init = |method_name: &str| {
+        let method_name = Ident::new(method_name, proc_macro2::Span::call_site());
+        transform_variants_into_ts(&variants, &tag_name, &|variant, _| {
+            variant.variant_struct.as_ref().map_or_else(
+                || quote!(),
+                |variant_struct| {
+                    let column = &variant_struct.column;
+                    quote!(#column: self.#column.#method_name(),)
+                },
+            )
+        })
+    };
+    let iter_next_arms = |ortho_enum_name: &Ident| {
+        transform_variants_into_ts(&variants, &tag_name, &|variant, tag| {
+            let variant_ident = &variant.ident;
+            variant.variant_struct.as_ref().map_or_else(
+                || quote!(#tag => #ortho_enum_name::#variant_ident,),
+                |variant_struct| {
+                    let column = &variant_struct.column;
+                    quote!(#tag => #ortho_enum_name::#variant_ident(self.#column.next()?),)
+                },
+            )
+        })
+    };
+
+    let iter_columns_decl_ts = iter_columns_decl(|names| &names.iter, true);
+    let iter_columns_init_ts = iter_columns_init("iter");
+    let iter_next_arms_ts = iter_next_arms(ortho_enum_name);
+
+    let iter_mut_columns_decl_ts = iter_columns_decl(|names| &names.iter_mut, true);
+    let iter_mut_columns_init_ts = iter_columns_init("iter_mut");
+    let iter_mut_next_arms_ts = iter_next_arms(ortho_enum_mut_name);
+
+    let into_iter_columns_decl_ts = iter_columns_decl(|names| &names.into_iter, false);
+    let into_iter_columns_init_ts = iter_columns_init("into_iter");
+
+    let ortho_vec_iter_doc =
+        format!("An iterator over views of the elements of [`{ortho_vec_name}`].");
+    let ortho_vec_iter_mut_doc =
+        format!("An iterator over mutable views of the elements of [`{ortho_vec_name}`].");
+    let ortho_vec_into_iter_doc =
+        format!("An iterator moving the elements out of [`{ortho_vec_name}`].");
+
+    let ortho_vec_iters = quote! {
+        #[doc = #ortho_vec_iter_doc]
+        #vis struct #ortho_vec_iter_name #ortho_generics
+        #where_clause
+        {
+            tags: ::core::slice::Iter<#ortho_lifetime, #tag_name>,
+            #iter_columns_decl_ts
+        }
+
+        impl #ortho_impl_generics ::core::iter::Iterator for #ortho_vec_iter_name #ortho_ty_generics
+        #where_clause
+        {
+            type Item = #ortho_enum_name #ortho_ty_generics;
+
+            #[inline]
+            fn next(&mut self) -> ::core::option::Option<Self::Item> {
+                ::core::option::Option::Some(match *self.tags.next()? {
+                    #iter_next_arms_ts
+                })
+            }
+        }
+
+        #[doc = #ortho_vec_iter_mut_doc]
+        #vis struct #ortho_vec_iter_mut_name #ortho_generics
+        #where_clause
+        {
+            tags: ::core::slice::Iter<#ortho_lifetime, #tag_name>,
+            #iter_mut_columns_decl_ts
+        }
+
+        impl #ortho_impl_generics ::core::iter::Iterator for #ortho_vec_iter_mut_name #ortho_ty_generics
+        #where_clause
+        {
+            type Item = #ortho_enum_mut_name #ortho_ty_generics;
+
+            #[inline]
+            fn next(&mut self) -> ::core::option::Option<Self::Item> {
+                ::core::option::Option::Some(match *self.tags.next()? {
+                    #iter_mut_next_arms_ts
+                })
+            }
+        }
+
+        impl #ortho_vec_ortho_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause
+        {
+            /// Returns an iterator over views of the elements.
+            pub fn iter(&#ortho_lifetime self) -> #ortho_vec_iter_name #ortho_ty_generics {
+                #ortho_vec_iter_name {
+                    tags: self.tags.iter(),
+                    #iter_columns_init_ts
+                }
+            }
+
+            /// Returns an iterator over mutable views of the elements.
+            pub fn iter_mut(&#ortho_lifetime mut self) -> #ortho_vec_iter_mut_name #ortho_ty_generics {
+                #ortho_vec_iter_mut_name {
+                    tags: self.tags.iter(),
+                    #iter_mut_columns_init_ts
+                }
+            }
+        }
+
+        #[doc = #ortho_vec_into_iter_doc]
+        #vis struct #ortho_vec_into_iter_name #ortho_vec_generics
+        #where_clause
+        {
+            tags: <#tags_ty as ::core::iter::IntoIterator>::IntoIter,
+            #into_iter_columns_decl_ts
+        }
+
+        impl #ortho_vec_impl_generics ::core::iter::Iterator for #ortho_vec_into_iter_name #ortho_vec_ty_generics
+        #where_clause
+        {
+            type Item = #name #ty_generics;
+
+            #[inline]
+            fn next(&mut self) -> ::core::option::Option<Self::Item> {
+                ::core::option::Option::Some(match self.tags.next()? {
+                    #into_iter_value_arms
+                })
+            }
+        }
+
+        impl #ortho_vec_impl_generics ::core::iter::IntoIterator for #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause
+        {
+            type Item = #name #ty_generics;
+            type IntoIter = #ortho_vec_into_iter_name #ortho_vec_ty_generics;
+
+            fn into_iter(self) -> #ortho_vec_into_iter_name #ortho_vec_ty_generics {
+                #ortho_vec_into_iter_name {
+                    tags: ::core::iter::IntoIterator::into_iter(self.tags),
+                    #into_iter_columns_init_ts
+                }
+            }
+        }
+    };
+
+    let ortho_vec_trait_impl =
+        build_ortho_vec_trait_impl(name, generics, where_clause, &names, &ortho_lifetime);
+
+    let variant_structs_ts = quote!(#(#variant_structs_ts)*);
+
+    Ok(quote! {
+        #variant_structs_ts
+
+        #tag_decl
+        #ortho_vec_struct_decl
+        #ortho_vec_impl
+
+        #ortho_enums_decl
+        #ortho_vec_iters
+
+        #ortho_vec_trait_impl
+    })
+}
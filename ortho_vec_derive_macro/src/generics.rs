@@ -0,0 +1,173 @@
+//! Helpers for the generics of the generated items.
+
+use std::collections::HashSet;
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{
+    punctuated::Punctuated, GenericArgument, GenericParam, Generics, PathArguments, TypeParamBound,
+    WhereClause, WherePredicate,
+};
+
+/// Collects every identifier in `ts`, lifetimes are collected without their `'`.
+fn collect_idents(ts: TokenStream, idents: &mut HashSet<String>) {
+    for tt in ts {
+        match tt {
+            TokenTree::Group(group) => collect_idents(group.stream(), idents),
+            TokenTree::Ident(ident) => {
+                idents.insert(ident.to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn generic_param_name(param: &GenericParam) -> String {
+    match param {
+        GenericParam::Type(tp) => tp.ident.to_string(),
+        GenericParam::Lifetime(lp) => lp.lifetime.ident.to_string(),
+        GenericParam::Const(cp) => cp.ident.to_string(),
+    }
+}
+
+/// Collects every identifier in the types bound to associated types in `bounds`, such as `U` in
+/// `Iterator<Item = U>`.
+fn collect_assoc_type_idents<'a>(
+    bounds: impl IntoIterator<Item = &'a TypeParamBound>,
+    idents: &mut HashSet<String>,
+) {
+    for bound in bounds {
+        let TypeParamBound::Trait(trait_bound) = bound else {
+            continue;
+        };
+
+        for segment in &trait_bound.path.segments {
+            let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+                continue;
+            };
+
+            for argument in &arguments.args {
+                if let GenericArgument::AssocType(assoc_type) = argument {
+                    collect_idents(assoc_type.ty.to_token_stream(), idents);
+                }
+            }
+        }
+    }
+}
+
+/// Returns whether `tokens` name none of `params`.
+fn names_none_of(tokens: &impl ToTokens, params: &HashSet<String>) -> bool {
+    let mut idents = HashSet::new();
+    collect_idents(tokens.to_token_stream(), &mut idents);
+
+    idents.is_disjoint(params)
+}
+
+/// Keeps only the bounds that name none of the `removed` params.
+fn retain_bounds<T: Clone + ToTokens, P: Default>(
+    bounds: &mut Punctuated<T, P>,
+    removed: &HashSet<String>,
+) {
+    *bounds = bounds
+        .iter()
+        .filter(|bound| names_none_of(*bound, removed))
+        .cloned()
+        .collect();
+}
+
+/// Keeps only the generic params (and bounds) that `fields` need, for structs holding only some
+/// of the fields, which must use all of their generic params.
+///
+/// Params bound to the associated types of a kept param, like `U` with `T: Iterator<Item = U>`,
+/// are kept along with the bound, which a field such as `T::Item` needs. Any other bound naming a
+/// dropped param, like `T: From<U>`, is dropped with it: keeping the param would leave it unused,
+/// and the fields don't name it, so they can't need the bound.
+pub(crate) fn used_generics<'a>(
+    generics: &Generics,
+    where_clause: &Option<WhereClause>,
+    fields: impl IntoIterator<Item = &'a syn::Field>,
+) -> (Generics, Option<WhereClause>) {
+    let mut used = HashSet::new();
+    for field in fields {
+        collect_idents(field.ty.to_token_stream(), &mut used);
+    }
+
+    // Params may be used only through the associated types of the bounds of other used params
+    loop {
+        let used_count = used.len();
+        let mut constrained = HashSet::new();
+
+        for param in &generics.params {
+            if let GenericParam::Type(type_param) = param {
+                if used.contains(&type_param.ident.to_string()) {
+                    collect_assoc_type_idents(&type_param.bounds, &mut constrained);
+                }
+            }
+        }
+        for predicate in where_clause
+            .iter()
+            .flat_map(|where_clause| &where_clause.predicates)
+        {
+            if let WherePredicate::Type(predicate) = predicate {
+                if !names_none_of(&predicate.bounded_ty, &used) {
+                    collect_assoc_type_idents(&predicate.bounds, &mut constrained);
+                }
+            }
+        }
+        used.extend(constrained);
+
+        if used.len() == used_count {
+            break;
+        }
+    }
+
+    let (kept, removed): (Vec<_>, Vec<_>) = generics
+        .params
+        .iter()
+        .cloned()
+        .partition(|param| used.contains(&generic_param_name(param)));
+    let removed: HashSet<String> = removed.iter().map(generic_param_name).collect();
+
+    let mut used_generics = generics.clone();
+    used_generics.params = kept
+        .into_iter()
+        .map(|mut param| {
+            match &mut param {
+                GenericParam::Type(type_param) => retain_bounds(&mut type_param.bounds, &removed),
+                GenericParam::Lifetime(lifetime_param) => {
+                    retain_bounds(&mut lifetime_param.bounds, &removed);
+                }
+                GenericParam::Const(_) => {}
+            }
+            param
+        })
+        .collect();
+
+    let used_where_clause = where_clause.as_ref().map(|where_clause| {
+        let mut used_where_clause = where_clause.clone();
+        used_where_clause.predicates = where_clause
+            .predicates
+            .iter()
+            .cloned()
+            .filter_map(|mut predicate| {
+                let kept = match &mut predicate {
+                    WherePredicate::Type(predicate) => {
+                        retain_bounds(&mut predicate.bounds, &removed);
+                        names_none_of(&predicate.bounded_ty, &removed)
+                            && !predicate.bounds.is_empty()
+                    }
+                    WherePredicate::Lifetime(predicate) => {
+                        retain_bounds(&mut predicate.bounds, &removed);
+                        names_none_of(&predicate.lifetime, &removed) && !predicate.bounds.is_empty()
+                    }
+                    _ => names_none_of(&predicate, &removed),
+                };
+
+                kept.then_some(predicate)
+            })
+            .collect();
+        used_where_clause
+    });
+
+    (used_generics, used_where_clause)
+}
@@ -0,0 +1,207 @@
+//! Storing groups of fields together, in a single column.
+//!
+//! Every group gets a struct holding its fields, and the ortho-`Vec` holds a single column of
+//! these structs for the whole group, instead of a column for each of the fields.
+//! The views still point at each field on its own, so they look the same either way.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DataStruct, Generics, Ident, Member, WhereClause};
+
+use crate::{
+    attrs::{FieldAttrs, ForwardedAttrs},
+    combine_errors, field_column_ident, field_member,
+    generics::used_generics,
+    marker_field_value_ts, RESERVED_FIELD_NAMES,
+};
+
+/// Fields stored together in a single column.
+pub(crate) struct FieldGroup {
+    /// The column holding the group, named after the group.
+    pub(crate) column: Ident,
+    /// The struct holding the fields of the group.
+    pub(crate) name: Ident,
+    pub(crate) generics: Generics,
+    pub(crate) where_clause: Option<WhereClause>,
+    /// The indices of the group's fields in the struct.
+    pub(crate) indices: Vec<usize>,
+    /// The members of the group's fields on the struct.
+    pub(crate) members: Vec<Member>,
+    /// The fields of the group's struct, named like the columns they would have had otherwise.
+    pub(crate) fields: Vec<syn::Field>,
+}
+
+impl FieldGroup {
+    /// Returns the type of the elements in the group's column.
+    pub(crate) fn ty_ts(&self) -> TokenStream {
+        let name = &self.name;
+        let (_, ty_generics, _) = self.generics.split_for_impl();
+
+        quote!(#name #ty_generics)
+    }
+
+    /// Returns the declaration of the group's struct, which derives what the ortho-`Vec` does.
+    pub(crate) fn decl_ts(&self, forwarded_attrs: &ForwardedAttrs) -> TokenStream {
+        let FieldGroup {
+            name,
+            generics,
+            where_clause,
+            fields,
+            ..
+        } = self;
+        let column_attrs = forwarded_attrs.column_ts();
+
+        // The fields are moved in and out of the group as a whole, and reached one by one through
+        // the views, so not all of them have to be read
+        quote! {
+            #[allow(dead_code)]
+            #column_attrs
+            struct #name #generics
+            #where_clause
+            {
+                #(#fields,)*
+            }
+        }
+    }
+}
+
+fn to_upper_camel_case(name: &str) -> String {
+    name.split('_')
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .into_iter()
+                .flat_map(char::to_uppercase)
+                .chain(chars)
+        })
+        .collect()
+}
+
+/// Returns an error if the field is stored in a column of its own kind, which can't hold the
+/// other fields of a group.
+fn stored_apart_error(field_attrs: &FieldAttrs) -> Option<syn::Error> {
+    let FieldAttrs {
+        flatten,
+        bitpacked,
+        nullable,
+        dictionary,
+        encoding,
+        column,
+        ..
+    } = field_attrs;
+
+    let (tokens, message) = if let Some(flatten) = flatten {
+        (
+            quote!(#flatten),
+            "flattened fields are stored in the columns of their own type, so they can't be in a group",
+        )
+    } else if let Some(bitpacked) = bitpacked {
+        (
+            quote!(#bitpacked),
+            "bit-packed fields are stored in a column of bits of their own, so they can't be in a group",
+        )
+    } else if let Some(nullable) = nullable {
+        (
+            quote!(#nullable),
+            "nullable fields are stored in a validity bitmap and a column of their own, so they can't be in a group",
+        )
+    } else if let Some(dictionary) = dictionary {
+        (
+            quote!(#dictionary),
+            "dictionary-encoded fields are stored in a column of codes of their own, so they can't be in a group",
+        )
+    } else if let Some(encoding) = encoding {
+        (
+            quote!(#encoding),
+            "encoded fields are stored in an encoded column of their own, so they can't be in a group",
+        )
+    } else if let Some(column) = column {
+        (
+            quote!(#column),
+            "fields given a `column` are stored in that column of their own, so they can't be in a group",
+        )
+    } else {
+        return None;
+    };
+
+    Some(syn::Error::new_spanned(tokens, message))
+}
+
+/// Returns the groups given with `#[ortho(group = "...")]` on the fields of `data_struct`, in the
+/// order they first appear in.
+///
+/// `field_attrs` are the options given on each of the fields.
+pub(crate) fn field_groups(
+    name: &Ident,
+    data_struct: &DataStruct,
+    field_attrs: &[FieldAttrs],
+    generics: &Generics,
+    where_clause: &Option<WhereClause>,
+) -> syn::Result<Vec<FieldGroup>> {
+    let mut groups: Vec<FieldGroup> = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, (field, field_attrs)) in data_struct.fields.iter().zip(field_attrs).enumerate() {
+        let Some(group) = field_attrs.group.clone() else {
+            continue;
+        };
+
+        if let Some(err) = stored_apart_error(field_attrs) {
+            errors.push(err);
+            continue;
+        }
+
+        if marker_field_value_ts(field).is_some() {
+            errors.push(syn::Error::new_spanned(
+                &field.ty,
+                "marker fields have no column, so they can't be in a group",
+            ));
+            continue;
+        }
+
+        let mut group_field = field.clone();
+        group_field.ident = Some(field_column_ident(index, field));
+        group_field.attrs.clear();
+
+        if let Some(existing_group) = groups.iter_mut().find(|g| g.column == group) {
+            existing_group.indices.push(index);
+            existing_group.members.push(field_member(index, field));
+            existing_group.fields.push(group_field);
+        } else {
+            let column_clashes = data_struct
+                .fields
+                .iter()
+                .enumerate()
+                .any(|(index, field)| field_column_ident(index, field) == group)
+                || RESERVED_FIELD_NAMES.contains(&group.to_string().as_str());
+            if column_clashes {
+                errors.push(syn::Error::new_spanned(
+                    &group,
+                    format!(
+                        "the group `{group}` would have the same name as another column, rename it"
+                    ),
+                ));
+                continue;
+            }
+
+            groups.push(FieldGroup {
+                name: format_ident!("{}{}Group", name, to_upper_camel_case(&group.to_string())),
+                column: group,
+                generics: Generics::default(),
+                where_clause: None,
+                indices: vec![index],
+                members: vec![field_member(index, field)],
+                fields: vec![group_field],
+            });
+        }
+    }
+
+    combine_errors(errors)?;
+
+    for group in &mut groups {
+        (group.generics, group.where_clause) = used_generics(generics, where_clause, &group.fields);
+    }
+
+    Ok(groups)
+}
@@ -1,469 +1,1628 @@
 extern crate proc_macro;
 
+mod attrs;
+mod bits;
+mod blocks;
+mod custom;
+mod dictionary;
+mod encoding;
+mod enums;
+mod generics;
+mod groups;
+mod nullable;
+mod single;
+
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote, ToTokens};
 use syn::{
-    self, DeriveInput, Ident, __private::Span, parse_macro_input, punctuated::Punctuated,
-    token::Plus, DataStruct, Fields::Named, Generics, Lifetime, LifetimeParam, TypeParamBound,
-    WhereClause,
+    self, __private::Span, parse_macro_input, parse_quote, Attribute, DataStruct, DeriveInput,
+    Fields, GenericParam, Generics, Ident, Index, Lifetime, LifetimeParam, LitInt, Member, Type,
+    TypeParamBound, VisRestricted, Visibility, WhereClause,
 };
 
+use attrs::{ColumnsLayout, ContainerAttrs, Encoding, FieldAttrs, ForwardedAttrs};
+use groups::{field_groups, FieldGroup};
+
+/// The struct the items are generated for.
+struct OrthoStructInput {
+    name: Ident,
+    data_struct: DataStruct,
+    /// The options given on each of the fields, parsed once, in the order of the fields.
+    field_attrs: Vec<FieldAttrs>,
+    generics: Generics,
+    where_clause: Option<WhereClause>,
+    /// The visibility of all of the generated types.
+    vis: Visibility,
+    names: OrthoNames,
+    /// The groups of fields stored together in a single column.
+    groups: Vec<FieldGroup>,
+    forwarded_attrs: ForwardedAttrs,
+    /// The `Storage` of the columns, which are `Vec`s if it isn't given.
+    storage: Option<Type>,
+    /// Whether the columns are kept apart, all together in a single allocation or in blocks.
+    layout: ColumnsLayout,
+    /// The type param of the allocator of the columns, which are in the global allocator if it
+    /// isn't given.
+    allocator: Option<Ident>,
+    /// The alignment of the columns in bytes, which only have the alignment of their elements if
+    /// it isn't given.
+    align: Option<LitInt>,
+    /// The number of elements in every block of the columns, when they're laid out in blocks.
+    chunk: Option<LitInt>,
+}
+
+impl OrthoStructInput {
+    /// Returns the generics of the ortho-`Vec` and of the iterator moving out of it, which take
+    /// the allocator as their last type param if there is one, the global allocator by default.
+    fn ortho_vec_generics(&self) -> Generics {
+        allocator_generics(&self.generics, self.allocator.as_ref())
+    }
+
+    /// Returns an iterator over the fields, with the options given on each of them.
+    fn fields(&self) -> impl Iterator<Item = (&syn::Field, &FieldAttrs)> {
+        self.data_struct.fields.iter().zip(&self.field_attrs)
+    }
+
+    /// Returns the group the field at `index` is in, if it's in one.
+    fn field_group(&self, index: usize) -> Option<&FieldGroup> {
+        self.groups
+            .iter()
+            .find(|group| group.indices.contains(&index))
+    }
+}
+
+/// The names of the generated items, which are named after the struct unless given with
+/// `#[ortho(...)]`.
+struct OrthoNames {
+    ortho_vec: Ident,
+    view: Ident,
+    view_mut: Ident,
+    iter: Ident,
+    iter_mut: Ident,
+    into_iter: Ident,
+}
+
+impl OrthoNames {
+    fn new(name: &Ident, container_attrs: &ContainerAttrs) -> Self {
+        let name_or_default = |option: &Option<Ident>, prefix: &str| {
+            option
+                .clone()
+                .unwrap_or_else(|| format_ident!("{}{}", prefix, name))
+        };
+
+        Self {
+            ortho_vec: name_or_default(&container_attrs.name, "OrthoVec"),
+            view: name_or_default(&container_attrs.view, "Ortho"),
+            view_mut: name_or_default(&container_attrs.view_mut, "OrthoMut"),
+            iter: name_or_default(&container_attrs.iter, "OrthoVecIter"),
+            iter_mut: name_or_default(&container_attrs.iter_mut, "OrthoVecIterMut"),
+            into_iter: name_or_default(&container_attrs.into_iter, "OrthoVecIntoIter"),
+        }
+    }
+}
+
+/// Returns `generics` with `allocator` added as their last type param, if it's given.
+fn allocator_generics(generics: &Generics, allocator: Option<&Ident>) -> Generics {
+    let mut allocator_generics = generics.clone();
+
+    if let Some(allocator) = allocator {
+        allocator_generics.params.push(parse_quote! {
+            #allocator: ::ortho_vec_derive::allocator_api2::alloc::Allocator
+                = ::ortho_vec_derive::allocator_api2::alloc::Global
+        });
+    }
+
+    allocator_generics
+}
+
+/// Names of the fields the generated items keep next to the columns.
+const RESERVED_FIELD_NAMES: [&str; 5] = ["__len", "__marker", "__index", "__columns", "__blocks"];
+
+/// Returns all of `errors` as a single error, if there are any.
+fn combine_errors(errors: impl IntoIterator<Item = syn::Error>) -> syn::Result<()> {
+    errors
+        .into_iter()
+        .reduce(|mut combined, error| {
+            combined.combine(error);
+            combined
+        })
+        .map_or(Ok(()), Err)
+}
+
+/// Returns an error for a lifetime param clashing with the one the views and iterators add.
+fn check_generics(generics: &Generics, ortho_lifetime: &Lifetime) -> syn::Result<()> {
+    combine_errors(
+        generics
+            .lifetimes()
+            .filter(|lp| lp.lifetime.ident == ortho_lifetime.ident)
+            .map(|lp| {
+                syn::Error::new_spanned(
+                    &lp.lifetime,
+                    format!("the lifetime `{ortho_lifetime}` is used by the views and iterators OrthoVec generates, rename it"),
+                )
+            }),
+    )
+}
+
+/// Returns an error for an allocator named the same as a generic param of the struct (or enum).
+fn check_allocator_name(generics: &Generics, allocator: Option<&Ident>) -> syn::Result<()> {
+    let Some(allocator) = allocator else {
+        return Ok(());
+    };
+
+    let clashes = generics.params.iter().any(|param| match param {
+        GenericParam::Type(tp) => tp.ident == *allocator,
+        GenericParam::Const(cp) => cp.ident == *allocator,
+        GenericParam::Lifetime(_) => false,
+    });
+
+    if clashes {
+        Err(syn::Error::new_spanned(
+            allocator,
+            format!("the generic param `{allocator}` is already declared, name the allocator differently"),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns an error for every flattened field of a struct with an allocator.
+fn check_allocator_fields(input: &OrthoStructInput) -> syn::Result<()> {
+    if input.allocator.is_none() {
+        return Ok(());
+    }
+
+    combine_errors(input.field_attrs.iter().filter_map(|field_attrs| {
+        let flatten = field_attrs.flatten.as_ref()?;
+
+        Some(syn::Error::new_spanned(
+            flatten,
+            "flattened fields are stored in the columns of their own type, which are in the global allocator, so they can't be given an `allocator`",
+        ))
+    }))
+}
+
+/// Returns an error for a field stored in a column of its own kind, pointing at `tokens`, if the
+/// ortho-`Vec` can't hold such a column: one with a single allocation, with blocks or with an
+/// allocator.
+///
+/// `kind` names the fields in the message, like `"bit-packed fields"`.
+fn stored_apart_layout_error(
+    kind: &str,
+    input: &OrthoStructInput,
+    tokens: impl ToTokens,
+) -> Option<syn::Error> {
+    let message = match input.layout {
+        ColumnsLayout::Columns if input.allocator.is_some() => format!(
+            "{kind} are stored in a column of their own, which doesn't take the allocator, so they can't be given an `allocator`"
+        ),
+        ColumnsLayout::Columns => return None,
+        ColumnsLayout::Single => format!(
+            "{kind} are stored in a column of their own, so they can't be in a single allocation"
+        ),
+        ColumnsLayout::Blocks => {
+            format!("{kind} are stored in a column of their own, so they can't be in blocks")
+        }
+    };
+
+    Some(syn::Error::new_spanned(tokens, message))
+}
+
+/// Returns the error for a flattened field that is also `what` it can't be, like `"bit-packed"`.
+fn flattened_error(flatten: &syn::Path, what: &str) -> syn::Error {
+    syn::Error::new_spanned(
+        flatten,
+        format!(
+            "flattened fields are stored in the columns of their own type, so they can't be {what}"
+        ),
+    )
+}
+
+/// Returns an error for every field clashing with the fields the generated items keep, and for
+/// every flattened marker field.
+fn check_fields(input: &OrthoStructInput) -> syn::Result<()> {
+    let reserved_name_errors = input
+        .data_struct
+        .fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref())
+        .filter(|ident| RESERVED_FIELD_NAMES.contains(&ident.to_string().as_str()))
+        .map(|ident| {
+            syn::Error::new_spanned(
+                ident,
+                format!(
+                    "the field name `{ident}` is used by the items OrthoVec generates, rename it"
+                ),
+            )
+        });
+    let flattened_marker_errors = input
+        .fields()
+        .filter(|(field, field_attrs)| {
+            field_attrs.flatten.is_some() && marker_field_value_ts(field).is_some()
+        })
+        .map(|(field, _)| {
+            syn::Error::new_spanned(
+                &field.ty,
+                "marker fields have no column, so they can't be flattened",
+            )
+        });
+
+    combine_errors(reserved_name_errors.chain(flattened_marker_errors))
+}
+
+/// Returns the [`Member`] used to access a field, its name for named fields and its index for
+/// tuple fields.
+fn field_member(index: usize, field: &syn::Field) -> Member {
+    field.ident.as_ref().map_or_else(
+        || Member::Unnamed(Index::from(index)),
+        |ident| Member::Named(ident.clone()),
+    )
+}
+
+/// Returns the [`Ident`] of the column holding a field, tuple fields are named by their index
+/// (`_0`, `_1`, ...).
+fn field_column_ident(index: usize, field: &syn::Field) -> Ident {
+    field
+        .ident
+        .clone()
+        .unwrap_or_else(|| format_ident!("_{}", index))
+}
+
 /// Returns [`proc_macro2::TokenStream`] (not [`proc_macro::TokenStream`]).
-fn transform_named_fields_into_ts(
-    data_struct: &DataStruct,
-    transform_named_field_fn: &dyn Fn(&syn::Field) -> proc_macro2::TokenStream,
+///
+/// `transform_field_fn` gets the member used to access the field on the struct, the ident of
+/// the column holding it, the field itself and the options given on it.
+fn transform_fields_into_ts(
+    input: &OrthoStructInput,
+    transform_field_fn: &dyn Fn(
+        &Member,
+        &Ident,
+        &syn::Field,
+        &FieldAttrs,
+    ) -> proc_macro2::TokenStream,
 ) -> proc_macro2::TokenStream {
-    match data_struct.fields {
-        Named(ref fields) => {
-            // Create iterator over named fields, holding generated props token streams.
-            let props_ts_iter = fields
-                .named
-                .iter()
-                .map(|named_field| transform_named_field_fn(named_field));
-
-            // Unwrap iterator into a [proc_macro2::TokenStream].
-            quote! {
-              #(#props_ts_iter)*
+    // Create iterator over the fields, holding generated props token streams.
+    let props_ts_iter = input
+        .fields()
+        .enumerate()
+        .map(|(index, (field, field_attrs))| {
+            transform_field_fn(
+                &field_member(index, field),
+                &field_column_ident(index, field),
+                field,
+                field_attrs,
+            )
+        });
+
+    // Unwrap iterator into a [proc_macro2::TokenStream].
+    quote! {
+      #(#props_ts_iter)*
+    }
+}
+
+/// Returns the value of a marker field, which is zero-sized and can be recreated from nothing.
+///
+/// Marker fields (`PhantomData` and `()`) get no column, their value is recreated whenever it is
+/// needed instead.
+/// A derive macro only sees the tokens of the type, so `PhantomData` is only recognized written
+/// as `PhantomData` or as `core::marker::PhantomData` or `std::marker::PhantomData`; behind an
+/// alias or a renamed import it gets a column like any other field.
+fn marker_field_value_ts(field: &syn::Field) -> Option<proc_macro2::TokenStream> {
+    match &field.ty {
+        Type::Tuple(tuple) if tuple.elems.is_empty() => Some(quote!(())),
+        Type::Path(type_path) if type_path.qself.is_none() && is_phantom_data(&type_path.path) => {
+            Some(quote!(::core::marker::PhantomData))
+        }
+        _ => None,
+    }
+}
+
+/// Returns whether `path` names `PhantomData`, bare or by its path in `core` or `std`.
+fn is_phantom_data(path: &syn::Path) -> bool {
+    let idents = path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>();
+    let prefix_has_arguments = path
+        .segments
+        .iter()
+        .rev()
+        .skip(1)
+        .any(|segment| !segment.arguments.is_none());
+
+    !prefix_has_arguments
+        && match idents.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+            ["PhantomData"] => path.leading_colon.is_none(),
+            ["core" | "std", "marker", "PhantomData"] => true,
+            _ => false,
+        }
+}
+
+/// Returns the ortho-`Vec` of `ty`, as an `OrthoVec` naming the items generated for it.
+fn flattened_ortho_vec_ts(ty: &Type) -> proc_macro2::TokenStream {
+    quote!(<::ortho_vec_derive::Soa<#ty> as ::ortho_vec_derive::OrthoVec>)
+}
+
+/// Returns the type of a column holding elements of type `ty`, backed by `storage` if it's given,
+/// or in `allocator` if that's given.
+fn storage_column_ty_ts(
+    storage: Option<&Type>,
+    allocator: Option<&Ident>,
+    ty: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if let Some(storage) = storage {
+        quote!(<#storage as ::ortho_vec_derive::storage::Storage>::Column<#ty>)
+    } else if let Some(allocator) = allocator {
+        quote!(::ortho_vec_derive::allocator_api2::vec::Vec<#ty, #allocator>)
+    } else {
+        quote!(::ortho_vec_derive::alloc::vec::Vec<#ty>)
+    }
+}
+
+/// Returns the `Column` implementation of a column holding elements of type `ty`, through which
+/// the column is changed, as not every storage has the methods of `Vec` itself.
+///
+/// Columns in an allocator can't be constructed without it, so they aren't `Column`s, and are
+/// changed through the methods of their `Vec` instead, which has all of them but `reverse`.
+fn storage_column_ts(
+    storage: Option<&Type>,
+    allocator: Option<&Ident>,
+    ty: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let column_ty = storage_column_ty_ts(storage, allocator, ty);
+
+    if allocator.is_some() {
+        quote!(<#column_ty>)
+    } else {
+        quote!(<#column_ty as ::ortho_vec_derive::storage::Column<#ty>>)
+    }
+}
+
+/// A column of the ortho-`Vec`, holding a single field or a group of fields.
+///
+/// Turns into the ident of the column when quoted.
+enum Column<'a> {
+    Field {
+        /// The member used to access the field on the struct.
+        member: Member,
+        ident: Ident,
+        field: &'a syn::Field,
+    },
+    /// A field stored in the ortho-`Vec` of its own type.
+    Flattened {
+        member: Member,
+        ident: Ident,
+        field: &'a syn::Field,
+    },
+    /// A `bool` field stored in a column of bits.
+    Bits {
+        member: Member,
+        ident: Ident,
+        field: &'a syn::Field,
+    },
+    /// An `Option` field stored in a validity bitmap and a column of the present values.
+    Nullable {
+        member: Member,
+        ident: Ident,
+        field: &'a syn::Field,
+        /// The type of the values, `T` of the `Option<T>`.
+        value_ty: &'a Type,
+    },
+    /// A field stored in a column of codes and a dictionary of the distinct values.
+    Dictionary {
+        member: Member,
+        ident: Ident,
+        field: &'a syn::Field,
+        /// The type of the codes.
+        code: Ident,
+    },
+    /// A field stored run-length or delta encoded.
+    Encoded {
+        member: Member,
+        ident: Ident,
+        field: &'a syn::Field,
+        encoding: Encoding,
+    },
+    /// A field stored in a `ColumnStorage` of the user's.
+    Custom {
+        member: Member,
+        ident: Ident,
+        field: &'a syn::Field,
+        /// The type of the column.
+        column_ty: Type,
+    },
+    Group(&'a FieldGroup),
+}
+
+impl<'a> Column<'a> {
+    /// Returns the column of a field that isn't in a group, stored as `field_attrs` ask.
+    fn new(index: usize, field: &'a syn::Field, field_attrs: &FieldAttrs) -> Self {
+        let member = field_member(index, field);
+        let ident = field_column_ident(index, field);
+
+        if field_attrs.flatten.is_some() {
+            Column::Flattened {
+                member,
+                ident,
+                field,
+            }
+        } else if field_attrs.bitpacked.is_some() {
+            Column::Bits {
+                member,
+                ident,
+                field,
+            }
+        } else if let Some(value_ty) = field_attrs
+            .nullable
+            .as_ref()
+            .and_then(|_| nullable::option_value_ty(&field.ty))
+        {
+            Column::Nullable {
+                member,
+                ident,
+                field,
+                value_ty,
+            }
+        } else if let Some(code) = &field_attrs.dictionary {
+            Column::Dictionary {
+                member,
+                ident,
+                field,
+                code: code.clone(),
+            }
+        } else if let Some(encoding) = &field_attrs.encoding {
+            Column::Encoded {
+                member,
+                ident,
+                field,
+                encoding: encoding.clone(),
+            }
+        } else if let Some(column_ty) = &field_attrs.column {
+            Column::Custom {
+                member,
+                ident,
+                field,
+                column_ty: column_ty.clone(),
+            }
+        } else {
+            Column::Field {
+                member,
+                ident,
+                field,
             }
         }
-        _ => quote! {},
     }
 }
 
+impl Column<'_> {
+    fn ident(&self) -> &Ident {
+        match self {
+            Column::Field { ident, .. }
+            | Column::Flattened { ident, .. }
+            | Column::Bits { ident, .. }
+            | Column::Nullable { ident, .. }
+            | Column::Dictionary { ident, .. }
+            | Column::Encoded { ident, .. }
+            | Column::Custom { ident, .. } => ident,
+            Column::Group(group) => &group.column,
+        }
+    }
+
+    const fn is_flattened(&self) -> bool {
+        matches!(self, Column::Flattened { .. })
+    }
+
+    /// Returns whether the views of the column's elements are taken from an iterator of its own,
+    /// rather than from a slice.
+    const fn has_iterator(&self) -> bool {
+        matches!(
+            self,
+            Column::Flattened { .. }
+                | Column::Bits { .. }
+                | Column::Nullable { .. }
+                | Column::Dictionary { .. }
+                | Column::Encoded { .. }
+                | Column::Custom { .. }
+        )
+    }
+
+    /// Returns whether the mutable views borrow the column's elements mutably, the elements of
+    /// dictionary-encoded and encoded columns are shared or decoded, so they can't be changed in
+    /// place.
+    const fn is_mutable(&self) -> bool {
+        !matches!(self, Column::Dictionary { .. } | Column::Encoded { .. })
+    }
+
+    /// Returns the type of the iterator the column is iterated over with, borrowed mutably if
+    /// `mutable`, or `None` if it's iterated over as a slice.
+    fn iter_ty_ts(&self, lifetime: &Lifetime, mutable: bool) -> Option<proc_macro2::TokenStream> {
+        match self {
+            Column::Flattened { field, .. } => {
+                let ortho_vec = flattened_ortho_vec_ts(&field.ty);
+                Some(if mutable {
+                    quote!(#ortho_vec::IterMut<#lifetime>)
+                } else {
+                    quote!(#ortho_vec::Iter<#lifetime>)
+                })
+            }
+            Column::Bits { .. } if mutable => {
+                Some(quote!(::ortho_vec_derive::bits::IterMut<#lifetime>))
+            }
+            Column::Bits { .. } => Some(quote!(::ortho_vec_derive::bits::Iter<#lifetime>)),
+            Column::Nullable { value_ty, .. } if mutable => Some(quote!(
+                ::ortho_vec_derive::nullable::IterMut<#lifetime, #value_ty>
+            )),
+            Column::Nullable { value_ty, .. } => Some(quote!(
+                ::ortho_vec_derive::nullable::Iter<#lifetime, #value_ty>
+            )),
+            // The values are shared, so they're only ever borrowed immutably
+            Column::Dictionary { field, code, .. } => {
+                let ty = &field.ty;
+                Some(quote!(::ortho_vec_derive::dictionary::Iter<#lifetime, #ty, #code>))
+            }
+            Column::Encoded {
+                field, encoding, ..
+            } => Some(encoding::iter_ty_ts(encoding, &field.ty, lifetime)),
+            Column::Custom {
+                field, column_ty, ..
+            } => Some(custom::iter_ty_ts(column_ty, &field.ty, lifetime, mutable)),
+            Column::Field { .. } | Column::Group(_) => None,
+        }
+    }
+
+    /// Returns the type of the elements of the column.
+    fn ty_ts(&self) -> proc_macro2::TokenStream {
+        match self {
+            Column::Field { field, .. }
+            | Column::Flattened { field, .. }
+            | Column::Bits { field, .. }
+            | Column::Nullable { field, .. }
+            | Column::Dictionary { field, .. }
+            | Column::Encoded { field, .. }
+            | Column::Custom { field, .. } => field.ty.to_token_stream(),
+            Column::Group(group) => group.ty_ts(),
+        }
+    }
+
+    /// Returns the type of the column itself.
+    fn vec_ty_ts(&self, input: &OrthoStructInput) -> proc_macro2::TokenStream {
+        match self {
+            Column::Flattened { field, .. } => {
+                let ty = &field.ty;
+                quote!(::ortho_vec_derive::Soa<#ty>)
+            }
+            Column::Bits { .. } => bits::vec_ty_ts(input),
+            Column::Nullable { value_ty, .. } => nullable::vec_ty_ts(input, value_ty),
+            Column::Dictionary { field, code, .. } => dictionary::vec_ty_ts(input, &field.ty, code),
+            Column::Encoded {
+                field, encoding, ..
+            } => encoding::vec_ty_ts(encoding, &field.ty),
+            Column::Custom { column_ty, .. } => quote!(#column_ty),
+            Column::Field { .. } | Column::Group(_) => storage_column_ty_ts(
+                input.storage.as_ref(),
+                input.allocator.as_ref(),
+                &self.ty_ts(),
+            ),
+        }
+    }
+
+    /// Returns the trait implementation the column is changed through, flattened columns are
+    /// changed as the `OrthoVec` they are, columns given by the user as the `ColumnStorage` they
+    /// are, and columns of bits, nullable, dictionary-encoded and encoded columns through the
+    /// methods of their own type.
+    fn ops_ts(&self, input: &OrthoStructInput) -> proc_macro2::TokenStream {
+        match self {
+            Column::Flattened { field, .. } => flattened_ortho_vec_ts(&field.ty),
+            Column::Custom {
+                field, column_ty, ..
+            } => custom::ops_ts(column_ty, &field.ty),
+            Column::Bits { .. }
+            | Column::Nullable { .. }
+            | Column::Dictionary { .. }
+            | Column::Encoded { .. } => {
+                let vec_ty = self.vec_ty_ts(input);
+                quote!(<#vec_ty>)
+            }
+            Column::Field { .. } | Column::Group(_) => storage_column_ts(
+                input.storage.as_ref(),
+                input.allocator.as_ref(),
+                &self.ty_ts(),
+            ),
+        }
+    }
+
+    /// Returns the element of the column holding the fields of `value`, a value of the struct.
+    fn element_ts(&self, value: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        match self {
+            Column::Field { member, .. }
+            | Column::Flattened { member, .. }
+            | Column::Bits { member, .. }
+            | Column::Nullable { member, .. }
+            | Column::Dictionary { member, .. }
+            | Column::Encoded { member, .. }
+            | Column::Custom { member, .. } => quote!(#value.#member),
+            Column::Group(group) => {
+                let group_name = &group.name;
+                let group_members = group.fields.iter().map(|field| &field.ident);
+                let members = &group.members;
+
+                quote!(#group_name { #(#group_members: #value.#members,)* })
+            }
+        }
+    }
+
+    /// Returns the iterator over the column of `self`, borrowed mutably if `mutable`, for columns
+    /// that have an iterator of their own.
+    fn iter_call_ts(&self, input: &OrthoStructInput, mutable: bool) -> proc_macro2::TokenStream {
+        let column = self;
+
+        match self {
+            Column::Custom { .. } => {
+                let column_ops = self.ops_ts(input);
+                if mutable {
+                    quote!(#column_ops::iter_mut(&mut self.#column))
+                } else {
+                    quote!(#column_ops::iter(&self.#column))
+                }
+            }
+            _ if mutable => quote!(self.#column.iter_mut()),
+            _ => quote!(self.#column.iter()),
+        }
+    }
+}
+
+impl ToTokens for Column<'_> {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        self.ident().to_tokens(tokens);
+    }
+}
+
+/// Returns the columns in the order of the fields, the column of a group comes in place of its
+/// first field and marker fields have no column.
+fn columns(input: &OrthoStructInput) -> Vec<Column<'_>> {
+    input
+        .fields()
+        .enumerate()
+        .filter(|(_, (field, _))| marker_field_value_ts(field).is_none())
+        .filter_map(
+            |(index, (field, field_attrs))| match input.field_group(index) {
+                Some(group) if group.indices[0] == index => Some(Column::Group(group)),
+                Some(_) => None,
+                None => Some(Column::new(index, field, field_attrs)),
+            },
+        )
+        .collect()
+}
+
 /// Returns [`proc_macro2::TokenStream`] (not [`proc_macro::TokenStream`]).
-fn take_first_named_field_ts(data_struct: &DataStruct) -> proc_macro2::TokenStream {
-    match data_struct.fields {
-        Named(ref fields) => {
-            // Take first prop ident
-            let first_prop_ident = fields
-                .named
-                .first()
-                .expect("Struct should have at least one field")
-                .ident
-                .as_ref()
-                .expect("First field should have an indentifier");
-
-            // convert first ident into a [proc_macro2::TokenStream].
+///
+/// Goes over the columns in the order of [`columns`].
+fn transform_columns_into_ts(
+    input: &OrthoStructInput,
+    transform_column_fn: &dyn Fn(&Column) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let columns_ts_iter = columns(input)
+        .into_iter()
+        .map(|column| transform_column_fn(&column));
+
+    quote! {
+        #(#columns_ts_iter)*
+    }
+}
+
+/// Returns the binding holding the value of a group's column while its fields are taken out.
+fn group_binding_ident(group: &FieldGroup) -> Ident {
+    format_ident!("__group_{}", group.column)
+}
+
+/// Returns [`proc_macro2::TokenStream`]s (not [`proc_macro::TokenStream`]).
+///
+/// Builds the struct literal members of all fields, using `column_value_fn` for the values taken
+/// out of the columns.
+/// The values of the groups' columns are bound by the first returned token stream, which has to
+/// come before the struct literal, and their fields are taken out of them with
+/// `group_field_prefix` (nothing to move them, `&` or `&mut` to borrow them).
+fn fields_values_ts(
+    input: &OrthoStructInput,
+    column_value_fn: &dyn Fn(&Column) -> proc_macro2::TokenStream,
+    group_field_prefix: &proc_macro2::TokenStream,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let bind_groups_ts_iter = input.groups.iter().map(|group| {
+        let group_binding = group_binding_ident(group);
+        let group_value = column_value_fn(&Column::Group(group));
+
+        quote! {
+            let #group_binding = #group_value;
+        }
+    });
+
+    let members_ts_iter = input
+        .fields()
+        .enumerate()
+        .map(|(index, (field, field_attrs))| {
+            let member = field_member(index, field);
+
+            let value = if let Some(marker_value) = marker_field_value_ts(field) {
+                marker_value
+            } else if let Some(group) = input.field_group(index) {
+                let group_binding = group_binding_ident(group);
+                let column = field_column_ident(index, field);
+                quote!(#group_field_prefix #group_binding.#column)
+            } else {
+                column_value_fn(&Column::new(index, field, field_attrs))
+            };
+
             quote! {
-              #first_prop_ident
+                #member: #value,
             }
-        }
-        _ => quote! {},
+        });
+
+    (
+        quote! {
+            #(#bind_groups_ts_iter)*
+        },
+        quote! {
+            #(#members_ts_iter)*
+        },
+    )
+}
+
+fn has_columns(data_struct: &DataStruct) -> bool {
+    data_struct
+        .fields
+        .iter()
+        .any(|field| marker_field_value_ts(field).is_none())
+}
+
+/// Returns how the columns of `data_struct` are laid out, structs without any column have nothing
+/// to allocate, so they always keep the default layout.
+fn columns_layout(container_attrs: &ContainerAttrs, data_struct: &DataStruct) -> ColumnsLayout {
+    if has_columns(data_struct) {
+        container_attrs.columns_layout()
+    } else {
+        ColumnsLayout::Columns
     }
 }
 
-fn remove_trait_bounds_from_generics(generics: &Generics) -> Generics {
-    let mut generics_no_trait_bounds = generics.clone();
+/// Returns the length of the shortest column of `ortho_vec`, which bounds the unchecked accesses
+/// to the columns.
+///
+/// The columns are private fields, but code in the module of the struct can still reach them, so
+/// the iterators can't rely on all of them having the length of the ortho-`Vec`.
+fn min_columns_len_ts(
+    input: &OrthoStructInput,
+    ortho_vec: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    // A single allocation keeps one length for all of its columns
+    if input.layout == ColumnsLayout::Single {
+        return quote!(#ortho_vec.__columns.len());
+    }
 
-    generics_no_trait_bounds.params.iter_mut().for_each(|p| {
-        if let syn::GenericParam::Type(tp) = p {
-            let mut new_bounds = Punctuated::<TypeParamBound, Plus>::new();
-            tp.bounds
-                .iter()
-                .filter(|bound| !matches!(bound, TypeParamBound::Trait(_)))
-                .for_each(|bound| new_bounds.push(bound.clone()));
+    let columns_len = transform_columns_into_ts(input, &|column| {
+        if let Column::Custom { .. } = column {
+            let column_ops = column.ops_ts(input);
+            return quote! {
+                .min(#column_ops::len(&#ortho_vec.#column))
+            };
+        }
 
-            tp.bounds = new_bounds;
+        quote! {
+            .min(#ortho_vec.#column.len())
         }
     });
 
-    generics_no_trait_bounds
+    quote! {
+        #ortho_vec.__len #columns_len
+    }
+}
+
+/// Returns the elements of a column of `self` as a slice, borrowed mutably if `mutable`.
+fn column_slice_ts(
+    input: &OrthoStructInput,
+    column: &Column,
+    mutable: bool,
+) -> proc_macro2::TokenStream {
+    match input.layout {
+        ColumnsLayout::Columns if mutable => {
+            quote!(::core::ops::DerefMut::deref_mut(&mut self.#column))
+        }
+        ColumnsLayout::Columns => quote!(::core::ops::Deref::deref(&self.#column)),
+        ColumnsLayout::Single => single::column_slice_ts(input, column, mutable),
+        ColumnsLayout::Blocks => unreachable!("the columns in blocks aren't slices"),
+    }
 }
 
-fn add_lifetime_to_generics(generics: &Generics, lifetime: &Lifetime) -> Generics {
-    let lifetime_generic_param = syn::GenericParam::Lifetime(LifetimeParam::new(lifetime.clone()));
+/// Without any field borrowed from a column, when there are no columns or only delta-encoded ones
+/// whose views hold the decoded values, nothing in a view uses its lifetime, so views of such
+/// structs get an extra marker member after the fields of the struct.
+fn view_marker_member(input: &OrthoStructInput) -> Option<Member> {
+    let has_borrowed_fields = input.fields().any(|(field, field_attrs)| {
+        marker_field_value_ts(field).is_none()
+            && !matches!(field_attrs.encoding, Some(Encoding::Delta(_)))
+    });
 
+    if has_borrowed_fields {
+        None
+    } else if let Fields::Unnamed(fields) = &input.data_struct.fields {
+        Some(Member::Unnamed(Index::from(fields.unnamed.len())))
+    } else {
+        Some(Member::Named(format_ident!("__marker")))
+    }
+}
+
+/// Returns the declaration of the view's marker member, if it needs one.
+fn view_marker_decl_ts(
+    input: &OrthoStructInput,
+    marker_ty: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match view_marker_member(input) {
+        Some(Member::Named(marker_ident)) => quote! {
+            #marker_ident: ::core::marker::PhantomData<#marker_ty>,
+        },
+        Some(Member::Unnamed(_)) => quote! {
+            ::core::marker::PhantomData<#marker_ty>,
+        },
+        None => quote! {},
+    }
+}
+
+/// Returns the struct literal member initializing the view's marker member, if it has one.
+fn view_marker_value_ts(input: &OrthoStructInput) -> proc_macro2::TokenStream {
+    view_marker_member(input).map_or_else(
+        || quote! {},
+        |marker_member| quote!(#marker_member: ::core::marker::PhantomData,),
+    )
+}
+
+/// Wraps the declarations of a struct's fields the same way the fields of `data_struct` are
+/// wrapped, so tuple structs generate tuple structs.
+fn struct_body_ts(
+    data_struct: &DataStruct,
+    where_clause: &Option<WhereClause>,
+    fields_decl_ts: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if let Fields::Unnamed(_) = data_struct.fields {
+        quote! {
+            (#fields_decl_ts)
+            #where_clause;
+        }
+    } else {
+        quote! {
+            #where_clause
+            {
+                #fields_decl_ts
+            }
+        }
+    }
+}
+
+/// Returns the doc comments in `attrs`, so generated items mirroring the struct's items carry its
+/// docs.
+fn doc_attrs_ts(attrs: &[Attribute]) -> proc_macro2::TokenStream {
+    let doc_attrs = attrs.iter().filter(|attr| attr.path().is_ident("doc"));
+
+    quote! {
+        #(#doc_attrs)*
+    }
+}
+
+/// Adds `lifetime` to `generics`, for the views and iterators borrowing from the ortho-`Vec`.
+///
+/// All other lifetimes and type params have to outlive it, as the borrowed columns hold them.
+fn add_lifetime_to_generics(generics: &Generics, lifetime: &Lifetime) -> Generics {
     let mut generics_w_lifetime = generics.clone();
-    generics_w_lifetime.params.push(lifetime_generic_param);
+
+    generics_w_lifetime.params.iter_mut().for_each(|p| match p {
+        GenericParam::Lifetime(lp) => lp.bounds.push(lifetime.clone()),
+        GenericParam::Type(tp) => tp.bounds.push(TypeParamBound::Lifetime(lifetime.clone())),
+        GenericParam::Const(_) => {}
+    });
+
+    let lifetime_generic_param = GenericParam::Lifetime(LifetimeParam::new(lifetime.clone()));
+    generics_w_lifetime.params.insert(0, lifetime_generic_param);
 
     generics_w_lifetime
 }
 
 fn build_ortho_struct(
-    name: &Ident,
-    data_struct: &DataStruct,
-    generics: &Generics,
-    where_clause: &Option<WhereClause>,
+    input: &OrthoStructInput,
     ortho_lifetime: &Lifetime,
 ) -> (Ident, proc_macro2::TokenStream) {
-    let ortho_struct_name = Ident::new(
-        &("Ortho".to_string() + &name.to_string()),
-        Span::call_site(),
-    );
+    let OrthoStructInput {
+        name,
+        data_struct,
+        generics,
+        where_clause,
+        vis,
+        names,
+        ..
+    } = input;
 
-    let props_ts_iter = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
-        let field_ty = &named_field.ty;
+    let ortho_struct_name = &names.view;
+    let view_attrs = input.forwarded_attrs.view_ts();
 
-        quote! {
-          pub(super) #field_ident: &#ortho_lifetime #field_ty,
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let view_marker_ts = view_marker_decl_ts(input, &quote!(&#ortho_lifetime #name #ty_generics));
+
+    let props_ts_iter = transform_fields_into_ts(input, &|_, _, field, field_attrs| {
+        let field_name = field
+            .ident
+            .as_ref()
+            .map(|field_ident| quote!(#field_ident:));
+        let field_ty = &field.ty;
+        let field_vis = &field.vis;
+        let field_docs = doc_attrs_ts(&field.attrs);
+
+        // Marker fields are held by value, they have no column to point into
+        if marker_field_value_ts(field).is_some() {
+            quote! {
+              #field_docs
+              #field_vis #field_name #field_ty,
+            }
+        } else if field_attrs.flatten.is_some() {
+            let ortho_vec = flattened_ortho_vec_ts(field_ty);
+            quote! {
+              #field_docs
+              #field_vis #field_name #ortho_vec::Ref<#ortho_lifetime>,
+            }
+        } else if field_attrs.bitpacked.is_some() {
+            quote! {
+              #field_docs
+              #field_vis #field_name ::ortho_vec_derive::bits::BitRef<#ortho_lifetime>,
+            }
+        } else if let Some(value_ty) = field_attrs
+            .nullable
+            .as_ref()
+            .and_then(|_| nullable::option_value_ty(field_ty))
+        {
+            quote! {
+              #field_docs
+              #field_vis #field_name ::core::option::Option<&#ortho_lifetime #value_ty>,
+            }
+        } else if let Some(encoding) = &field_attrs.encoding {
+            let view_field_ty = encoding::view_field_ty_ts(encoding, field_ty, ortho_lifetime);
+            quote! {
+              #field_docs
+              #field_vis #field_name #view_field_ty,
+            }
+        } else if let Some(column_ty) = &field_attrs.column {
+            let view_field_ty =
+                custom::view_field_ty_ts(column_ty, field_ty, ortho_lifetime, false);
+            quote! {
+              #field_docs
+              #field_vis #field_name #view_field_ty,
+            }
+        } else {
+            quote! {
+              #field_docs
+              #field_vis #field_name &#ortho_lifetime #field_ty,
+            }
         }
     });
+    let props_ts_iter = quote! {
+        #props_ts_iter
+        #view_marker_ts
+    };
 
     let ortho_generics = add_lifetime_to_generics(generics, ortho_lifetime);
 
-    (
-        ortho_struct_name.clone(),
+    // Not every field of a view has to be read, the view is there to reach any of them
+    (ortho_struct_name.clone(), {
+        let struct_body = struct_body_ts(data_struct, where_clause, &props_ts_iter);
+
+        let doc = format!("A view of a [`{name}`] stored in an orthogonal `Vec`.");
+
         quote!(
-        pub(super) struct #ortho_struct_name #ortho_generics
-        #where_clause
-        {
-            #props_ts_iter
-        }),
-    )
+            #[doc = #doc]
+            #[allow(dead_code)]
+            #view_attrs
+            #vis struct #ortho_struct_name #ortho_generics
+            #struct_body)
+    })
 }
 
 fn build_ortho_struct_mut(
-    name: &Ident,
-    data_struct: &DataStruct,
-    generics: &Generics,
-    where_clause: &Option<WhereClause>,
+    input: &OrthoStructInput,
     ortho_lifetime: &Lifetime,
 ) -> (Ident, proc_macro2::TokenStream) {
-    let ortho_struct_mut_name = Ident::new(
-        &("OrthoMut".to_string() + &name.to_string()),
-        Span::call_site(),
-    );
-
-    let props_ts_iter = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
-        let field_ty = &named_field.ty;
-
-        quote! {
-          pub(super) #field_ident: &#ortho_lifetime mut #field_ty,
+    let OrthoStructInput {
+        name,
+        data_struct,
+        generics,
+        where_clause,
+        vis,
+        names,
+        ..
+    } = input;
+
+    let ortho_struct_mut_name = &names.view_mut;
+    let view_mut_attrs = input.forwarded_attrs.view_mut_ts();
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let view_marker_ts =
+        view_marker_decl_ts(input, &quote!(&#ortho_lifetime mut #name #ty_generics));
+
+    let props_ts_iter = transform_fields_into_ts(input, &|_, _, field, field_attrs| {
+        let field_name = field
+            .ident
+            .as_ref()
+            .map(|field_ident| quote!(#field_ident:));
+        let field_ty = &field.ty;
+        let field_vis = &field.vis;
+        let field_docs = doc_attrs_ts(&field.attrs);
+
+        // Marker fields are held by value, they have no column to point into
+        if marker_field_value_ts(field).is_some() {
+            quote! {
+              #field_docs
+              #field_vis #field_name #field_ty,
+            }
+        } else if field_attrs.flatten.is_some() {
+            let ortho_vec = flattened_ortho_vec_ts(field_ty);
+            quote! {
+              #field_docs
+              #field_vis #field_name #ortho_vec::RefMut<#ortho_lifetime>,
+            }
+        } else if field_attrs.bitpacked.is_some() {
+            quote! {
+              #field_docs
+              #field_vis #field_name ::ortho_vec_derive::bits::BitMut<#ortho_lifetime>,
+            }
+        } else if let Some(value_ty) = field_attrs
+            .nullable
+            .as_ref()
+            .and_then(|_| nullable::option_value_ty(field_ty))
+        {
+            quote! {
+              #field_docs
+              #field_vis #field_name ::core::option::Option<&#ortho_lifetime mut #value_ty>,
+            }
+        } else if field_attrs.dictionary.is_some() {
+            // The value is shared by every element with the same code, so it can't be changed
+            // through a single one
+            quote! {
+              #field_docs
+              #field_vis #field_name &#ortho_lifetime #field_ty,
+            }
+        } else if let Some(encoding) = &field_attrs.encoding {
+            // The elements are shared by a run or decoded, so they can't be changed in place
+            let view_field_ty = encoding::view_field_ty_ts(encoding, field_ty, ortho_lifetime);
+            quote! {
+              #field_docs
+              #field_vis #field_name #view_field_ty,
+            }
+        } else if let Some(column_ty) = &field_attrs.column {
+            let view_field_ty = custom::view_field_ty_ts(column_ty, field_ty, ortho_lifetime, true);
+            quote! {
+              #field_docs
+              #field_vis #field_name #view_field_ty,
+            }
+        } else {
+            quote! {
+              #field_docs
+              #field_vis #field_name &#ortho_lifetime mut #field_ty,
+            }
         }
     });
+    let props_ts_iter = quote! {
+        #props_ts_iter
+        #view_marker_ts
+    };
 
     let ortho_generics = add_lifetime_to_generics(generics, ortho_lifetime);
 
-    (
-        ortho_struct_mut_name.clone(),
+    (ortho_struct_mut_name.clone(), {
+        let struct_body = struct_body_ts(data_struct, where_clause, &props_ts_iter);
+
+        let doc = format!("A mutable view of a [`{name}`] stored in an orthogonal `Vec`.");
+
         quote!(
-        pub(super) struct #ortho_struct_mut_name #ortho_generics
-        #where_clause
-        {
-            #props_ts_iter
-        }),
-    )
+            #[doc = #doc]
+            #[allow(dead_code)]
+            #view_mut_attrs
+            #vis struct #ortho_struct_mut_name #ortho_generics
+            #struct_body)
+    })
 }
 
-fn build_ortho_vec_struct(
-    name: &Ident,
-    data_struct: &DataStruct,
-    generics: &Generics,
-    where_clause: &Option<WhereClause>,
-) -> (Ident, proc_macro2::TokenStream) {
-    let ortho_vec_name = Ident::new(
-        &("OrthoVec".to_string() + &name.to_string()),
-        Span::call_site(),
-    );
+/// Returns the body of the conversion from a `Vec` of the struct, moving `value` into a new
+/// ortho-`Vec` of the same elements.
+fn from_vec_body_ts(input: &OrthoStructInput) -> proc_macro2::TokenStream {
+    // The columns of a storage and the flattened columns are collected from `Vec`s of their
+    // elements all at once, as pushing into a column that can't grow in place copies all of it,
+    // the other columns grow in place and are pushed into directly
+    let columns = columns(input);
+    let (collected_columns, pushed_columns): (Vec<_>, Vec<_>) =
+        columns.iter().partition(|column| {
+            matches!(
+                column,
+                Column::Field { .. } | Column::Group(_) | Column::Flattened { .. }
+            )
+        });
+    let elements_bindings = collected_columns
+        .iter()
+        .map(|column| format_ident!("__elements_{}", column.ident()))
+        .collect::<Vec<_>>();
+    let collected_elements = collected_columns
+        .iter()
+        .map(|column| column.element_ts(&quote!(value)));
+    let pushed_elements = pushed_columns.iter().map(|column| {
+        let element = column.element_ts(&quote!(value));
+        let column_ops = column.ops_ts(input);
 
-    let vec_props_ts_iter = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
-        let field_ty = &named_field.ty;
+        quote!(#column_ops::push(&mut v.#column, #element);)
+    });
+    let collect_columns =
+        collected_columns
+            .iter()
+            .zip(&elements_bindings)
+            .map(|(column, elements)| {
+                if column.is_flattened() {
+                    quote!(v.#column = ::core::convert::From::from(#elements);)
+                } else {
+                    let column_ops = column.ops_ts(input);
+                    quote!(v.#column = #column_ops::from_elements(#elements);)
+                }
+            });
 
+    // The columns in an allocator all grow in place, so they're pushed into as the elements are
+    if input.allocator.is_some() {
         quote! {
-            #field_ident: Vec<#field_ty>,
-        }
-    });
+            let mut v = Self::with_capacity(value.len());
 
-    let ortho_vec_struct_decl = quote!(
-        pub(super) struct #ortho_vec_name #generics
-        #where_clause
-        {
-            #vec_props_ts_iter
-        }
-    );
+            for p in value {
+                v.push(p);
+            }
 
-    let generics_no_trait_bounds = remove_trait_bounds_from_generics(generics);
-    let first_ident_name = take_first_named_field_ts(data_struct);
+            v
+        }
+    } else {
+        quote! {
+            let len = value.len();
+            let mut v = Self::new();
+            #(let mut #elements_bindings = ::ortho_vec_derive::alloc::vec::Vec::with_capacity(len);)*
 
-    let ortho_vec_len_impl = quote!(
-        impl #generics #ortho_vec_name #generics_no_trait_bounds
-        #where_clause
-        {
-            pub(super) fn len(&self) -> usize {
-                self.#first_ident_name.len()
+            for value in value {
+                #(#elements_bindings.push(#collected_elements);)*
+                #(#pushed_elements)*
             }
+
+            #(#collect_columns)*
+            v.__len = len;
+
+            v
         }
-    );
+    }
+}
 
-    let empty_vecs_with_value_capacity_ts_iter =
-        transform_named_fields_into_ts(data_struct, &|named_field| {
-            let field_ident = named_field.ident.as_ref().unwrap();
+fn build_ortho_vec_struct(input: &OrthoStructInput) -> (Ident, proc_macro2::TokenStream) {
+    let OrthoStructInput {
+        name,
+        generics,
+        where_clause,
+        vis,
+        names,
+        ..
+    } = input;
 
-            quote! {
-                #field_ident: Vec::with_capacity(value.len()),
-            }
-        });
+    let ortho_vec_name = &names.ortho_vec;
+    let ortho_vec_attrs = input.forwarded_attrs.ortho_vec_ts();
 
-    let push_p_into_v_props_ts_iter = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
+    let vec_props_ts_iter = transform_columns_into_ts(input, &|column| {
+        let vec_ty = column.vec_ty_ts(input);
 
         quote! {
-          v.#field_ident.push(p.#field_ident);
+            #column: #vec_ty,
         }
     });
 
-    let ortho_vec_from_vec_impl = quote!(
-        impl #generics From<Vec<#name #generics_no_trait_bounds>> for #ortho_vec_name #generics_no_trait_bounds
-        #where_clause
-        {
-            fn from(value: Vec<#name #generics_no_trait_bounds>) -> Self {
-                let mut v = Self {
-                    #empty_vecs_with_value_capacity_ts_iter
-                };
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (ortho_vec_impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
 
-                for p in value {
-                    #push_p_into_v_props_ts_iter
-                }
+    // The length is kept on its own, as the struct may have no columns at all.
+    // The marker makes sure all generics are used, even if only marker fields use them.
+    let ortho_vec_doc =
+        format!("An orthogonal `Vec` of [`{name}`], holding a `Vec` for each of its fields.");
 
-                v
-            }
+    let ortho_vec_struct_decl = quote!(
+        #[doc = #ortho_vec_doc]
+        #ortho_vec_attrs
+        #vis struct #ortho_vec_name #ortho_vec_generics
+        #where_clause
+        {
+            #vec_props_ts_iter
+            __len: usize,
+            __marker: ::core::marker::PhantomData<#name #ty_generics>,
         }
     );
 
-    let into_ortho_name = Ident::new(
-        &("IntoOrtho".to_string() + &name.to_string()),
-        Span::call_site(),
+    let ortho_vec_len_impl = quote!(
+        impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause
+        {
+            /// Returns the number of elements.
+            pub fn len(&self) -> usize {
+                self.__len
+            }
+        }
     );
 
-    let vec_into_ortho_impl = quote!(
-        pub(super) trait #into_ortho_name {
-            type OrthoVec;
-
-            fn into_ortho(self) -> Self::OrthoVec;
-        }
+    let from_vec_body = from_vec_body_ts(input);
 
-        impl #generics #into_ortho_name for Vec<#name #generics_no_trait_bounds>
+    // Converted `Vec`s are in the global allocator, so their elements are too, and there are no
+    // `Vec`s to convert without `alloc`
+    let ortho_vec_from_vec_impl = quote!(::ortho_vec_derive::__with_alloc! {
+        impl #impl_generics ::core::convert::From<::ortho_vec_derive::alloc::vec::Vec<#name #ty_generics>> for #ortho_vec_name #ty_generics
         #where_clause
         {
-            type OrthoVec = #ortho_vec_name #generics_no_trait_bounds;
-
-            fn into_ortho(self) -> Self::OrthoVec {
-                self.into()
+            fn from(value: ::ortho_vec_derive::alloc::vec::Vec<#name #ty_generics>) -> Self {
+                #from_vec_body
             }
         }
-    );
+    });
 
     (
-        ortho_vec_name,
+        ortho_vec_name.clone(),
         quote!(
             #ortho_vec_struct_decl
 
             #ortho_vec_len_impl
 
             #ortho_vec_from_vec_impl
-
-            #vec_into_ortho_impl
         ),
     )
 }
 
-fn impl_vec_method_mut_self_move_struct(
-    struct_name: &Ident,
-    method_name: &str,
-    ortho_vec_name: &Ident,
-    data_struct: &DataStruct,
-    generics: &Generics,
-    where_clause: &Option<WhereClause>,
-) -> proc_macro2::TokenStream {
-    let method_name = Ident::new(method_name, Span::call_site());
+fn impl_vec_push(input: &OrthoStructInput, ortho_vec_name: &Ident) -> proc_macro2::TokenStream {
+    let OrthoStructInput {
+        name: struct_name,
+        generics,
+        where_clause,
+        ..
+    } = input;
 
-    let call_method_on_props_pass_value =
-        transform_named_fields_into_ts(data_struct, &|named_field| {
-            let field_ident = named_field.ident.as_ref().unwrap();
+    let call_push_on_props_pass_value = transform_columns_into_ts(input, &|column| {
+        let element = column.element_ts(&quote!(value));
+        let column_ops = column.ops_ts(input);
 
-            quote! {
-                self.#field_ident.#method_name(value.#field_ident);
-            }
-        });
+        quote! {
+            #column_ops::push(&mut self.#column, #element);
+        }
+    });
 
-    let generics_no_trait_bounds = remove_trait_bounds_from_generics(generics);
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
 
     quote! {
-        impl #generics #ortho_vec_name #generics_no_trait_bounds
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
         #where_clause {
-            pub(super) fn #method_name(&mut self, value: #struct_name #generics_no_trait_bounds) {
-                #call_method_on_props_pass_value
+            /// Appends an element to the back.
+            pub fn push(&mut self, value: #struct_name #ty_generics) {
+                #call_push_on_props_pass_value
+                self.__len += 1;
             }
         }
     }
 }
 
-fn impl_vec_method_mut_self_ret_opt_struct(
-    struct_name: &Ident,
-    method_name: &str,
-    ortho_vec_name: &Ident,
-    data_struct: &DataStruct,
-    generics: &Generics,
-    where_clause: &Option<WhereClause>,
-) -> proc_macro2::TokenStream {
-    let method_name = Ident::new(method_name, Span::call_site());
+fn impl_vec_pop(input: &OrthoStructInput, ortho_vec_name: &Ident) -> proc_macro2::TokenStream {
+    let OrthoStructInput {
+        name: struct_name,
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    let (bind_popped_groups, call_pop_on_props_assign_member) = fields_values_ts(
+        input,
+        &|column| {
+            let column_ops = column.ops_ts(input);
+            quote!(#column_ops::pop(&mut self.#column)?)
+        },
+        &quote!(),
+    );
 
-    let call_method_on_props_assign_member =
-        transform_named_fields_into_ts(data_struct, &|named_field| {
-            let field_ident = named_field.ident.as_ref().unwrap();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
 
-            quote! {
-                #field_ident: self.#field_ident.#method_name()?,
+    quote! {
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            /// Removes the last element and returns it, or `None` if there are no elements.
+            pub fn pop(&mut self) -> ::core::option::Option<#struct_name #ty_generics> {
+                if self.__len == 0 {
+                    return ::core::option::Option::None;
+                }
+
+                self.__len -= 1;
+                #bind_popped_groups
+                ::core::option::Option::Some(#struct_name {
+                    #call_pop_on_props_assign_member
+                })
             }
-        });
+        }
+    }
+}
 
-    let generics_no_trait_bounds = remove_trait_bounds_from_generics(generics);
+fn impl_vec_clear(input: &OrthoStructInput, ortho_vec_name: &Ident) -> proc_macro2::TokenStream {
+    let OrthoStructInput { where_clause, .. } = input;
+
+    let call_clear_on_props = transform_columns_into_ts(input, &|column| {
+        let column_ops = column.ops_ts(input);
+
+        quote! {
+            #column_ops::clear(&mut self.#column);
+        }
+    });
+
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ty_generics, _) = ortho_vec_generics.split_for_impl();
 
     quote! {
-        impl #generics #ortho_vec_name #generics_no_trait_bounds
+        impl #impl_generics #ortho_vec_name #ty_generics
         #where_clause {
-            pub(super) fn #method_name(&mut self) -> Option<#struct_name #generics_no_trait_bounds> {
-                Some(#struct_name {
-                    #call_method_on_props_assign_member
-                })
+            /// Removes all elements.
+            pub fn clear(&mut self) {
+                #call_clear_on_props
+                self.__len = 0;
             }
         }
     }
 }
 
 fn impl_vec_method_mut_self(
+    input: &OrthoStructInput,
     method_name: &str,
     ortho_vec_name: &Ident,
-    data_struct: &DataStruct,
-    generics: &Generics,
-    where_clause: &Option<WhereClause>,
 ) -> proc_macro2::TokenStream {
+    let OrthoStructInput { where_clause, .. } = input;
+
+    let doc = format!("Calls `Vec::{method_name}` on every column.");
     let method_name = Ident::new(method_name, Span::call_site());
 
-    let call_method_on_props = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
+    let call_method_on_props = transform_columns_into_ts(input, &|column| {
+        let column_ops = if method_name == "reverse" && input.allocator.is_some() {
+            // The `Vec`s in an allocator are reversed as the slices they are
+            let ty = column.ty_ts();
+            quote!(<[#ty]>)
+        } else {
+            column.ops_ts(input)
+        };
 
         quote! {
-            self.#field_ident.#method_name();
+            #column_ops::#method_name(&mut self.#column);
         }
     });
 
-    let generics_no_trait_bounds = remove_trait_bounds_from_generics(generics);
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ty_generics, _) = ortho_vec_generics.split_for_impl();
 
     quote! {
-        impl #generics #ortho_vec_name #generics_no_trait_bounds
+        impl #impl_generics #ortho_vec_name #ty_generics
         #where_clause {
-            pub(super) fn #method_name(&mut self) {
+            #[doc = #doc]
+            pub fn #method_name(&mut self) {
                 #call_method_on_props
             }
         }
     }
 }
 
-fn impl_vec_insert(
-    struct_name: &Ident,
-    ortho_vec_name: &Ident,
-    data_struct: &DataStruct,
-    generics: &Generics,
-    where_clause: &Option<WhereClause>,
-) -> proc_macro2::TokenStream {
-    let call_insert_on_props = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
+fn impl_vec_insert(input: &OrthoStructInput, ortho_vec_name: &Ident) -> proc_macro2::TokenStream {
+    let OrthoStructInput {
+        name: struct_name,
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    let call_insert_on_props = transform_columns_into_ts(input, &|column| {
+        let element = column.element_ts(&quote!(element));
+        let column_ops = column.ops_ts(input);
 
         quote! {
-            self.#field_ident.insert(index, element.#field_ident);
+            #column_ops::insert(&mut self.#column, index, #element);
         }
     });
 
-    let generics_no_trait_bounds = remove_trait_bounds_from_generics(generics);
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
 
     quote! {
-        impl #generics #ortho_vec_name #generics_no_trait_bounds
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
         #where_clause {
-            pub(super) fn insert(&mut self, index: usize, element: #struct_name #generics_no_trait_bounds) {
+            /// Inserts an element at `index`, panics if `index` is greater than the length.
+            pub fn insert(&mut self, index: usize, element: #struct_name #ty_generics) {
+                let len = self.__len;
+                ::core::assert!(index <= len, "insertion index (is {index}) should be <= len (is {len})");
+
                 #call_insert_on_props
+                self.__len += 1;
             }
         }
     }
 }
 
 fn impl_vec_method_mut_self_index_ret_struct(
-    struct_name: &Ident,
+    input: &OrthoStructInput,
     method_name: &str,
     ortho_vec_name: &Ident,
-    data_struct: &DataStruct,
-    generics: &Generics,
-    where_clause: &Option<WhereClause>,
 ) -> proc_macro2::TokenStream {
+    let OrthoStructInput {
+        name: struct_name,
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    let doc =
+        format!("Same as `Vec::{method_name}`, removes the element at `index` and returns it.");
     let method_name = Ident::new(method_name, Span::call_site());
 
-    let call_method_on_props = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
+    let (bind_removed_groups, call_method_on_props) = fields_values_ts(
+        input,
+        &|column| {
+            let column_ops = column.ops_ts(input);
+            quote!(#column_ops::#method_name(&mut self.#column, index))
+        },
+        &quote!(),
+    );
+    let index_out_of_bounds_message =
+        format!("{method_name} index (is {{index}}) should be < len (is {{len}})");
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            #[doc = #doc]
+            pub fn #method_name(&mut self, index: usize) -> #struct_name #ty_generics {
+                let len = self.__len;
+                ::core::assert!(index < len, #index_out_of_bounds_message);
+
+                self.__len -= 1;
+                #bind_removed_groups
+                #struct_name {
+                    #call_method_on_props
+                }
+            }
+        }
+    }
+}
+
+fn impl_vec_new(input: &OrthoStructInput, ortho_vec_name: &Ident) -> proc_macro2::TokenStream {
+    let OrthoStructInput {
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    let call_new_on_props = transform_columns_into_ts(input, &|column| {
+        let column_ops = column.ops_ts(input);
 
         quote! {
-            #field_ident: self.#field_ident.#method_name(index),
+            #column: #column_ops::new(),
         }
     });
 
-    let generics_no_trait_bounds = remove_trait_bounds_from_generics(generics);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
 
     quote! {
-        impl #generics #ortho_vec_name #generics_no_trait_bounds
+        impl #impl_generics #ortho_vec_name #ty_generics
         #where_clause {
-            pub(super) fn #method_name(&mut self, index: usize) -> #struct_name #generics_no_trait_bounds {
-                #struct_name {
-                    #call_method_on_props
+            /// Constructs a new, empty orthogonal `Vec`.
+            pub fn new() -> #ortho_vec_name #ty_generics {
+                #ortho_vec_name {
+                    #call_new_on_props
+                    __len: 0,
+                    __marker: ::core::marker::PhantomData,
                 }
             }
         }
     }
 }
 
-fn impl_vec_new(
+fn impl_vec_with_capacity(
+    input: &OrthoStructInput,
     ortho_vec_name: &Ident,
-    data_struct: &DataStruct,
-    generics: &Generics,
-    where_clause: &Option<WhereClause>,
 ) -> proc_macro2::TokenStream {
-    let call_new_on_props = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
-        let field_ty = &named_field.ty;
+    let OrthoStructInput {
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    let call_with_capacity_on_props = transform_columns_into_ts(input, &|column| {
+        let column_ops = column.ops_ts(input);
 
         quote! {
-            #field_ident: Vec::<#field_ty>::new(),
+            #column: #column_ops::with_capacity(capacity),
         }
     });
 
-    let generics_no_trait_bounds = remove_trait_bounds_from_generics(generics);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
 
     quote! {
-        impl #generics #ortho_vec_name #generics_no_trait_bounds
+        impl #impl_generics #ortho_vec_name #ty_generics
         #where_clause {
-            pub(super) fn new() -> #ortho_vec_name #generics_no_trait_bounds {
+            /// Constructs a new, empty orthogonal `Vec` with room for at least `capacity` elements
+            /// in every column.
+            pub fn with_capacity(capacity: usize) -> #ortho_vec_name #ty_generics {
                 #ortho_vec_name {
-                    #call_new_on_props
+                    #call_with_capacity_on_props
+                    __len: 0,
+                    __marker: ::core::marker::PhantomData,
                 }
             }
         }
     }
 }
 
-fn impl_vec_with_capacity(
+/// Implements the constructors of an ortho-`Vec` with an allocator: `{method_name}_in`, which
+/// constructs every column in a clone of the allocator with the method of its `Vec` of the same
+/// name, and `method_name`, which calls it with the global allocator.
+fn impl_vec_constructor_in(
+    input: &OrthoStructInput,
     ortho_vec_name: &Ident,
-    data_struct: &DataStruct,
-    generics: &Generics,
-    where_clause: &Option<WhereClause>,
+    allocator: &Ident,
+    (method_name, doc): (&str, &str),
+    params: &proc_macro2::TokenStream,
+    args: &proc_macro2::TokenStream,
 ) -> proc_macro2::TokenStream {
-    let call_with_capacity_on_props = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
-        let field_ty = &named_field.ty;
+    let OrthoStructInput {
+        data_struct,
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    let method_name_in = format_ident!("{}_in", method_name);
+    let method_name = Ident::new(method_name, Span::call_site());
+    let doc_in = format!("{doc} in `alloc`.");
+    let doc = format!("{doc} in the global allocator.");
+
+    let call_method_in_on_props = transform_columns_into_ts(input, &|column| {
+        let column_ops = column.ops_ts(input);
 
         quote! {
-            #field_ident: Vec::<#field_ty>::with_capacity(capacity),
+            #column: #column_ops::#method_name_in(#args ::core::clone::Clone::clone(&alloc)),
         }
     });
+    // Without any column the allocator isn't needed at all
+    let drop_alloc = (!has_columns(data_struct)).then(|| quote!(let _ = alloc;));
 
-    let generics_no_trait_bounds = remove_trait_bounds_from_generics(generics);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (ortho_vec_impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
 
     quote! {
-        impl #generics #ortho_vec_name #generics_no_trait_bounds
+        impl #impl_generics #ortho_vec_name #ty_generics
         #where_clause {
-            pub(super) fn with_capacity(capacity: usize) -> #ortho_vec_name #generics_no_trait_bounds {
+            #[doc = #doc]
+            pub fn #method_name(#params) -> Self {
+                Self::#method_name_in(#args ::ortho_vec_derive::allocator_api2::alloc::Global)
+            }
+        }
+
+        impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            #[doc = #doc_in]
+            pub fn #method_name_in(#params alloc: #allocator) -> Self
+            where
+                #allocator: ::core::clone::Clone,
+            {
+                #drop_alloc
                 #ortho_vec_name {
-                    #call_with_capacity_on_props
+                    #call_method_in_on_props
+                    __len: 0,
+                    __marker: ::core::marker::PhantomData,
                 }
             }
         }
@@ -471,73 +1630,59 @@ fn impl_vec_with_capacity(
 }
 
 fn build_ortho_vec_impl_vec_methods(
-    struct_name: &Ident,
+    input: &OrthoStructInput,
     ortho_vec_name: &Ident,
-    data_struct: &DataStruct,
-    generics: &Generics,
-    where_clause: &Option<WhereClause>,
 ) -> proc_macro2::TokenStream {
-    let mut_self_move_struct_methods = ["push"].iter().map(|method_name| {
-        impl_vec_method_mut_self_move_struct(
-            struct_name,
-            method_name,
-            ortho_vec_name,
-            data_struct,
-            generics,
-            where_clause,
-        )
-    });
+    let push = impl_vec_push(input, ortho_vec_name);
 
-    let mut_self_ret_opt_struct_methods = ["pop"].iter().map(|method_name| {
-        impl_vec_method_mut_self_ret_opt_struct(
-            struct_name,
-            method_name,
-            ortho_vec_name,
-            data_struct,
-            generics,
-            where_clause,
-        )
-    });
+    let pop = impl_vec_pop(input, ortho_vec_name);
+
+    let clear = impl_vec_clear(input, ortho_vec_name);
 
     // dedup() and sort()/sort_unstable() won't simply work as we need to compare the elements
-    let mut_self_methods = ["clear", "shrink_to_fit", "reverse"]
+    let mut_self_methods = ["shrink_to_fit", "reverse"]
         .iter()
-        .map(|method_name| {
-            impl_vec_method_mut_self(
-                method_name,
-                ortho_vec_name,
-                data_struct,
-                generics,
-                where_clause,
-            )
-        });
+        .map(|method_name| impl_vec_method_mut_self(input, method_name, ortho_vec_name));
 
-    let insert = impl_vec_insert(
-        struct_name,
-        ortho_vec_name,
-        data_struct,
-        generics,
-        where_clause,
-    );
+    let insert = impl_vec_insert(input, ortho_vec_name);
 
     let mut_self_index_ret_struct_methods = ["remove", "swap_remove"].iter().map(|method_name| {
-        impl_vec_method_mut_self_index_ret_struct(
-            struct_name,
-            method_name,
-            ortho_vec_name,
-            data_struct,
-            generics,
-            where_clause,
-        )
+        impl_vec_method_mut_self_index_ret_struct(input, method_name, ortho_vec_name)
     });
 
-    let new = impl_vec_new(ortho_vec_name, data_struct, generics, where_clause);
-
-    let with_capacity = impl_vec_with_capacity(ortho_vec_name, data_struct, generics, where_clause);
+    let (new, with_capacity) = if let Some(allocator) = &input.allocator {
+        (
+            impl_vec_constructor_in(
+                input,
+                ortho_vec_name,
+                allocator,
+                ("new", "Constructs a new, empty orthogonal `Vec`"),
+                &quote!(),
+                &quote!(),
+            ),
+            impl_vec_constructor_in(
+                input,
+                ortho_vec_name,
+                allocator,
+                (
+                    "with_capacity",
+                    "Constructs a new, empty orthogonal `Vec` with room for at least `capacity` elements in every column,",
+                ),
+                &quote!(capacity: usize,),
+                &quote!(capacity,),
+            ),
+        )
+    } else {
+        (
+            impl_vec_new(input, ortho_vec_name),
+            impl_vec_with_capacity(input, ortho_vec_name),
+        )
+    };
 
     quote! {
-        #(#mut_self_move_struct_methods)*
-        #(#mut_self_ret_opt_struct_methods)*
+        #push
+        #pop
+        #clear
         #(#mut_self_methods)*
         #insert
         #(#mut_self_index_ret_struct_methods)*
@@ -547,67 +1692,117 @@ fn build_ortho_vec_impl_vec_methods(
 }
 
 fn build_ortho_vec_iter_struct(
-    name: &Ident,
+    input: &OrthoStructInput,
     ortho_struct_name: &Ident,
     ortho_vec_name: &Ident,
-    data_struct: &DataStruct,
-    generics: &Generics,
-    where_clause: &Option<WhereClause>,
     ortho_lifetime: &Lifetime,
 ) -> (Ident, proc_macro2::TokenStream) {
-    let ortho_vec_iter_name = Ident::new(
-        &("OrthoVecIter".to_string() + &name.to_string()),
-        Span::call_site(),
-    );
+    let OrthoStructInput {
+        name,
+        generics,
+        where_clause,
+        vis,
+        names,
+        ..
+    } = input;
+
+    let ortho_vec_iter_name = &names.iter;
 
     let ortho_generics = add_lifetime_to_generics(generics, ortho_lifetime);
-    let generics_no_trait_bounds = remove_trait_bounds_from_generics(generics);
-    let ortho_generics_no_trait_bounds = remove_trait_bounds_from_generics(&ortho_generics);
+    // The method borrowing the ortho-`Vec` is implemented for it in any allocator
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (_, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+    let ortho_vec_ortho_generics = add_lifetime_to_generics(&ortho_vec_generics, ortho_lifetime);
+    let (ortho_vec_impl_generics, _, _) = ortho_vec_ortho_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let (ortho_impl_generics, ortho_ty_generics, _) = ortho_generics.split_for_impl();
+
+    // Flattened columns and columns of bits are iterated over with their own iterators, the rest
+    // as slices
+    let vec_iter_define_props = transform_columns_into_ts(input, &|column| {
+        if let Some(iter_ty) = column.iter_ty_ts(ortho_lifetime, false) {
+            return quote! {
+                #column: #iter_ty,
+            };
+        }
 
-    let vec_iter_props_assign_iter = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
+        let field_type = column.ty_ts();
 
         quote! {
-            // SAFETY: We do a bounds check one time on the first vector
-            #field_ident: unsafe { self.v.#field_ident.get_unchecked(self.index - 1) },
+            #column: &#ortho_lifetime [#field_type],
+        }
+    });
+    let vec_iter_assign_props_from_self = transform_columns_into_ts(input, &|column| {
+        if column.has_iterator() {
+            let iter = column.iter_call_ts(input, false);
+            quote!(#column: #iter,)
+        } else {
+            let slice = column_slice_ts(input, column, false);
+            quote!(#column: #slice,)
         }
     });
 
+    // SAFETY: We do a bounds check one time on the length of the shortest column, and the slices
+    // of a `Column` are as long as the column, which is an invariant of that unsafe trait
+    let (bind_iter_groups, vec_iter_props_assign_iter) = fields_values_ts(
+        input,
+        &|column| {
+            if column.has_iterator() {
+                quote!(self.#column.next()?)
+            } else {
+                quote!(unsafe { self.#column.get_unchecked(self.__index - 1) })
+            }
+        },
+        &quote!(&),
+    );
+    let view_marker_value = view_marker_value_ts(input);
+    let min_columns_len = min_columns_len_ts(input, &quote!(self));
+
+    let doc = format!("An iterator over views of the elements of [`{ortho_vec_name}`].");
+
     (
         ortho_vec_iter_name.clone(),
         quote!(
-            pub(super) struct #ortho_vec_iter_name #ortho_generics
+            #[doc = #doc]
+            #vis struct #ortho_vec_iter_name #ortho_generics
             #where_clause
             {
-                v: & #ortho_lifetime #ortho_vec_name #generics_no_trait_bounds,
-                index: usize,
+                #vec_iter_define_props
+                __index: usize,
+                __len: usize,
+                __marker: ::core::marker::PhantomData<&#ortho_lifetime #name #ty_generics>,
             }
 
-            impl #ortho_generics Iterator for #ortho_vec_iter_name #ortho_generics_no_trait_bounds
+            impl #ortho_impl_generics ::core::iter::Iterator for #ortho_vec_iter_name #ortho_ty_generics
             #where_clause
             {
-                type Item = #ortho_struct_name #ortho_generics_no_trait_bounds;
+                type Item = #ortho_struct_name #ortho_ty_generics;
 
                 #[inline]
-                fn next(&mut self) -> Option<Self::Item> {
-                    if self.index >= self.v.len() {
-                        None
+                fn next(&mut self) -> ::core::option::Option<Self::Item> {
+                    if self.__index >= self.__len {
+                        ::core::option::Option::None
                     } else {
-                        self.index += 1;
-                        Some(#ortho_struct_name {
+                        self.__index += 1;
+                        #bind_iter_groups
+                        ::core::option::Option::Some(#ortho_struct_name {
                             #vec_iter_props_assign_iter
+                            #view_marker_value
                         })
                     }
                 }
             }
 
-            impl #ortho_generics #ortho_vec_name #generics_no_trait_bounds
+            impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
             #where_clause
             {
-                pub(super) fn iter(&#ortho_lifetime self) -> #ortho_vec_iter_name #ortho_generics_no_trait_bounds {
+                /// Returns an iterator over views of the elements.
+                pub fn iter(&#ortho_lifetime self) -> #ortho_vec_iter_name #ortho_ty_generics {
                     #ortho_vec_iter_name {
-                        v: &self,
-                        index: 0
+                        __index: 0,
+                        __len: #min_columns_len,
+                        #vec_iter_assign_props_from_self
+                        __marker: ::core::marker::PhantomData,
                     }
                 }
             }
@@ -615,117 +1810,153 @@ fn build_ortho_vec_iter_struct(
     )
 }
 
+// Slices and flattened columns are iterated over differently, and the iterator needs both
+#[allow(clippy::too_many_lines)]
 fn build_ortho_vec_iter_mut_struct(
-    name: &Ident,
+    input: &OrthoStructInput,
     ortho_struct_mut_name: &Ident,
     ortho_vec_name: &Ident,
-    data_struct: &DataStruct,
-    generics: &Generics,
-    where_clause: &Option<WhereClause>,
     ortho_lifetime: &Lifetime,
 ) -> (Ident, proc_macro2::TokenStream) {
-    let ortho_vec_iter_mut_name = Ident::new(
-        &("OrthoVecIterMut".to_string() + &name.to_string()),
-        Span::call_site(),
-    );
+    let OrthoStructInput {
+        name,
+        generics,
+        where_clause,
+        vis,
+        names,
+        ..
+    } = input;
+
+    let ortho_vec_iter_mut_name = &names.iter_mut;
 
     let ortho_generics = add_lifetime_to_generics(generics, ortho_lifetime);
-    let generics_no_trait_bounds = remove_trait_bounds_from_generics(generics);
-    let ortho_generics_no_trait_bounds = remove_trait_bounds_from_generics(&ortho_generics);
+    // The method borrowing the ortho-`Vec` is implemented for it in any allocator
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (_, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+    let ortho_vec_ortho_generics = add_lifetime_to_generics(&ortho_vec_generics, ortho_lifetime);
+    let (ortho_vec_impl_generics, _, _) = ortho_vec_ortho_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let (ortho_impl_generics, ortho_ty_generics, _) = ortho_generics.split_for_impl();
+
+    // Flattened columns and columns of bits are iterated over with their own iterators, the rest
+    // as slices
+    let vec_iter_mut_define_props = transform_columns_into_ts(input, &|column| {
+        if let Some(iter_ty) = column.iter_ty_ts(ortho_lifetime, true) {
+            return quote! {
+                #column: #iter_ty,
+            };
+        }
 
-    let vec_iter_mut_define_props = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
-        let field_type = &named_field.ty;
+        let field_type = column.ty_ts();
 
         quote! {
-            #field_ident: &#ortho_lifetime mut [#field_type],
+            #column: &#ortho_lifetime mut [#field_type],
         }
     });
 
-    let vec_iter_mut_assign_props_from_self =
-        transform_named_fields_into_ts(data_struct, &|named_field| {
-            let field_ident = named_field.ident.as_ref().unwrap();
-
+    let vec_iter_mut_assign_props_from_self = transform_columns_into_ts(input, &|column| {
+        if column.has_iterator() {
+            let iter = column.iter_call_ts(input, column.is_mutable());
             quote! {
-                #field_ident: self.#field_ident.as_mut_slice(),
+                #column: #iter,
             }
-        });
+        } else {
+            let slice = column_slice_ts(input, column, true);
+            quote! {
+                #column: #slice,
+            }
+        }
+    });
 
-    let mut_entry_props_assign_iter = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
+    // SAFETY: The borrow will live long enough because the originial slice lives for 'ortho
+    let (bind_iter_mut_groups, mut_entry_props_assign_iter) = fields_values_ts(
+        input,
+        &|column| {
+            if column.has_iterator() {
+                quote!(self.#column.next()?)
+            } else {
+                quote!(unsafe { &mut *::core::ptr::addr_of_mut!(*#column) })
+            }
+        },
+        &quote!(&mut),
+    );
+    let view_marker_value = view_marker_value_ts(input);
+    let min_columns_len = min_columns_len_ts(input, &quote!(self));
 
-        quote! {
-            // SAFETY: The borrow will live long enough because the originial slice lives for 'ortho
-            #field_ident: unsafe { &mut *(#field_ident as *mut _) },
+    let split_at_first_assignment = transform_columns_into_ts(input, &|column| {
+        if column.has_iterator() {
+            return quote! {};
         }
-    });
 
-    let split_at_first_assignment = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
-        let rest_of_ident = Ident::new(
-            &("rest_of_".to_string() + &field_ident.to_string()),
-            Span::call_site(),
-        );
+        let rest_of_ident = format_ident!("rest_of_{}", column.ident());
 
         quote! {
-            // SAFETY: We do a bounds check one time on the first slice
-            let (#field_ident, #rest_of_ident) = unsafe { self.#field_ident.split_first_mut().unwrap_unchecked() };
+            // SAFETY: We do a bounds check one time on the length, which no slice is shorter than, as
+            // `Column`s have to deref to slices as long as they are
+            let (#column, #rest_of_ident) = unsafe { self.#column.split_first_mut().unwrap_unchecked() };
         }
     });
 
-    let assign_rest_of_to_self = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
-        let rest_of_ident = Ident::new(
-            &("rest_of_".to_string() + &field_ident.to_string()),
-            Span::call_site(),
-        );
+    let assign_rest_of_to_self = transform_columns_into_ts(input, &|column| {
+        if column.has_iterator() {
+            return quote! {};
+        }
+
+        let rest_of_ident = format_ident!("rest_of_{}", column.ident());
 
         quote! {
             // SAFETY: The slice will live long enough because the originial slice lives for 'ortho
-            self.#field_ident = unsafe { &mut *(#rest_of_ident as *mut _) };
+            self.#column = unsafe { &mut *(#rest_of_ident as *mut _) };
         }
     });
 
-    let first_ident_name = take_first_named_field_ts(data_struct);
+    let doc = format!("An iterator over mutable views of the elements of [`{ortho_vec_name}`].");
 
     (
         ortho_vec_iter_mut_name.clone(),
         quote!(
-            pub(super) struct #ortho_vec_iter_mut_name #ortho_generics
+            #[doc = #doc]
+            #vis struct #ortho_vec_iter_mut_name #ortho_generics
             #where_clause
             {
                 #vec_iter_mut_define_props
-                index: usize,
+                __len: usize,
+                __marker: ::core::marker::PhantomData<&#ortho_lifetime mut #name #ty_generics>,
             }
 
-            impl #ortho_generics Iterator for #ortho_vec_iter_mut_name #ortho_generics_no_trait_bounds
+            impl #ortho_impl_generics ::core::iter::Iterator for #ortho_vec_iter_mut_name #ortho_ty_generics
             #where_clause
             {
-                type Item = #ortho_struct_mut_name #ortho_generics_no_trait_bounds;
+                type Item = #ortho_struct_mut_name #ortho_ty_generics;
 
                 #[inline]
-                fn next(&mut self) -> Option<Self::Item> {
-                    if self.index >= self.#first_ident_name.len() {
-                        None
+                fn next(&mut self) -> ::core::option::Option<Self::Item> {
+                    // The slices shrink with every call, along with the remaining length
+                    if self.__len == 0 {
+                        ::core::option::Option::None
                     } else {
-                        self.index += 1;
+                        self.__len -= 1;
                         #split_at_first_assignment
 
                         #assign_rest_of_to_self
-                        Some(#ortho_struct_mut_name {
+                        #bind_iter_mut_groups
+                        ::core::option::Option::Some(#ortho_struct_mut_name {
                             #mut_entry_props_assign_iter
+                            #view_marker_value
                         })
                     }
                 }
             }
 
-            impl #ortho_generics #ortho_vec_name #generics_no_trait_bounds
+            impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
             #where_clause
             {
-                pub(super) fn iter_mut(&#ortho_lifetime mut self) -> #ortho_vec_iter_mut_name #ortho_generics_no_trait_bounds {
+                /// Returns an iterator over mutable views of the elements.
+                pub fn iter_mut(&#ortho_lifetime mut self) -> #ortho_vec_iter_mut_name #ortho_ty_generics {
                     #ortho_vec_iter_mut_name {
+                        __len: #min_columns_len,
                         #vec_iter_mut_assign_props_from_self
-                        index: 0
+                        __marker: ::core::marker::PhantomData,
                     }
                 }
             }
@@ -734,85 +1965,101 @@ fn build_ortho_vec_iter_mut_struct(
 }
 
 fn build_ortho_vec_into_iter_struct(
-    name: &Ident,
+    input: &OrthoStructInput,
     ortho_vec_name: &Ident,
-    data_struct: &DataStruct,
-    generics: &Generics,
-    where_clause: &Option<WhereClause>,
 ) -> (Ident, proc_macro2::TokenStream) {
-    let ortho_vec_into_iter_name = Ident::new(
-        &("OrthoVecIntoIter".to_string() + &name.to_string()),
-        Span::call_site(),
-    );
-
-    let generics_no_trait_bounds = remove_trait_bounds_from_generics(generics);
+    let OrthoStructInput {
+        name,
+        generics,
+        where_clause,
+        vis,
+        names,
+        ..
+    } = input;
 
-    let into_iter_props = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
-        let field_ty = named_field.ty.clone();
+    let ortho_vec_into_iter_name = &names.into_iter;
 
-        quote! {
-            #field_ident: <Vec<#field_ty> as IntoIterator>::IntoIter,
-        }
-    });
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
 
-    let iter_props_assign_into_iter = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
+    let into_iter_props = transform_columns_into_ts(input, &|column| {
+        let vec_ty = column.vec_ty_ts(input);
 
         quote! {
-            // SAFETY: We do a bounds check
-            #field_ident: unsafe { self.#field_ident.next().unwrap_unchecked() },
+            #column: <#vec_ty as ::core::iter::IntoIterator>::IntoIter,
         }
     });
 
-    let into_iter_for_each_vec = transform_named_fields_into_ts(data_struct, &|named_field| {
-        let field_ident = named_field.ident.as_ref().unwrap();
+    // SAFETY: We do a bounds check on the length of the shortest column, and a `Column` yields
+    // as many elements as it's long, which is an invariant of that unsafe trait, while the
+    // columns given with `column` are safe to implement, so their lengths aren't trusted
+    let (bind_into_iter_groups, iter_props_assign_into_iter) = fields_values_ts(
+        input,
+        &|column| {
+            if column.is_flattened() || matches!(column, Column::Custom { .. }) {
+                quote!(self.#column.next()?)
+            } else {
+                quote!(unsafe { self.#column.next().unwrap_unchecked() })
+            }
+        },
+        &quote!(),
+    );
 
+    let min_columns_len = min_columns_len_ts(input, &quote!(self));
+
+    let into_iter_for_each_vec = transform_columns_into_ts(input, &|column| {
         quote! {
-            #field_ident: self.#field_ident.into_iter(),
+            #column: ::core::iter::IntoIterator::into_iter(self.#column),
         }
     });
 
+    let doc = format!("An iterator moving the elements out of [`{ortho_vec_name}`].");
+
     (
         ortho_vec_into_iter_name.clone(),
         quote!(
-            pub(super) struct #ortho_vec_into_iter_name #generics
+            #[doc = #doc]
+            #vis struct #ortho_vec_into_iter_name #ortho_vec_generics
             #where_clause
             {
-                index: usize,
-                len: usize,
+                __index: usize,
+                __len: usize,
                 #into_iter_props
+                __marker: ::core::marker::PhantomData<#name #ty_generics>,
             }
 
-            impl #generics Iterator for #ortho_vec_into_iter_name #generics_no_trait_bounds
+            impl #impl_generics ::core::iter::Iterator for #ortho_vec_into_iter_name #ortho_vec_ty_generics
             #where_clause
             {
-                type Item = #name #generics_no_trait_bounds;
+                type Item = #name #ty_generics;
 
                 #[inline]
-                fn next(&mut self) -> Option<Self::Item> {
-                    if self.index >= self.len {
-                        None
+                fn next(&mut self) -> ::core::option::Option<Self::Item> {
+                    if self.__index >= self.__len {
+                        ::core::option::Option::None
                     } else {
-                        self.index += 1;
-                        Some(#name {
+                        self.__index += 1;
+                        #bind_into_iter_groups
+                        ::core::option::Option::Some(#name {
                             #iter_props_assign_into_iter
                         })
                     }
                 }
             }
 
-            impl #generics IntoIterator for #ortho_vec_name #generics_no_trait_bounds
+            impl #impl_generics ::core::iter::IntoIterator for #ortho_vec_name #ortho_vec_ty_generics
             #where_clause
             {
-                type Item = #name #generics_no_trait_bounds;
-                type IntoIter = #ortho_vec_into_iter_name #generics_no_trait_bounds;
+                type Item = #name #ty_generics;
+                type IntoIter = #ortho_vec_into_iter_name #ortho_vec_ty_generics;
 
-                fn into_iter(self) -> #ortho_vec_into_iter_name #generics_no_trait_bounds {
+                fn into_iter(self) -> #ortho_vec_into_iter_name #ortho_vec_ty_generics {
                     #ortho_vec_into_iter_name {
-                        index: 0,
-                        len: self.len(),
+                        __index: 0,
+                        __len: #min_columns_len,
                         #into_iter_for_each_vec
+                        __marker: ::core::marker::PhantomData,
                     }
                 }
             }
@@ -820,103 +2067,418 @@ fn build_ortho_vec_into_iter_struct(
     )
 }
 
-#[proc_macro_derive(OrthoVec)]
-pub fn ortho_vec(input: TokenStream) -> TokenStream {
-    let DeriveInput {
-        ident: struct_name_ident,
-        data,
-        mut generics,
+/// Returns whether the generated types are visible enough to be named by the `Columnar`
+/// implementation of the struct, which can't hold types less visible than the struct.
+///
+/// Restricted visibilities are compared by their paths, so `pub(crate)` is as visible as
+/// `pub(in crate)`.
+fn is_columnar_visible(vis: &Visibility, container_attrs: &ContainerAttrs) -> bool {
+    container_attrs
+        .vis
+        .as_ref()
+        .map_or(true, |ortho_vis| match (vis, ortho_vis) {
+            (Visibility::Inherited, _) | (_, Visibility::Public(_)) => true,
+            (Visibility::Restricted(restricted), Visibility::Restricted(ortho_restricted)) => {
+                restricted_path(restricted) == restricted_path(ortho_restricted)
+            }
+            _ => false,
+        })
+}
+
+/// Returns the path a restricted visibility is restricted to, without the `in` it may be
+/// written with.
+fn restricted_path(restricted: &VisRestricted) -> Vec<String> {
+    restricted
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect()
+}
+
+/// Returns the methods iterating over the column of each field in chunks of SIMD lanes, which the
+/// ortho-`Vec` has when its columns are aligned.
+///
+/// Only the columns of single fields have them, the elements of groups and flattened fields don't
+/// fit in lanes.
+fn build_ortho_vec_lanes_impl(input: &OrthoStructInput) -> proc_macro2::TokenStream {
+    let OrthoStructInput {
+        names,
+        where_clause,
+        align,
         ..
-    }: DeriveInput = parse_macro_input!(input as DeriveInput);
+    } = input;
 
-    let name = &struct_name_ident;
+    let Some(align) = align else {
+        return quote! {};
+    };
 
-    let gen = if let syn::Data::Struct(data_struct) = data {
-        let where_clause = generics.where_clause.take();
+    let ortho_vec_name = &names.ortho_vec;
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (ortho_vec_impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    let lanes_methods = columns(input).into_iter().filter_map(|column| {
+        let Column::Field { ident, .. } = &column else {
+            return None;
+        };
+
+        let ty = column.ty_ts();
+        let lanes = format_ident!("{}_lanes", ident);
+        let lanes_mut = format_ident!("{}_lanes_mut", ident);
+        let slice = column_slice_ts(input, &column, false);
+        let slice_mut = column_slice_ts(input, &column, true);
+        let lanes_doc = format!(
+            "Returns an iterator over the `{ident}` column in chunks of `LANES` elements, each \
+            aligned to its size if that divides {align} bytes, and the remainder."
+        );
+        let lanes_mut_doc = format!(
+            "Returns an iterator over the `{ident}` column in mutable chunks of `LANES` elements, \
+            each aligned to its size if that divides {align} bytes, and the remainder."
+        );
 
-        let ortho_lifetime = Lifetime::new("'ortho", Span::call_site());
+        Some(quote! {
+            #[doc = #lanes_doc]
+            pub fn #lanes<const LANES: usize>(&self) -> ::ortho_vec_derive::lanes::Lanes<'_, #ty, LANES> {
+                ::ortho_vec_derive::lanes::Lanes::new(#slice)
+            }
 
-        let (ortho_vec_name, ortho_vec_ts) =
-            build_ortho_vec_struct(name, &data_struct, &generics, &where_clause);
+            #[doc = #lanes_mut_doc]
+            pub fn #lanes_mut<const LANES: usize>(&mut self) -> ::ortho_vec_derive::lanes::LanesMut<'_, #ty, LANES> {
+                ::ortho_vec_derive::lanes::LanesMut::new(#slice_mut)
+            }
+        })
+    });
 
-        let ortho_vec_methods_ts = build_ortho_vec_impl_vec_methods(
-            name,
-            &ortho_vec_name,
-            &data_struct,
-            &generics,
-            &where_clause,
-        );
+    quote! {
+        impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause
+        {
+            #(#lanes_methods)*
+        }
+    }
+}
 
-        let (ortho_struct_name, ortho_struct_ts) = build_ortho_struct(
-            name,
-            &data_struct,
-            &generics,
-            &where_clause,
-            &ortho_lifetime,
-        );
+/// Implements `Columnar` for the struct (or enum), linking it to its ortho-`Vec`.
+fn build_columnar_impl(
+    name: &Ident,
+    generics: &Generics,
+    where_clause: &Option<WhereClause>,
+    ortho_vec_name: &Ident,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::ortho_vec_derive::Columnar for #name #ty_generics
+        #where_clause
+        {
+            type Vec = #ortho_vec_name #ty_generics;
+        }
+    }
+}
+
+/// Implements `OrthoVec` for the ortho-`Vec` of the struct (or enum), through its inherent
+/// methods.
+fn build_ortho_vec_trait_impl(
+    name: &Ident,
+    generics: &Generics,
+    where_clause: &Option<WhereClause>,
+    names: &OrthoNames,
+    ortho_lifetime: &Lifetime,
+) -> proc_macro2::TokenStream {
+    let OrthoNames {
+        ortho_vec,
+        view,
+        view_mut,
+        iter,
+        iter_mut,
+        ..
+    } = names;
+
+    let ortho_generics = add_lifetime_to_generics(generics, ortho_lifetime);
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let (_, ortho_ty_generics, _) = ortho_generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::ortho_vec_derive::OrthoVec for #ortho_vec #ty_generics
+        #where_clause
+        {
+            type Ref<#ortho_lifetime> = #view #ortho_ty_generics where Self: #ortho_lifetime;
+            type RefMut<#ortho_lifetime> = #view_mut #ortho_ty_generics where Self: #ortho_lifetime;
+            type Iter<#ortho_lifetime> = #iter #ortho_ty_generics where Self: #ortho_lifetime;
+            type IterMut<#ortho_lifetime> = #iter_mut #ortho_ty_generics where Self: #ortho_lifetime;
+
+            fn new() -> Self {
+                Self::new()
+            }
+
+            fn with_capacity(capacity: usize) -> Self {
+                Self::with_capacity(capacity)
+            }
+
+            fn len(&self) -> usize {
+                Self::len(self)
+            }
+
+            fn push(&mut self, value: #name #ty_generics) {
+                Self::push(self, value)
+            }
+
+            fn pop(&mut self) -> ::core::option::Option<#name #ty_generics> {
+                Self::pop(self)
+            }
+
+            fn insert(&mut self, index: usize, element: #name #ty_generics) {
+                Self::insert(self, index, element)
+            }
+
+            fn remove(&mut self, index: usize) -> #name #ty_generics {
+                Self::remove(self, index)
+            }
+
+            fn swap_remove(&mut self, index: usize) -> #name #ty_generics {
+                Self::swap_remove(self, index)
+            }
+
+            fn clear(&mut self) {
+                Self::clear(self)
+            }
+
+            fn reverse(&mut self) {
+                Self::reverse(self)
+            }
+
+            fn shrink_to_fit(&mut self) {
+                Self::shrink_to_fit(self)
+            }
+
+            fn iter(&self) -> Self::Iter<'_> {
+                Self::iter(self)
+            }
+
+            fn iter_mut(&mut self) -> Self::IterMut<'_> {
+                Self::iter_mut(self)
+            }
+        }
+    }
+}
+
+/// Builds all of the items generated for a struct: the ortho-`Vec`, its methods, the views and
+/// the iterators.
+fn build_ortho_vec_items(input: &OrthoStructInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ortho_lifetime = Lifetime::new("'ortho", Span::call_site());
+
+    combine_errors(
+        [
+            check_generics(&input.generics, &ortho_lifetime),
+            check_fields(input),
+            single::check_fields(input),
+            blocks::check_fields(input),
+            check_allocator_name(&input.generics, input.allocator.as_ref()),
+            check_allocator_fields(input),
+            bits::check_fields(input),
+            nullable::check_fields(input),
+            dictionary::check_fields(input),
+            encoding::check_fields(input),
+            custom::check_fields(input),
+        ]
+        .into_iter()
+        .filter_map(Result::err),
+    )?;
+
+    let (ortho_vec_name, ortho_vec_ts) = match input.layout {
+        ColumnsLayout::Columns => build_ortho_vec_struct(input),
+        ColumnsLayout::Single => single::build_ortho_vec_struct(input),
+        ColumnsLayout::Blocks => blocks::build_ortho_vec_struct(input),
+    };
+
+    let ortho_vec_methods_ts = match input.layout {
+        ColumnsLayout::Columns => build_ortho_vec_impl_vec_methods(input, &ortho_vec_name),
+        ColumnsLayout::Single => single::build_ortho_vec_impl_vec_methods(input, &ortho_vec_name),
+        ColumnsLayout::Blocks => blocks::build_ortho_vec_impl_vec_methods(input, &ortho_vec_name),
+    };
 
-        let (_, ortho_vec_iter_ts) = build_ortho_vec_iter_struct(
-            name,
+    let (ortho_struct_name, ortho_struct_ts) = build_ortho_struct(input, &ortho_lifetime);
+
+    let (_, ortho_vec_iter_ts) = if input.layout == ColumnsLayout::Blocks {
+        blocks::build_ortho_vec_iter_struct(
+            input,
             &ortho_struct_name,
             &ortho_vec_name,
-            &data_struct,
-            &generics,
-            &where_clause,
             &ortho_lifetime,
-        );
+        )
+    } else {
+        build_ortho_vec_iter_struct(input, &ortho_struct_name, &ortho_vec_name, &ortho_lifetime)
+    };
 
-        let (ortho_struct_mut_name, ortho_struct_mut_ts) = build_ortho_struct_mut(
-            name,
-            &data_struct,
-            &generics,
-            &where_clause,
-            &ortho_lifetime,
-        );
+    let (ortho_struct_mut_name, ortho_struct_mut_ts) =
+        build_ortho_struct_mut(input, &ortho_lifetime);
 
-        let (_, ortho_vec_iter_mut_ts) = build_ortho_vec_iter_mut_struct(
-            name,
+    let (_, ortho_vec_iter_mut_ts) = if input.layout == ColumnsLayout::Blocks {
+        blocks::build_ortho_vec_iter_mut_struct(
+            input,
             &ortho_struct_mut_name,
             &ortho_vec_name,
-            &data_struct,
-            &generics,
-            &where_clause,
             &ortho_lifetime,
-        );
-
-        let (_, ortho_vec_into_iter_ts) = build_ortho_vec_into_iter_struct(
-            name,
+        )
+    } else {
+        build_ortho_vec_iter_mut_struct(
+            input,
+            &ortho_struct_mut_name,
             &ortho_vec_name,
-            &data_struct,
+            &ortho_lifetime,
+        )
+    };
+
+    let (_, ortho_vec_into_iter_ts) = match input.layout {
+        ColumnsLayout::Columns => build_ortho_vec_into_iter_struct(input, &ortho_vec_name),
+        ColumnsLayout::Single => single::build_ortho_vec_into_iter_struct(input, &ortho_vec_name),
+        ColumnsLayout::Blocks => blocks::build_ortho_vec_into_iter_struct(input, &ortho_vec_name),
+    };
+
+    let ortho_vec_trait_impl_ts = build_ortho_vec_trait_impl(
+        &input.name,
+        &input.generics,
+        &input.where_clause,
+        &input.names,
+        &ortho_lifetime,
+    );
+
+    let groups_ts_iter = input
+        .groups
+        .iter()
+        .map(|group| group.decl_ts(&input.forwarded_attrs));
+
+    Ok(quote! {
+        #(#groups_ts_iter)*
+
+        #ortho_vec_ts
+        #ortho_vec_methods_ts
+
+        #ortho_struct_ts
+        #ortho_vec_iter_ts
+
+        #ortho_struct_mut_ts
+        #ortho_vec_iter_mut_ts
+
+        #ortho_vec_into_iter_ts
+
+        #ortho_vec_trait_impl_ts
+    })
+}
+
+fn expand_ortho_vec(
+    DeriveInput {
+        attrs,
+        vis,
+        ident: name,
+        data,
+        mut generics,
+    }: DeriveInput,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let container_attrs = ContainerAttrs::from_attrs(&attrs)?;
+    let where_clause = generics.where_clause.take();
+
+    // Types with less visible generated types can't be flattened into others
+    let columnar_impl_ts = if is_columnar_visible(&vis, &container_attrs) {
+        build_columnar_impl(
+            &name,
             &generics,
             &where_clause,
-        );
+            &OrthoNames::new(&name, &container_attrs).ortho_vec,
+        )
+    } else {
+        quote! {}
+    };
 
-        let ortho_mod_name = Ident::new(
-            &("ortho_mod_".to_string() + &name.to_string()),
-            Span::call_site(),
-        );
+    // The generated types are as visible as the struct, unless asked otherwise
+    let vis = container_attrs.vis.clone().unwrap_or(vis);
 
-        quote! {
-            // Wrap with a module so no one can mutate attributes unsafely
-            pub(crate) mod #ortho_mod_name {
-                use super::#struct_name_ident;
+    let items_ts = match data {
+        syn::Data::Struct(data_struct) => {
+            if let Some(tag_name) = &container_attrs.tag {
+                return Err(syn::Error::new_spanned(
+                    tag_name,
+                    "only enums have a tag, so `tag` can't be given for a struct",
+                ));
+            }
 
-                #ortho_vec_ts
-                #ortho_vec_methods_ts
+            let field_attrs = FieldAttrs::from_fields(&data_struct.fields)?;
+            let input = OrthoStructInput {
+                names: OrthoNames::new(&name, &container_attrs),
+                groups: field_groups(&name, &data_struct, &field_attrs, &generics, &where_clause)?,
+                forwarded_attrs: container_attrs.forwarded.clone(),
+                storage: container_attrs.columns_storage(),
+                layout: columns_layout(&container_attrs, &data_struct),
+                allocator: container_attrs.allocator.clone(),
+                align: container_attrs.align.clone(),
+                chunk: container_attrs.chunk.clone(),
+                name,
+                data_struct,
+                field_attrs,
+                generics,
+                where_clause,
+                vis,
+            };
 
-                #ortho_struct_ts
-                #ortho_vec_iter_ts
+            // Only structs get the lanes, the views of blocks, the columns of bits, the present
+            // values of nullable columns, the codes of dictionary-encoded columns, the encoded
+            // columns and the columns given by the user, the ortho-`Vec`s of the variants of an
+            // enum can't be reached through it
+            let ortho_vec_items = build_ortho_vec_items(&input)?;
+            let lanes_impl = build_ortho_vec_lanes_impl(&input);
+            let blocks_views = blocks::build_ortho_vec_blocks_views(&input);
+            let bits_impl = bits::build_ortho_vec_bits_impl(&input);
+            let nullable_impl = nullable::build_ortho_vec_nullable_impl(&input);
+            let dictionary_impl = dictionary::build_ortho_vec_dictionary_impl(&input);
+            let encoding_impl = encoding::build_ortho_vec_encoding_impl(&input);
+            let custom_impl = custom::build_ortho_vec_custom_impl(&input);
 
-                #ortho_struct_mut_ts
-                #ortho_vec_iter_mut_ts
+            Ok(quote! {
+                #ortho_vec_items
 
-                #ortho_vec_into_iter_ts
-            }
-            use #ortho_mod_name::*;
+                #lanes_impl
+
+                #blocks_views
+
+                #bits_impl
+
+                #nullable_impl
+
+                #dictionary_impl
+
+                #encoding_impl
+
+                #custom_impl
+            })
         }
-    } else {
-        quote!()
-    };
+        syn::Data::Enum(data_enum) => enums::build_ortho_vec_enum_items(
+            &name,
+            &data_enum,
+            &generics,
+            &where_clause,
+            &vis,
+            &container_attrs,
+        ),
+        syn::Data::Union(data_union) => Err(syn::Error::new_spanned(
+            data_union.union_token,
+            "OrthoVec can't be derived on unions, as only one of their fields holds a value",
+        )),
+    }?;
+
+    Ok(quote! {
+        #items_ts
+
+        #columnar_impl_ts
+    })
+}
+
+#[proc_macro_derive(OrthoVec, attributes(ortho))]
+pub fn ortho_vec(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
 
-    gen.into()
+    // The items are generated right next to the struct, so they work wherever it is defined
+    // (inside functions too) and see the same names it does
+    expand_ortho_vec(derive_input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
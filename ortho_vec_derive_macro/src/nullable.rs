@@ -0,0 +1,144 @@
+//! The columns of the `Option` fields given `#[ortho(nullable)]`, held as a validity bitmap and a
+//! dense column of the present values.
+//!
+//! Every such field gets a `NullableVec` column of its own, keeping both in the storage of the
+//! ortho-`Vec`.
+//! The views hold an `Option` of a reference to the value instead of a reference to the `Option`,
+//! and the ortho-`Vec` has iterators over the present values only.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{GenericArgument, PathArguments, Type};
+
+use crate::{
+    attrs::FieldAttrs, columns, combine_errors, flattened_error, stored_apart_layout_error, Column,
+    OrthoStructInput,
+};
+
+/// Returns `T` if `ty` is `Option<T>`, the only type of field that can be nullable.
+pub(crate) fn option_value_ty(ty: &Type) -> Option<&Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+
+    match args.args.first() {
+        Some(GenericArgument::Type(value_ty))
+            if type_path.qself.is_none() && segment.ident == "Option" && args.args.len() == 1 =>
+        {
+            Some(value_ty)
+        }
+        _ => None,
+    }
+}
+
+/// Returns an error for every nullable field that isn't an `Option`, is flattened, or is in an
+/// ortho-`Vec` that can't hold a nullable column.
+pub(crate) fn check_fields(input: &OrthoStructInput) -> syn::Result<()> {
+    combine_errors(input.fields().flat_map(|(field, field_attrs)| {
+        let FieldAttrs {
+            nullable: Some(nullable),
+            flatten,
+            ..
+        } = field_attrs
+        else {
+            return Vec::new();
+        };
+
+        let ty_error = option_value_ty(&field.ty)
+            .is_none()
+            .then(|| syn::Error::new_spanned(&field.ty, "only `Option` fields can be nullable"));
+        let flatten_error = flatten
+            .as_ref()
+            .map(|flatten| flattened_error(flatten, "nullable"));
+        let layout_error = stored_apart_layout_error("nullable fields", input, nullable);
+
+        ty_error
+            .into_iter()
+            .chain(flatten_error)
+            .chain(layout_error)
+            .collect()
+    }))
+}
+
+/// Returns the type of a nullable column of `Option`s of `value_ty`, in the storage of the
+/// ortho-`Vec`.
+pub(crate) fn vec_ty_ts(input: &OrthoStructInput, value_ty: &Type) -> TokenStream {
+    let storage = input.storage.as_ref().map_or_else(
+        || quote!(::ortho_vec_derive::storage::VecStorage),
+        |storage| quote!(#storage),
+    );
+
+    quote!(::ortho_vec_derive::nullable::NullableVec<#value_ty, #storage>)
+}
+
+/// Returns the methods going over the present values of each nullable field, which the
+/// ortho-`Vec` has when it has any.
+pub(crate) fn build_ortho_vec_nullable_impl(input: &OrthoStructInput) -> TokenStream {
+    let OrthoStructInput {
+        names,
+        where_clause,
+        ..
+    } = input;
+
+    let nullable_methods = columns(input)
+        .into_iter()
+        .filter_map(|column| {
+            let Column::Nullable { ident, value_ty, .. } = &column else {
+                return None;
+            };
+
+            let present = format_ident!("{}_present", ident);
+            let present_mut = format_ident!("{}_present_mut", ident);
+            let validity = format_ident!("{}_validity", ident);
+            let present_doc = format!(
+                "Returns an iterator over the indices and values of the elements whose `{ident}` \
+                is `Some`, skipping the rest a word of the validity bitmap at a time."
+            );
+            let present_mut_doc = format!(
+                "Returns an iterator over the indices and values of the elements whose `{ident}` \
+                is `Some`, with the values borrowed mutably."
+            );
+            let validity_doc = format!(
+                "Returns the validity bitmap of the `{ident}` column, with a bit set for every \
+                element whose `{ident}` is `Some`."
+            );
+
+            Some(quote! {
+                #[doc = #present_doc]
+                pub fn #present(&self) -> ::ortho_vec_derive::nullable::Present<'_, #value_ty> {
+                    self.#column.present()
+                }
+
+                #[doc = #present_mut_doc]
+                pub fn #present_mut(&mut self) -> ::ortho_vec_derive::nullable::PresentMut<'_, #value_ty> {
+                    self.#column.present_mut()
+                }
+
+                #[doc = #validity_doc]
+                pub fn #validity(&self) -> ::ortho_vec_derive::bits::Bits<'_> {
+                    self.#column.validity()
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if nullable_methods.is_empty() {
+        return quote! {};
+    }
+
+    let ortho_vec_name = &names.ortho_vec;
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (ortho_vec_impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    quote! {
+        impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause
+        {
+            #(#nullable_methods)*
+        }
+    }
+}
@@ -0,0 +1,667 @@
+//! The ortho-`Vec` of `#[ortho(layout = "single")]`, holding all of its columns in a single
+//! allocation.
+//!
+//! The columns are kept in a `RawColumns`, which owns the memory and the length, so the
+//! ortho-`Vec` only moves the elements in and out of the columns, and code in the module of the
+//! struct can't change the length without `unsafe`.
+//! Every column is known by its index in [`columns`], along with the type of its elements, and
+//! the layouts of all of these types are given by implementing `ColumnLayouts` for the struct.
+//! The views and the iterators over them borrow the columns as slices, the same as they borrow
+//! separate columns.
+
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+use crate::{
+    attrs::ColumnsLayout, columns, combine_errors, fields_values_ts, transform_columns_into_ts,
+    Column, OrthoStructInput,
+};
+
+/// Returns an error for every flattened field of a struct with a single allocation.
+pub(crate) fn check_fields(input: &OrthoStructInput) -> syn::Result<()> {
+    if input.layout != ColumnsLayout::Single {
+        return Ok(());
+    }
+
+    combine_errors(input.field_attrs.iter().filter_map(|field_attrs| {
+        let flatten = field_attrs.flatten.as_ref()?;
+
+        Some(syn::Error::new_spanned(
+            flatten,
+            "flattened fields are stored in the columns of their own type, so they can't be in a single allocation",
+        ))
+    }))
+}
+
+/// Returns the index of the column in the allocation.
+fn column_index(input: &OrthoStructInput, column: &Column) -> Literal {
+    let index = columns(input)
+        .iter()
+        .position(|other| other.ident() == column.ident())
+        .expect("the column is one of the columns of the struct");
+
+    Literal::usize_unsuffixed(index)
+}
+
+/// Returns the elements of a column of `self` as a slice, borrowed mutably if `mutable`.
+pub(crate) fn column_slice_ts(
+    input: &OrthoStructInput,
+    column: &Column,
+    mutable: bool,
+) -> TokenStream {
+    let index = column_index(input, column);
+    let ty = column.ty_ts();
+
+    // SAFETY: The first `len` elements of every column are initialized, and the columns don't
+    // overlap, so each can be borrowed mutably on its own
+    if mutable {
+        quote!(unsafe {
+            ::core::slice::from_raw_parts_mut(
+                self.__columns.column::<#ty>(#index),
+                self.__columns.len(),
+            )
+        })
+    } else {
+        quote!(unsafe { self.__columns.slice::<#ty>(#index, self.__columns.len()) })
+    }
+}
+
+/// Returns the type `raw_ty` of the `raw` module, laid out for the columns of the struct, in the
+/// allocator if there is one.
+fn raw_columns_ty_ts(input: &OrthoStructInput, raw_ty: &str) -> TokenStream {
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let name = &input.name;
+    let raw_ty = Ident::new(raw_ty, proc_macro2::Span::call_site());
+    let columns_count = Literal::usize_unsuffixed(columns(input).len());
+
+    if let Some(allocator) = &input.allocator {
+        quote!(::ortho_vec_derive::raw::#raw_ty<#name #ty_generics, #columns_count, #allocator>)
+    } else {
+        quote!(::ortho_vec_derive::raw::#raw_ty<#name #ty_generics, #columns_count>)
+    }
+}
+
+/// Returns the same as [`transform_columns_into_ts`], with the index of each column given along
+/// with it.
+fn transform_indexed_columns_into_ts(
+    input: &OrthoStructInput,
+    transform_column_fn: &dyn Fn(&Column, &Literal) -> TokenStream,
+) -> TokenStream {
+    transform_columns_into_ts(input, &|column| {
+        transform_column_fn(column, &column_index(input, column))
+    })
+}
+
+pub(crate) fn build_ortho_vec_struct(input: &OrthoStructInput) -> (Ident, TokenStream) {
+    let OrthoStructInput {
+        name,
+        generics,
+        where_clause,
+        vis,
+        names,
+        ..
+    } = input;
+
+    let ortho_vec_name = &names.ortho_vec;
+    let ortho_vec_attrs = input.forwarded_attrs.ortho_vec_ts();
+    let columns_count = Literal::usize_unsuffixed(columns(input).len());
+
+    // Each column starts at a multiple of the alignment of its layout, an alignment given for all
+    // of the columns is added to each of them
+    let column_layouts = transform_columns_into_ts(input, &|column| {
+        let ty = column.ty_ts();
+        let layout = quote!(::core::alloc::Layout::new::<#ty>());
+
+        if let Some(align) = &input.align {
+            quote!(::ortho_vec_derive::raw::align_layout(#layout, #align),)
+        } else {
+            quote!(#layout,)
+        }
+    });
+
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (ortho_vec_impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+    let raw_columns_ty = raw_columns_ty_ts(input, "RawColumns");
+
+    let ortho_vec_doc = format!(
+        "An orthogonal `Vec` of [`{name}`], holding a column for each of its fields in a single allocation."
+    );
+
+    (
+        ortho_vec_name.clone(),
+        quote!(
+            #[doc = #ortho_vec_doc]
+            #ortho_vec_attrs
+            #vis struct #ortho_vec_name #ortho_vec_generics
+            #where_clause
+            {
+                __columns: #raw_columns_ty,
+                __marker: ::core::marker::PhantomData<#name #ty_generics>,
+            }
+
+            impl #impl_generics ::ortho_vec_derive::raw::ColumnLayouts<#columns_count> for #name #ty_generics
+            #where_clause
+            {
+                const LAYOUTS: [::core::alloc::Layout; #columns_count] = [#column_layouts];
+            }
+
+            impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+            #where_clause
+            {
+                /// Returns the number of elements.
+                pub fn len(&self) -> usize {
+                    self.__columns.len()
+                }
+
+                /// Returns the number of elements every column has room for, without
+                /// reallocating.
+                pub fn capacity(&self) -> usize {
+                    self.__columns.capacity()
+                }
+            }
+
+            impl #impl_generics ::core::convert::From<::ortho_vec_derive::alloc::vec::Vec<#name #ty_generics>> for #ortho_vec_name #ty_generics
+            #where_clause
+            {
+                fn from(value: ::ortho_vec_derive::alloc::vec::Vec<#name #ty_generics>) -> Self {
+                    let mut v = Self::with_capacity(value.len());
+
+                    for p in value {
+                        v.push(p);
+                    }
+
+                    v
+                }
+            }
+
+            impl #ortho_vec_impl_generics ::core::ops::Drop for #ortho_vec_name #ortho_vec_ty_generics
+            #where_clause
+            {
+                fn drop(&mut self) {
+                    self.clear();
+                }
+            }
+        ),
+    )
+}
+
+fn impl_vec_push(input: &OrthoStructInput, ortho_vec_name: &Ident) -> TokenStream {
+    let OrthoStructInput {
+        name: struct_name,
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    let write_value_into_columns = transform_indexed_columns_into_ts(input, &|column, index| {
+        let element = column.element_ts(&quote!(value));
+        let ty = column.ty_ts();
+
+        quote! {
+            self.__columns.write::<#ty>(#index, len, #element);
+        }
+    });
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    // SAFETY: The first `len` elements of every column are initialized, after reserving every
+    // column has room for one more, and one more is initialized before the length is set
+    quote! {
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            /// Appends an element to the back, growing all of the columns together if they are
+            /// full.
+            pub fn push(&mut self, value: #struct_name #ty_generics) {
+                let len = self.__columns.len();
+                self.__columns.reserve(1);
+
+                unsafe {
+                    #write_value_into_columns
+                    self.__columns.set_len(len + 1);
+                }
+            }
+        }
+    }
+}
+
+fn impl_vec_pop(input: &OrthoStructInput, ortho_vec_name: &Ident) -> TokenStream {
+    let OrthoStructInput {
+        name: struct_name,
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    // SAFETY: The element at the new length is initialized, and is left out of it before it is read
+    let (bind_popped_groups, read_columns_assign_member) = fields_values_ts(
+        input,
+        &|column| {
+            let index = column_index(input, column);
+            let ty = column.ty_ts();
+            quote!(unsafe { self.__columns.read::<#ty>(#index, len - 1) })
+        },
+        &quote!(),
+    );
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            /// Removes the last element and returns it, or `None` if there are no elements.
+            pub fn pop(&mut self) -> ::core::option::Option<#struct_name #ty_generics> {
+                let len = self.__columns.len();
+                if len == 0 {
+                    return ::core::option::Option::None;
+                }
+
+                unsafe { self.__columns.set_len(len - 1) };
+                #bind_popped_groups
+                ::core::option::Option::Some(#struct_name {
+                    #read_columns_assign_member
+                })
+            }
+        }
+    }
+}
+
+fn impl_vec_clear(input: &OrthoStructInput, ortho_vec_name: &Ident) -> TokenStream {
+    let OrthoStructInput { where_clause, .. } = input;
+
+    let drop_columns = transform_indexed_columns_into_ts(input, &|column, index| {
+        let ty = column.ty_ts();
+
+        quote! {
+            ::core::ptr::drop_in_place(self.__columns.slice_mut::<#ty>(#index, len));
+        }
+    });
+
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    // SAFETY: The first `len` elements of every column are initialized, and they are left out of
+    // the length before they are dropped, so a panicking drop leaks the rest instead of dropping
+    // them twice
+    quote! {
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            /// Removes all elements, keeping the capacity.
+            pub fn clear(&mut self) {
+                let len = self.__columns.len();
+
+                unsafe {
+                    self.__columns.set_len(0);
+                    #drop_columns
+                }
+            }
+        }
+    }
+}
+
+fn impl_vec_reverse(input: &OrthoStructInput, ortho_vec_name: &Ident) -> TokenStream {
+    let OrthoStructInput { where_clause, .. } = input;
+
+    let reverse_columns = transform_indexed_columns_into_ts(input, &|column, index| {
+        let ty = column.ty_ts();
+
+        quote! {
+            self.__columns.slice_mut::<#ty>(#index, len).reverse();
+        }
+    });
+
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    // SAFETY: The first `len` elements of every column are initialized
+    quote! {
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            /// Reverses the order of the elements.
+            pub fn reverse(&mut self) {
+                let len = self.__columns.len();
+
+                unsafe {
+                    #reverse_columns
+                }
+            }
+        }
+    }
+}
+
+fn impl_vec_insert(input: &OrthoStructInput, ortho_vec_name: &Ident) -> TokenStream {
+    let OrthoStructInput {
+        name: struct_name,
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    let insert_element_into_columns = transform_indexed_columns_into_ts(input, &|column, index| {
+        let element = column.element_ts(&quote!(element));
+        let ty = column.ty_ts();
+
+        quote! {
+            self.__columns.insert::<#ty>(#index, len, index, #element);
+        }
+    });
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    // SAFETY: The first `len` elements of every column are initialized, `index` is at most `len`,
+    // after reserving every column has room for one more, and one more is initialized before the
+    // length is set
+    quote! {
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            /// Inserts an element at `index`, panics if `index` is greater than the length.
+            pub fn insert(&mut self, index: usize, element: #struct_name #ty_generics) {
+                let len = self.__columns.len();
+                ::core::assert!(index <= len, "insertion index (is {index}) should be <= len (is {len})");
+                self.__columns.reserve(1);
+
+                unsafe {
+                    #insert_element_into_columns
+                    self.__columns.set_len(len + 1);
+                }
+            }
+        }
+    }
+}
+
+fn impl_vec_method_mut_self_index_ret_struct(
+    input: &OrthoStructInput,
+    method_name: &str,
+    ortho_vec_name: &Ident,
+) -> TokenStream {
+    let OrthoStructInput {
+        name: struct_name,
+        generics,
+        where_clause,
+        ..
+    } = input;
+
+    let doc =
+        format!("Same as `Vec::{method_name}`, removes the element at `index` and returns it.");
+    let method_name = Ident::new(method_name, proc_macro2::Span::call_site());
+
+    // SAFETY: The first `len` elements of every column are initialized and `index` is less than
+    // `len`, the element moved into the place of the removed one is left out of the new length
+    let (bind_removed_groups, remove_from_columns_assign_member) = fields_values_ts(
+        input,
+        &|column| {
+            let index = column_index(input, column);
+            let ty = column.ty_ts();
+            quote!(unsafe { self.__columns.#method_name::<#ty>(#index, len, index) })
+        },
+        &quote!(),
+    );
+    let index_out_of_bounds_message =
+        format!("{method_name} index (is {{index}}) should be < len (is {{len}})");
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            #[doc = #doc]
+            pub fn #method_name(&mut self, index: usize) -> #struct_name #ty_generics {
+                let len = self.__columns.len();
+                ::core::assert!(index < len, #index_out_of_bounds_message);
+
+                unsafe { self.__columns.set_len(len - 1) };
+                #bind_removed_groups
+                #struct_name {
+                    #remove_from_columns_assign_member
+                }
+            }
+        }
+    }
+}
+
+fn impl_vec_new(input: &OrthoStructInput, ortho_vec_name: &Ident) -> TokenStream {
+    let OrthoStructInput {
+        generics,
+        where_clause,
+        allocator,
+        ..
+    } = input;
+
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (ortho_vec_impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    // With an allocator the constructors are implemented for any allocator, and the ones without
+    // it construct the ortho-`Vec` in the global allocator
+    let (constructors_impl_generics, constructors_ty_generics, new, with_capacity, global_ctors) =
+        if let Some(allocator) = allocator {
+            (
+                &ortho_vec_impl_generics,
+                &ortho_vec_ty_generics,
+                quote!(new_in(alloc: #allocator)),
+                quote!(with_capacity_in(capacity: usize, alloc: #allocator)),
+                Some(quote! {
+                    impl #impl_generics #ortho_vec_name #ty_generics
+                    #where_clause {
+                        /// Constructs a new, empty orthogonal `Vec` in the global allocator,
+                        /// without allocating.
+                        pub fn new() -> Self {
+                            Self::new_in(::ortho_vec_derive::allocator_api2::alloc::Global)
+                        }
+
+                        /// Constructs a new, empty orthogonal `Vec` in the global allocator,
+                        /// with room for at least `capacity` elements in every column, with a
+                        /// single allocation.
+                        pub fn with_capacity(capacity: usize) -> Self {
+                            Self::with_capacity_in(
+                                capacity,
+                                ::ortho_vec_derive::allocator_api2::alloc::Global,
+                            )
+                        }
+                    }
+                }),
+            )
+        } else {
+            (
+                &impl_generics,
+                &ty_generics,
+                quote!(new()),
+                quote!(with_capacity(capacity: usize)),
+                None,
+            )
+        };
+    let (new_columns, new_call) = if allocator.is_some() {
+        (
+            quote!(::ortho_vec_derive::raw::RawColumns::new_in(alloc)),
+            quote!(Self::new_in(alloc)),
+        )
+    } else {
+        (
+            quote!(::ortho_vec_derive::raw::RawColumns::new()),
+            quote!(Self::new()),
+        )
+    };
+    let in_alloc = if allocator.is_some() {
+        " in `alloc`"
+    } else {
+        ""
+    };
+    let new_doc =
+        format!("Constructs a new, empty orthogonal `Vec`{in_alloc}, without allocating.");
+    let with_capacity_doc = format!(
+        "Constructs a new, empty orthogonal `Vec`{in_alloc} with room for at least `capacity` elements in every column, with a single allocation."
+    );
+
+    quote! {
+        #global_ctors
+
+        impl #constructors_impl_generics #ortho_vec_name #constructors_ty_generics
+        #where_clause {
+            #[doc = #new_doc]
+            pub fn #new -> Self {
+                #ortho_vec_name {
+                    __columns: #new_columns,
+                    __marker: ::core::marker::PhantomData,
+                }
+            }
+
+            #[doc = #with_capacity_doc]
+            pub fn #with_capacity -> Self {
+                let mut ortho_vec = #new_call;
+                ortho_vec.__columns.reserve(capacity);
+
+                ortho_vec
+            }
+        }
+
+        impl #ortho_vec_impl_generics #ortho_vec_name #ortho_vec_ty_generics
+        #where_clause {
+            /// Shrinks all of the columns to fit the elements, with a single reallocation.
+            pub fn shrink_to_fit(&mut self) {
+                self.__columns.shrink_to_fit();
+            }
+        }
+    }
+}
+
+pub(crate) fn build_ortho_vec_impl_vec_methods(
+    input: &OrthoStructInput,
+    ortho_vec_name: &Ident,
+) -> TokenStream {
+    let push = impl_vec_push(input, ortho_vec_name);
+
+    let pop = impl_vec_pop(input, ortho_vec_name);
+
+    let clear = impl_vec_clear(input, ortho_vec_name);
+
+    let reverse = impl_vec_reverse(input, ortho_vec_name);
+
+    let insert = impl_vec_insert(input, ortho_vec_name);
+
+    let mut_self_index_ret_struct_methods = ["remove", "swap_remove"].iter().map(|method_name| {
+        impl_vec_method_mut_self_index_ret_struct(input, method_name, ortho_vec_name)
+    });
+
+    let new = impl_vec_new(input, ortho_vec_name);
+
+    quote! {
+        #push
+        #pop
+        #clear
+        #reverse
+        #insert
+        #(#mut_self_index_ret_struct_methods)*
+        #new
+    }
+}
+
+pub(crate) fn build_ortho_vec_into_iter_struct(
+    input: &OrthoStructInput,
+    ortho_vec_name: &Ident,
+) -> (Ident, TokenStream) {
+    let OrthoStructInput {
+        name,
+        generics,
+        where_clause,
+        vis,
+        names,
+        ..
+    } = input;
+
+    let ortho_vec_into_iter_name = &names.into_iter;
+    let raw_into_iter_ty = raw_columns_ty_ts(input, "RawIntoIter");
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let ortho_vec_generics = input.ortho_vec_generics();
+    let (impl_generics, ortho_vec_ty_generics, _) = ortho_vec_generics.split_for_impl();
+
+    // SAFETY: The elements that weren't moved past are initialized, and every element is read once,
+    // right after moving past it
+    let (bind_read_groups, read_columns_assign_member) = fields_values_ts(
+        input,
+        &|column| {
+            let index = column_index(input, column);
+            let ty = column.ty_ts();
+            quote!(unsafe { self.__columns.read::<#ty>(#index, index) })
+        },
+        &quote!(),
+    );
+
+    let drop_rest_of_columns = transform_indexed_columns_into_ts(input, &|column, index| {
+        let ty = column.ty_ts();
+
+        quote! {
+            ::core::ptr::drop_in_place(&mut self.__columns.slice_mut::<#ty>(#index, rest.end)[rest.start..]);
+        }
+    });
+
+    let doc = format!("An iterator moving the elements out of [`{ortho_vec_name}`].");
+
+    // SAFETY: The ortho-`Vec` is never dropped, so its columns are only dropped by the iterator
+    (
+        ortho_vec_into_iter_name.clone(),
+        quote!(
+            #[doc = #doc]
+            #vis struct #ortho_vec_into_iter_name #ortho_vec_generics
+            #where_clause
+            {
+                __columns: #raw_into_iter_ty,
+                __marker: ::core::marker::PhantomData<#name #ty_generics>,
+            }
+
+            impl #impl_generics ::core::iter::Iterator for #ortho_vec_into_iter_name #ortho_vec_ty_generics
+            #where_clause
+            {
+                type Item = #name #ty_generics;
+
+                #[inline]
+                fn next(&mut self) -> ::core::option::Option<Self::Item> {
+                    let index = self.__columns.next_index()?;
+                    #bind_read_groups
+                    ::core::option::Option::Some(#name {
+                        #read_columns_assign_member
+                    })
+                }
+            }
+
+            impl #impl_generics ::core::ops::Drop for #ortho_vec_into_iter_name #ortho_vec_ty_generics
+            #where_clause
+            {
+                fn drop(&mut self) {
+                    let rest = self.__columns.skip_rest();
+
+                    unsafe {
+                        #drop_rest_of_columns
+                    }
+                }
+            }
+
+            impl #impl_generics ::core::iter::IntoIterator for #ortho_vec_name #ortho_vec_ty_generics
+            #where_clause
+            {
+                type Item = #name #ty_generics;
+                type IntoIter = #ortho_vec_into_iter_name #ortho_vec_ty_generics;
+
+                fn into_iter(self) -> #ortho_vec_into_iter_name #ortho_vec_ty_generics {
+                    let ortho_vec = ::core::mem::ManuallyDrop::new(self);
+
+                    #ortho_vec_into_iter_name {
+                        __columns: ::ortho_vec_derive::raw::RawIntoIter::new(unsafe {
+                            ::core::ptr::read(&ortho_vec.__columns)
+                        }),
+                        __marker: ::core::marker::PhantomData,
+                    }
+                }
+            }
+        ),
+    )
+}